@@ -6,6 +6,7 @@ use crate::fyrox::{
     asset::{manager::ResourceManager, Resource},
     core::{
         futures::executor::block_on,
+        math::curve::Curve,
         parking_lot::Mutex,
         pool::{ErasedHandle, Handle},
     },
@@ -55,10 +56,18 @@ use crate::fyrox::{
         node::Node,
         particle_system::{
             emitter::{
-                base::BaseEmitter, cuboid::CuboidEmitter, cylinder::CylinderEmitter,
-                sphere::SphereEmitter, Emitter,
+                base::{BaseEmitter, EmissionMode, EmissionVolume, ParticleCustomData},
+                cuboid::CuboidEmitter,
+                cylinder::{CylinderAxis, CylinderEmitter},
+                sphere::SphereEmitter,
+                torus::TorusEmitter,
+                Emitter,
             },
-            ParticleSystemRng,
+            turbulence::ParticleTurbulence,
+            ParticleAttractor, ParticleAttractorFalloff, ParticleCollisionPlane,
+            ParticleCollisionResponse, ParticleConfinement, ParticleConfinementResponse,
+            ParticleOrientationMode, ParticleSortMode, ParticleSpriteSheet, ParticleSystemRng,
+            SimulationSpace, SpriteSheetPlayback,
         },
         ragdoll::Limb,
         rigidbody::RigidBodyType,
@@ -245,6 +254,7 @@ pub fn make_property_editors_container(sender: MessageSender) -> PropertyEditorD
     container.insert(make_status_enum_editor_definition());
 
     container.insert(EnumPropertyEditorDefinition::<LodGroup>::new_optional());
+    container.insert(EnumPropertyEditorDefinition::<Curve>::new_optional());
     container.insert(InheritablePropertyEditorDefinition::<Option<LodGroup>>::new());
 
     {
@@ -360,7 +370,26 @@ pub fn make_property_editors_container(sender: MessageSender) -> PropertyEditorD
     container.register_inheritable_inspectable::<BaseEmitter>();
     container.register_inheritable_inspectable::<SphereEmitter>();
     container.register_inheritable_inspectable::<CylinderEmitter>();
+    container.register_inheritable_inspectable::<TorusEmitter>();
     container.register_inheritable_inspectable::<CuboidEmitter>();
+    container.insert(EnumPropertyEditorDefinition::<ParticleCollisionPlane>::new_optional());
+    container.insert(InheritablePropertyEditorDefinition::<
+        Option<ParticleCollisionPlane>,
+    >::new());
+    container.register_inheritable_inspectable::<ParticleCollisionPlane>();
+    container.insert(EnumPropertyEditorDefinition::<ParticleConfinement>::new_optional());
+    container.insert(InheritablePropertyEditorDefinition::<
+        Option<ParticleConfinement>,
+    >::new());
+    container.register_inheritable_inspectable::<ParticleConfinement>();
+    container.insert(EnumPropertyEditorDefinition::<ParticleSpriteSheet>::new_optional());
+    container.insert(InheritablePropertyEditorDefinition::<
+        Option<ParticleSpriteSheet>,
+    >::new());
+    container.register_inheritable_inspectable::<ParticleSpriteSheet>();
+    container.register_inheritable_inspectable::<ParticleAttractor>();
+    container.register_inheritable_vec_collection::<ParticleAttractor>();
+    container.register_inheritable_inspectable::<ParticleTurbulence>();
     container.register_inheritable_inspectable::<PerspectiveProjection>();
     container.register_inheritable_inspectable::<OrthographicProjection>();
     container.register_inheritable_inspectable::<Transform>();
@@ -395,6 +424,17 @@ pub fn make_property_editors_container(sender: MessageSender) -> PropertyEditorD
     container.register_inheritable_enum::<DistanceModel, _>();
     container.register_inheritable_enum::<sound::Renderer, _>();
     container.register_inheritable_enum::<RenderPath, _>();
+    container.register_inheritable_enum::<EmissionVolume, _>();
+    container.register_inheritable_enum::<EmissionMode, _>();
+    container.register_inheritable_enum::<ParticleCustomData, _>();
+    container.register_inheritable_enum::<SimulationSpace, _>();
+    container.register_inheritable_enum::<ParticleOrientationMode, _>();
+    container.register_inheritable_enum::<ParticleCollisionResponse, _>();
+    container.register_inheritable_enum::<ParticleConfinementResponse, _>();
+    container.register_inheritable_enum::<ParticleSortMode, _>();
+    container.register_inheritable_enum::<ParticleAttractorFalloff, _>();
+    container.register_inheritable_enum::<SpriteSheetPlayback, _>();
+    container.register_inheritable_enum::<CylinderAxis, _>();
 
     container.insert(EnumPropertyEditorDefinition::<Vec<ScriptRecord>>::new_optional());
     container.insert(VecCollectionPropertyEditorDefinition::<ScriptRecord>::new());
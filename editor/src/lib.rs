@@ -2096,7 +2096,8 @@ impl Editor {
     fn open_material_editor(&mut self, material: MaterialResource) {
         let engine = &mut self.engine;
 
-        self.material_editor.set_material(Some(material), engine);
+        self.material_editor
+            .set_material(Some(material), engine, &self.message_sender);
 
         engine
             .user_interfaces
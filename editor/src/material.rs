@@ -3,12 +3,14 @@ use crate::fyrox::{
     core::{
         algebra::{Matrix4, Vector2, Vector3, Vector4},
         futures::executor::block_on,
+        log::Log,
         make_relative_path,
         parking_lot::Mutex,
         pool::Handle,
         sstorage::ImmutableString,
         BiDirHashMap,
     },
+    fxhash::FxHashMap,
     graph::BaseSceneGraph,
     gui::{
         border::BorderBuilder,
@@ -47,7 +49,9 @@ use crate::{
     inspector::editors::resource::{ResourceFieldBuilder, ResourceFieldMessage},
     message::MessageSender,
     preview::PreviewPanel,
-    scene::commands::material::{SetMaterialPropertyValueCommand, SetMaterialShaderCommand},
+    scene::commands::material::{
+        SetMaterialPropertyOrderCommand, SetMaterialPropertyValueCommand, SetMaterialShaderCommand,
+    },
     send_sync_message, Engine, Message,
 };
 use fyrox::gui::menu::ContextMenuBuilder;
@@ -96,26 +100,77 @@ impl TextureContextMenu {
     }
 }
 
+struct PropertyContextMenu {
+    popup: RcUiNodeHandle,
+    move_up: Handle<UiNode>,
+    move_down: Handle<UiNode>,
+    target: Handle<UiNode>,
+}
+
+impl PropertyContextMenu {
+    fn new(ctx: &mut BuildContext) -> Self {
+        let move_up;
+        let move_down;
+        let popup = ContextMenuBuilder::new(
+            PopupBuilder::new(WidgetBuilder::new().with_visibility(false)).with_content(
+                StackPanelBuilder::new(
+                    WidgetBuilder::new()
+                        .with_child({
+                            move_up = MenuItemBuilder::new(WidgetBuilder::new())
+                                .with_content(MenuItemContent::text("Move Up"))
+                                .build(ctx);
+                            move_up
+                        })
+                        .with_child({
+                            move_down = MenuItemBuilder::new(WidgetBuilder::new())
+                                .with_content(MenuItemContent::text("Move Down"))
+                                .build(ctx);
+                            move_down
+                        }),
+                )
+                .build(ctx),
+            ),
+        )
+        .build(ctx);
+        let popup = RcUiNodeHandle::new(popup, ctx.sender());
+
+        Self {
+            popup,
+            move_up,
+            move_down,
+            target: Default::default(),
+        }
+    }
+}
+
 pub struct MaterialEditor {
     pub window: Handle<UiNode>,
     properties_panel: Handle<UiNode>,
     properties: BiDirHashMap<ImmutableString, Handle<UiNode>>,
+    // Order of the property views in the properties panel.
+    property_order: Vec<ImmutableString>,
+    // Values of the properties before their transient changes, see `set_transient_property_value`.
+    transient_values: FxHashMap<ImmutableString, PropertyValue>,
     preview: PreviewPanel,
     material: Option<MaterialResource>,
     shader: Handle<UiNode>,
     texture_context_menu: TextureContextMenu,
+    property_context_menu: PropertyContextMenu,
 }
 
 fn create_item_container(
     ctx: &mut BuildContext,
-    name: &str,
+    name: &ImmutableString,
     item: Handle<UiNode>,
+    context_menu: RcUiNodeHandle,
 ) -> Handle<UiNode> {
     ctx[item].set_column(1);
 
     GridBuilder::new(
         WidgetBuilder::new()
             .with_margin(Thickness::uniform(1.0))
+            .with_user_data(Arc::new(Mutex::new(name.clone())))
+            .with_context_menu(context_menu)
             .with_child(
                 TextBuilder::new(WidgetBuilder::new())
                     .with_text(name)
@@ -330,16 +385,30 @@ impl MaterialEditor {
 
         Self {
             texture_context_menu: TextureContextMenu::new(ctx),
+            property_context_menu: PropertyContextMenu::new(ctx),
             window,
             preview,
             properties_panel,
             properties: Default::default(),
+            property_order: Default::default(),
+            transient_values: Default::default(),
             material: None,
             shader,
         }
     }
 
-    pub fn set_material(&mut self, material: Option<MaterialResource>, engine: &mut Engine) {
+    pub fn set_material(
+        &mut self,
+        material: Option<MaterialResource>,
+        engine: &mut Engine,
+        sender: &MessageSender,
+    ) {
+        // Transient changes belong to the previous material, so they must be committed to it
+        // before the new material is set.
+        if let Some(previous_material) = self.material.clone() {
+            self.commit_transient_property_values(&previous_material, sender);
+        }
+
         self.material = material;
 
         if let Some(material) = self.material.clone() {
@@ -385,12 +454,25 @@ impl MaterialEditor {
                 }
             }
 
-            let mut sorted_properties = material
-                .properties()
+            // Re-create all the views if the order of the properties has changed, views are
+            // appended to the panel so there is no other way to put them in the right order.
+            let property_order = material.ordered_property_names();
+            if property_order != self.property_order {
+                for item in self.properties.forward_map().values() {
+                    let item_to_delete = ui.node(*item).parent();
+                    send_sync_message(
+                        ui,
+                        WidgetMessage::remove(item_to_delete, MessageDirection::ToWidget),
+                    );
+                }
+                self.properties.clear();
+                self.property_order = property_order.clone();
+            }
+
+            let sorted_properties = property_order
                 .iter()
-                .map(|(k, v)| (k.clone(), v.clone()))
+                .filter_map(|name| Some((name.clone(), material.property_ref(name)?.clone())))
                 .collect::<Vec<_>>();
-            sorted_properties.sort_by(|(name_a, _), (name_b, _)| name_a.cmp(name_b));
 
             // Add missing properties.
             for (name, property_value) in sorted_properties.iter() {
@@ -464,7 +546,12 @@ impl MaterialEditor {
 
                     self.properties.insert(name.to_owned(), item);
 
-                    let container = create_item_container(ctx, name, item);
+                    let container = create_item_container(
+                        ctx,
+                        name,
+                        item,
+                        self.property_context_menu.popup.clone(),
+                    );
 
                     send_sync_message(
                         ui,
@@ -609,6 +696,60 @@ impl MaterialEditor {
         }
     }
 
+    /// Applies the value to the material without creating a command. It is used to preview
+    /// intermediate values, while the user changes a value interactively (for example, drags a
+    /// slider of a color picker), so the intermediate values do not flood the command stack and do
+    /// not cause a save of the material on each change. The value, that the property had before
+    /// the first transient change, is remembered and used as undo baseline when the changes are
+    /// committed by [`Self::commit_transient_property_values`].
+    fn set_transient_property_value(
+        &mut self,
+        material: &MaterialResource,
+        name: ImmutableString,
+        value: PropertyValue,
+    ) {
+        let mut material = material.data_ref();
+        let value = match value.checked_for_shader(material.shader(), &name) {
+            Ok(value) => value,
+            Err(err) => {
+                Log::err(err.to_string());
+                return;
+            }
+        };
+        let Some(current_value) = material.property_ref(&name).cloned() else {
+            return;
+        };
+        Log::verify(material.set_property(&name, value));
+        self.transient_values.entry(name).or_insert(current_value);
+    }
+
+    /// Creates a command for every transient change of the material (see
+    /// [`Self::set_transient_property_value`]). The commands take the values before the transient
+    /// changes as the previous values, so a single undo reverts the entire interactive change.
+    fn commit_transient_property_values(
+        &mut self,
+        material: &MaterialResource,
+        sender: &MessageSender,
+    ) {
+        for (name, baseline) in self.transient_values.drain() {
+            let mut material_ref = material.data_ref();
+            let Some(value) = material_ref.property_ref(&name).cloned() else {
+                continue;
+            };
+            // Restore the baseline, so the command will see it as the previous value.
+            Log::verify(material_ref.set_property(&name, baseline.clone()));
+            drop(material_ref);
+
+            if value != baseline {
+                sender.do_command(SetMaterialPropertyValueCommand::new(
+                    material.clone(),
+                    name,
+                    value,
+                ));
+            }
+        }
+    }
+
     pub fn handle_ui_message(
         &mut self,
         message: &UiMessage,
@@ -618,6 +759,15 @@ impl MaterialEditor {
         self.preview.handle_message(message, engine);
 
         if let Some(material) = self.material.clone() {
+            if let Some(WidgetMessage::MouseUp { .. }) = message.data() {
+                // Interactive changes end when the mouse is released.
+                if !self.transient_values.is_empty()
+                    && engine.user_interfaces.first().captured_node().is_none()
+                {
+                    self.commit_transient_property_values(&material, sender);
+                }
+            }
+
             if let Some(msg) = message.data::<ResourceFieldMessage<Shader>>() {
                 if message.destination() == self.shader
                     && message.direction() == MessageDirection::FromWidget
@@ -634,6 +784,8 @@ impl MaterialEditor {
             {
                 if message.destination() == self.texture_context_menu.popup.handle() {
                     self.texture_context_menu.target = *target;
+                } else if message.destination() == self.property_context_menu.popup.handle() {
+                    self.property_context_menu.target = *target;
                 }
             } else if let Some(MenuItemMessage::Click) = message.data::<MenuItemMessage>() {
                 if message.destination() == self.texture_context_menu.show_in_asset_browser
@@ -670,10 +822,40 @@ impl MaterialEditor {
                             },
                         ));
                     }
+                } else if (message.destination() == self.property_context_menu.move_up
+                    || message.destination() == self.property_context_menu.move_down)
+                    && self.property_context_menu.target.is_some()
+                {
+                    let property_name = engine
+                        .user_interfaces
+                        .first_mut()
+                        .node(self.property_context_menu.target)
+                        .user_data_cloned::<ImmutableString>();
+                    let order = material
+                        .state()
+                        .data()
+                        .map(|material| material.ordered_property_names());
+                    if let (Some(property_name), Some(mut order)) = (property_name, order) {
+                        if let Some(index) = order.iter().position(|name| *name == property_name) {
+                            let new_index =
+                                if message.destination() == self.property_context_menu.move_up {
+                                    index.checked_sub(1)
+                                } else {
+                                    Some(index + 1).filter(|i| *i < order.len())
+                                };
+                            if let Some(new_index) = new_index {
+                                order.swap(index, new_index);
+                                sender.do_command(SetMaterialPropertyOrderCommand::new(
+                                    material.clone(),
+                                    order,
+                                ));
+                            }
+                        }
+                    }
                 }
             }
 
-            if let Some(property_name) = self.properties.key_of(&message.destination()) {
+            if let Some(property_name) = self.properties.key_of(&message.destination()).cloned() {
                 let property_value = if let Some(NumericUpDownMessage::Value(value)) =
                     message.data::<NumericUpDownMessage<f32>>()
                 {
@@ -681,7 +863,7 @@ impl MaterialEditor {
                         // NumericUpDown is used for Float, Int, UInt properties, so we have to check
                         // the actual property "type" to create suitable value from f32.
                         if let Some(material) = material.state().data() {
-                            match material.property_ref(property_name).unwrap() {
+                            match material.property_ref(&property_name).unwrap() {
                                 PropertyValue::Float(_) => Some(PropertyValue::Float(*value)),
                                 PropertyValue::Int(_) => Some(PropertyValue::Int(*value as i32)),
                                 PropertyValue::UInt(_) => Some(PropertyValue::UInt(*value as u32)),
@@ -760,11 +942,22 @@ impl MaterialEditor {
                 };
 
                 if let Some(property_value) = property_value {
-                    sender.do_command(SetMaterialPropertyValueCommand::new(
-                        material,
-                        property_name.clone(),
-                        property_value,
-                    ));
+                    let is_interactive = engine.user_interfaces.first().captured_node().is_some()
+                        && !matches!(property_value, PropertyValue::Sampler { .. });
+                    if is_interactive {
+                        self.set_transient_property_value(&material, property_name, property_value);
+                    } else {
+                        self.commit_transient_property_values(&material, sender);
+
+                        match SetMaterialPropertyValueCommand::try_new(
+                            material,
+                            property_name,
+                            property_value,
+                        ) {
+                            Ok(command) => sender.do_command(command),
+                            Err(err) => Log::err(err.to_string()),
+                        }
+                    }
                 }
             }
         }
@@ -0,0 +1,121 @@
+use crate::command::CommandContext;
+use crate::fyrox::{
+    core::{log::Log, pool::Handle},
+    graph::SceneGraph,
+    scene::{
+        dim2::collider::{Collider, ColliderShape},
+        node::Node,
+        Scene,
+    },
+};
+use crate::{command::CommandTrait, scene::commands::GameSceneContext};
+
+/// Returns a mutable reference to the shape of a 2D collider, or [`None`] if the handle does not
+/// point to a 2D collider.
+pub fn try_get_collider_shape_mut_2d(
+    collider: Handle<Node>,
+    scene: &mut Scene,
+) -> Option<&mut ColliderShape> {
+    scene
+        .graph
+        .try_get_mut_of_type::<Collider>(collider)
+        .map(|collider| collider.shape_mut())
+}
+
+/// Flips orientation of a 2D collider shape (see [`ColliderShape::flip_winding`]). Flipping is its
+/// own inverse, so the same operation is used to revert the command.
+#[derive(Debug)]
+pub struct FlipCollider2DWindingCommand {
+    collider: Handle<Node>,
+}
+
+impl FlipCollider2DWindingCommand {
+    pub fn new(collider: Handle<Node>) -> Self {
+        Self { collider }
+    }
+
+    fn flip(&self, context: &mut dyn CommandContext) {
+        let context = context.get_mut::<GameSceneContext>();
+        match try_get_collider_shape_mut_2d(self.collider, context.scene) {
+            Some(shape) => {
+                if !shape.flip_winding() {
+                    Log::warn(format!(
+                        "Unable to flip winding of {} collider shape, it has no orientation!",
+                        shape.as_ref()
+                    ));
+                }
+            }
+            None => Log::err(format!(
+                "Unable to flip winding, {} is not a 2D collider!",
+                self.collider
+            )),
+        }
+    }
+}
+
+impl CommandTrait for FlipCollider2DWindingCommand {
+    fn name(&mut self, _context: &dyn CommandContext) -> String {
+        "Flip Collider Winding".to_owned()
+    }
+
+    fn execute(&mut self, context: &mut dyn CommandContext) {
+        self.flip(context);
+    }
+
+    fn revert(&mut self, context: &mut dyn CommandContext) {
+        self.flip(context);
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{
+        command::CommandTrait,
+        fyrox::{
+            asset::manager::ResourceManager,
+            core::algebra::Vector2,
+            scene::{
+                base::BaseBuilder,
+                dim2::collider::{ColliderBuilder, ColliderShape},
+                Scene,
+            },
+        },
+        scene::commands::{
+            collider::{try_get_collider_shape_mut_2d, FlipCollider2DWindingCommand},
+            GameSceneContext,
+        },
+    };
+    use std::sync::Arc;
+
+    #[test]
+    fn test_flip_collider_2d_winding() {
+        let mut scene = Scene::new();
+        let triangle = ColliderShape::triangle(
+            Vector2::new(0.0, 0.0),
+            Vector2::new(1.0, 0.0),
+            Vector2::new(0.0, 1.0),
+        );
+        let collider = ColliderBuilder::new(BaseBuilder::new())
+            .with_shape(triangle.clone())
+            .build(&mut scene.graph);
+        let mut command = FlipCollider2DWindingCommand::new(collider);
+
+        let resource_manager = ResourceManager::new(Arc::new(Default::default()));
+        GameSceneContext::exec_with_scene(&mut scene, resource_manager, |context| {
+            command.execute(context);
+            assert_eq!(
+                try_get_collider_shape_mut_2d(collider, context.scene).cloned(),
+                Some(ColliderShape::triangle(
+                    Vector2::new(0.0, 0.0),
+                    Vector2::new(0.0, 1.0),
+                    Vector2::new(1.0, 0.0),
+                ))
+            );
+            command.revert(context);
+            assert_eq!(
+                try_get_collider_shape_mut_2d(collider, context.scene).cloned(),
+                Some(triangle)
+            );
+        });
+    }
+}
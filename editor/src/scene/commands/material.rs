@@ -1,23 +1,47 @@
 use crate::fyrox::{
-    asset::ResourceData,
-    core::{log::Log, sstorage::ImmutableString},
-    material::{shader::ShaderResource, Material, MaterialResource, PropertyValue},
+    asset::{manager::ResourceManager, untyped::ResourceKind, ResourceData},
+    core::{log::Log, pool::Handle, sstorage::ImmutableString},
+    graph::BaseSceneGraph,
+    material::{
+        shader::ShaderResource, Material, MaterialError, MaterialResource, PropertyValue,
+        ShaderMigrationPlan,
+    },
+    scene::{
+        dim2::rectangle::Rectangle, graph::Graph, mesh::Mesh, node::Node,
+        particle_system::ParticleSystem, sprite::Sprite,
+    },
 };
 use crate::{command::CommandTrait, scene::commands::GameSceneContext};
+use std::path::{Path, PathBuf};
 
 #[derive(Debug)]
 pub struct SetMaterialPropertyValueCommand {
     material: MaterialResource,
     name: ImmutableString,
     value: PropertyValue,
+    save_path: Option<PathBuf>,
 }
 use crate::command::CommandContext;
 
-fn try_save(material: &MaterialResource) {
-    let header = material.header();
-    if let Some(path) = header.kind.path_owned() {
-        drop(header);
-        Log::verify(material.data_ref().save(&path));
+/// Saves the material, but only if it is an external resource that was modified since the last
+/// save. This prevents rewriting unchanged materials on disk. A material without a path (for
+/// example, created at runtime) is saved to the given save path, if any, and becomes an external
+/// resource, otherwise its edits would never be persisted.
+fn try_save(material: &MaterialResource, save_path: Option<&Path>) {
+    let mut header = material.header();
+    let path = match (header.kind.path_owned(), save_path) {
+        (Some(path), _) => path,
+        (None, Some(save_path)) => {
+            header.kind.make_external(save_path.to_path_buf());
+            save_path.to_path_buf()
+        }
+        (None, None) => return,
+    };
+    drop(header);
+
+    let mut material = material.data_ref();
+    if material.is_modified() {
+        Log::verify(material.save(&path));
     }
 }
 
@@ -27,20 +51,44 @@ impl SetMaterialPropertyValueCommand {
             material,
             name,
             value,
+            save_path: None,
         }
     }
 
+    /// Sets a path to save the material to, if the material does not have a path yet. Materials
+    /// with a path are always saved to their own path.
+    pub fn with_save_path(mut self, path: PathBuf) -> Self {
+        self.save_path = Some(path);
+        self
+    }
+
+    /// Same as [`Self::new`], but checks that the value has the type that the shader of the
+    /// material expects for the property.
+    pub fn try_new(
+        material: MaterialResource,
+        name: ImmutableString,
+        value: PropertyValue,
+    ) -> Result<Self, MaterialError> {
+        let shader = material.data_ref().shader().clone();
+        let value = value.checked_for_shader(&shader, &name)?;
+        Ok(Self::new(material, name, value))
+    }
+
     fn swap(&mut self) {
         let mut material = self.material.data_ref();
 
         let old_value = material.property_ref(&self.name).unwrap().clone();
+        if old_value != self.value {
+            material.mark_modified();
+        }
 
         material
             .set_property(&self.name, std::mem::replace(&mut self.value, old_value))
             .unwrap();
+        material.record_property_value(&self.name);
 
         drop(material);
-        try_save(&self.material);
+        try_save(&self.material, self.save_path.as_deref());
     }
 }
 
@@ -58,6 +106,178 @@ impl CommandTrait for SetMaterialPropertyValueCommand {
     }
 }
 
+#[derive(Debug)]
+pub struct SetMaterialRenderQueueCommand {
+    material: MaterialResource,
+    render_queue: i32,
+    save_path: Option<PathBuf>,
+}
+
+impl SetMaterialRenderQueueCommand {
+    pub fn new(material: MaterialResource, render_queue: i32) -> Self {
+        Self {
+            material,
+            render_queue,
+            save_path: None,
+        }
+    }
+
+    /// Sets a path to save the material to, if the material does not have a path yet. Materials
+    /// with a path are always saved to their own path.
+    pub fn with_save_path(mut self, path: PathBuf) -> Self {
+        self.save_path = Some(path);
+        self
+    }
+
+    fn swap(&mut self) {
+        let mut material = self.material.data_ref();
+
+        let old_render_queue = material.set_render_queue(self.render_queue);
+        if old_render_queue != self.render_queue {
+            material.mark_modified();
+        }
+        self.render_queue = old_render_queue;
+
+        drop(material);
+        try_save(&self.material, self.save_path.as_deref());
+    }
+}
+
+impl CommandTrait for SetMaterialRenderQueueCommand {
+    fn name(&mut self, _: &dyn CommandContext) -> String {
+        "Set Material Render Queue".to_owned()
+    }
+
+    fn execute(&mut self, _: &mut dyn CommandContext) {
+        self.swap();
+    }
+
+    fn revert(&mut self, _: &mut dyn CommandContext) {
+        self.swap();
+    }
+}
+
+#[derive(Debug)]
+pub struct SetMaterialPropertyOrderCommand {
+    material: MaterialResource,
+    order: Vec<ImmutableString>,
+    save_path: Option<PathBuf>,
+}
+
+impl SetMaterialPropertyOrderCommand {
+    pub fn new(material: MaterialResource, order: Vec<ImmutableString>) -> Self {
+        Self {
+            material,
+            order,
+            save_path: None,
+        }
+    }
+
+    /// Sets a path to save the material to, if the material does not have a path yet. Materials
+    /// with a path are always saved to their own path.
+    pub fn with_save_path(mut self, path: PathBuf) -> Self {
+        self.save_path = Some(path);
+        self
+    }
+
+    fn swap(&mut self) {
+        let mut material = self.material.data_ref();
+
+        let old_order = material.set_property_order(std::mem::take(&mut self.order));
+        if old_order != material.property_order() {
+            material.mark_modified();
+        }
+        self.order = old_order;
+
+        drop(material);
+        try_save(&self.material, self.save_path.as_deref());
+    }
+}
+
+impl CommandTrait for SetMaterialPropertyOrderCommand {
+    fn name(&mut self, _: &dyn CommandContext) -> String {
+        "Reorder Material Properties".to_owned()
+    }
+
+    fn execute(&mut self, _: &mut dyn CommandContext) {
+        self.swap();
+    }
+
+    fn revert(&mut self, _: &mut dyn CommandContext) {
+        self.swap();
+    }
+}
+
+#[derive(Debug)]
+pub struct SetMaterialPropertyOnManyCommand {
+    materials: Vec<MaterialResource>,
+    name: ImmutableString,
+    // Each material has its own value, because the materials could have different values before
+    // the command is executed.
+    values: Vec<PropertyValue>,
+}
+
+impl SetMaterialPropertyOnManyCommand {
+    pub fn new(
+        materials: Vec<MaterialResource>,
+        name: ImmutableString,
+        value: PropertyValue,
+    ) -> Self {
+        Self {
+            values: vec![value; materials.len()],
+            materials,
+            name,
+        }
+    }
+
+    fn swap(&mut self) {
+        for (material_resource, value) in self.materials.iter().zip(self.values.iter_mut()) {
+            let mut material = material_resource.data_ref();
+
+            match material.property_ref(&self.name) {
+                Some(old_value) if old_value.is_same_type(value) => {
+                    let old_value = old_value.clone();
+                    if old_value != *value {
+                        material.mark_modified();
+                    }
+                    Log::verify(
+                        material.set_property(&self.name, std::mem::replace(value, old_value)),
+                    );
+                }
+                _ => {
+                    Log::err(format!(
+                        "Unable to set value of material property {}: there's no such \
+                        property or it has different type.",
+                        self.name
+                    ));
+                    continue;
+                }
+            }
+
+            drop(material);
+            try_save(material_resource, None);
+        }
+    }
+}
+
+impl CommandTrait for SetMaterialPropertyOnManyCommand {
+    fn name(&mut self, _: &dyn CommandContext) -> String {
+        format!(
+            "Set {} Property Value On {} Materials",
+            self.name,
+            self.materials.len()
+        )
+    }
+
+    fn execute(&mut self, _: &mut dyn CommandContext) {
+        self.swap();
+    }
+
+    fn revert(&mut self, _: &mut dyn CommandContext) {
+        self.swap();
+    }
+}
+
 #[derive(Debug)]
 enum SetMaterialShaderCommandState {
     Undefined,
@@ -70,6 +290,7 @@ enum SetMaterialShaderCommandState {
 pub struct SetMaterialShaderCommand {
     material: MaterialResource,
     state: SetMaterialShaderCommandState,
+    save_path: Option<PathBuf>,
 }
 
 impl SetMaterialShaderCommand {
@@ -77,6 +298,27 @@ impl SetMaterialShaderCommand {
         Self {
             material,
             state: SetMaterialShaderCommandState::NonExecuted { new_shader: shader },
+            save_path: None,
+        }
+    }
+
+    /// Sets a path to save the material to, if the material does not have a path yet. Materials
+    /// with a path are always saved to their own path.
+    pub fn with_save_path(mut self, path: PathBuf) -> Self {
+        self.save_path = Some(path);
+        self
+    }
+
+    /// Returns the plan of migration of the properties of the material to the new shader, without
+    /// modifying anything. It could be shown to the user before executing the command, which then
+    /// applies the same plan. Returns [`None`] if the command was already executed or the shader is
+    /// not loaded.
+    pub fn migration_plan(&self) -> Option<ShaderMigrationPlan> {
+        match self.state {
+            SetMaterialShaderCommandState::NonExecuted { ref new_shader } => {
+                self.material.data_ref().plan_shader_migration(new_shader)
+            }
+            _ => None,
         }
     }
 
@@ -89,10 +331,14 @@ impl SetMaterialShaderCommand {
             SetMaterialShaderCommandState::NonExecuted { new_shader } => {
                 let mut material = self.material.data_ref();
 
-                let old_material = std::mem::replace(
-                    &mut *material,
-                    Material::from_shader(new_shader, Some(context.resource_manager.clone())),
-                );
+                let resource_manager = Some(context.resource_manager.clone());
+                let new_material = match material.plan_shader_migration(&new_shader) {
+                    Some(plan) => {
+                        material.with_shader_migration(new_shader, &plan, resource_manager)
+                    }
+                    None => Material::from_shader(new_shader, resource_manager),
+                };
+                let old_material = std::mem::replace(&mut *material, new_material);
 
                 self.state = SetMaterialShaderCommandState::Executed { old_material };
             }
@@ -112,7 +358,8 @@ impl SetMaterialShaderCommand {
             }
         }
 
-        try_save(&self.material);
+        self.material.data_ref().mark_modified();
+        try_save(&self.material, self.save_path.as_deref());
     }
 }
 
@@ -129,3 +376,459 @@ impl CommandTrait for SetMaterialShaderCommand {
         self.swap(ctx);
     }
 }
+
+/// Returns materials of the node (one per surface for meshes), or [`None`] if the node has no
+/// materials.
+fn node_materials(node: &Node) -> Option<Vec<MaterialResource>> {
+    if let Some(mesh) = node.cast::<Mesh>() {
+        Some(
+            mesh.surfaces()
+                .iter()
+                .map(|surface| surface.material().clone())
+                .collect(),
+        )
+    } else if let Some(sprite) = node.cast::<Sprite>() {
+        Some(vec![(**sprite.material()).clone()])
+    } else if let Some(rectangle) = node.cast::<Rectangle>() {
+        Some(vec![(**rectangle.material()).clone()])
+    } else {
+        node.cast::<ParticleSystem>()
+            .map(|particle_system| vec![particle_system.texture()])
+    }
+}
+
+fn set_node_materials(node: &mut Node, materials: Vec<MaterialResource>) {
+    if let Some(mesh) = node.cast_mut::<Mesh>() {
+        for (surface, material) in mesh.surfaces_mut().iter_mut().zip(materials) {
+            surface.set_material(material);
+        }
+    } else if let Some(material) = materials.into_iter().next() {
+        if let Some(sprite) = node.cast_mut::<Sprite>() {
+            sprite.material_mut().set_value_and_mark_modified(material);
+        } else if let Some(rectangle) = node.cast_mut::<Rectangle>() {
+            rectangle
+                .material_mut()
+                .set_value_and_mark_modified(material);
+        } else if let Some(particle_system) = node.cast_mut::<ParticleSystem>() {
+            particle_system.set_material(material);
+        }
+    }
+}
+
+/// Replaces every occurrence of the `source` material of the node with the `new` material. Returns
+/// the previous materials of the node, or [`None`] if the node does not use the source material.
+fn replace_node_material(
+    graph: &mut Graph,
+    handle: Handle<Node>,
+    source: &MaterialResource,
+    new: &MaterialResource,
+) -> Option<Vec<MaterialResource>> {
+    let materials = graph.try_get(handle).and_then(node_materials)?;
+    if !materials.contains(source) {
+        return None;
+    }
+
+    let new_materials = materials
+        .iter()
+        .map(|material| {
+            if material == source {
+                new.clone()
+            } else {
+                material.clone()
+            }
+        })
+        .collect();
+    set_node_materials(&mut graph[handle], new_materials);
+
+    Some(materials)
+}
+
+/// Saves the material at the given path and registers it in the resource manager as a new
+/// external resource.
+fn save_as_resource(
+    mut material: Material,
+    path: &Path,
+    resource_manager: &ResourceManager,
+) -> Option<MaterialResource> {
+    if let Err(err) = material.save(path) {
+        Log::err(format!(
+            "Unable to save the material to {}. Reason: {:?}",
+            path.display(),
+            err
+        ));
+        return None;
+    }
+
+    let resource = MaterialResource::new_ok(ResourceKind::External(path.to_path_buf()), material);
+    Log::verify(resource_manager.register(resource.clone().into_untyped(), path, |_, _| true));
+    Some(resource)
+}
+
+/// Exchanges materials of two nodes (meshes, sprites, rectangles, particle systems). Meshes are
+/// swapped surface-by-surface, so both nodes must have the same amount of materials.
+#[derive(Debug)]
+pub struct SwapNodeMaterialsCommand {
+    first: Handle<Node>,
+    second: Handle<Node>,
+}
+
+impl SwapNodeMaterialsCommand {
+    pub fn new(first: Handle<Node>, second: Handle<Node>) -> Self {
+        Self { first, second }
+    }
+
+    fn swap(&mut self, context: &mut dyn CommandContext) {
+        let graph = &mut context.get_mut::<GameSceneContext>().scene.graph;
+
+        let (Some(first), Some(second)) = (
+            graph.try_get(self.first).and_then(node_materials),
+            graph.try_get(self.second).and_then(node_materials),
+        ) else {
+            Log::err("Unable to swap materials, one of the nodes has no materials!");
+            return;
+        };
+
+        if first.len() != second.len() {
+            Log::err(format!(
+                "Unable to swap materials, the nodes have different amount of materials ({} and {})!",
+                first.len(),
+                second.len()
+            ));
+            return;
+        }
+
+        set_node_materials(&mut graph[self.first], second);
+        set_node_materials(&mut graph[self.second], first);
+    }
+}
+
+impl CommandTrait for SwapNodeMaterialsCommand {
+    fn name(&mut self, _: &dyn CommandContext) -> String {
+        "Swap Node Materials".to_owned()
+    }
+
+    fn execute(&mut self, ctx: &mut dyn CommandContext) {
+        self.swap(ctx);
+    }
+
+    fn revert(&mut self, ctx: &mut dyn CommandContext) {
+        self.swap(ctx);
+    }
+}
+
+/// Moves the given properties of a material into a new material resource, that is saved at the
+/// path chosen by the user, and assigns the new material to the given nodes instead of the source
+/// one. Every other property of the new material has the default value from the shader. The
+/// source material is not modified, so reverting the command restores the original materials of
+/// the nodes (with their inline values). The file of the new material is kept on revert.
+#[derive(Debug)]
+pub struct ExtractMaterialPropertiesCommand {
+    source: MaterialResource,
+    property_names: Vec<ImmutableString>,
+    path: PathBuf,
+    nodes: Vec<Handle<Node>>,
+    new_material: Option<MaterialResource>,
+    old_materials: Vec<(Handle<Node>, Vec<MaterialResource>)>,
+}
+
+impl ExtractMaterialPropertiesCommand {
+    pub fn new(
+        source: MaterialResource,
+        property_names: Vec<ImmutableString>,
+        path: PathBuf,
+        nodes: Vec<Handle<Node>>,
+    ) -> Self {
+        Self {
+            source,
+            property_names,
+            path,
+            nodes,
+            new_material: None,
+            old_materials: Default::default(),
+        }
+    }
+
+    fn create_material(&mut self, context: &GameSceneContext) -> Option<MaterialResource> {
+        if let Some(new_material) = self.new_material.as_ref() {
+            return Some(new_material.clone());
+        }
+
+        let material = self
+            .source
+            .data_ref()
+            .extract_properties(&self.property_names, Some(context.resource_manager.clone()));
+        let resource = save_as_resource(material, &self.path, &context.resource_manager)?;
+
+        self.new_material = Some(resource.clone());
+        Some(resource)
+    }
+}
+
+impl CommandTrait for ExtractMaterialPropertiesCommand {
+    fn name(&mut self, _: &dyn CommandContext) -> String {
+        format!("Extract {} Material Properties", self.property_names.len())
+    }
+
+    fn execute(&mut self, context: &mut dyn CommandContext) {
+        let context = context.get_mut::<GameSceneContext>();
+
+        let Some(new_material) = self.create_material(context) else {
+            return;
+        };
+
+        let graph = &mut context.scene.graph;
+        for &handle in self.nodes.iter() {
+            if let Some(materials) =
+                replace_node_material(graph, handle, &self.source, &new_material)
+            {
+                self.old_materials.push((handle, materials));
+            }
+        }
+    }
+
+    fn revert(&mut self, context: &mut dyn CommandContext) {
+        let graph = &mut context.get_mut::<GameSceneContext>().scene.graph;
+        for (handle, materials) in self.old_materials.drain(..) {
+            if let Some(node) = graph.try_get_mut(handle) {
+                set_node_materials(node, materials);
+            }
+        }
+    }
+}
+
+/// Saves a copy of a material (usually an embedded one, that lives only in a scene) as a shared
+/// material resource at the path chosen by the user and makes the node reference the new resource
+/// instead of the material. The new resource is registered in the resource manager. Reverting the
+/// command restores the previous (inline) material of the node, the file of the new material is
+/// kept on revert and reused on redo.
+#[derive(Debug)]
+pub struct SaveMaterialAsResourceCommand {
+    node: Handle<Node>,
+    material: MaterialResource,
+    path: PathBuf,
+    new_material: Option<MaterialResource>,
+    old_materials: Option<Vec<MaterialResource>>,
+}
+
+impl SaveMaterialAsResourceCommand {
+    pub fn new(node: Handle<Node>, material: MaterialResource, path: PathBuf) -> Self {
+        Self {
+            node,
+            material,
+            path,
+            new_material: None,
+            old_materials: None,
+        }
+    }
+}
+
+impl CommandTrait for SaveMaterialAsResourceCommand {
+    fn name(&mut self, _: &dyn CommandContext) -> String {
+        "Save Material As Resource".to_owned()
+    }
+
+    fn execute(&mut self, context: &mut dyn CommandContext) {
+        let context = context.get_mut::<GameSceneContext>();
+
+        if self.new_material.is_none() {
+            let material = (*self.material.data_ref()).clone();
+            self.new_material = save_as_resource(material, &self.path, &context.resource_manager);
+        }
+        let Some(new_material) = self.new_material.as_ref() else {
+            return;
+        };
+
+        self.old_materials = replace_node_material(
+            &mut context.scene.graph,
+            self.node,
+            &self.material,
+            new_material,
+        );
+        if self.old_materials.is_none() {
+            Log::warn(format!(
+                "The material was saved to {}, but the node does not use it!",
+                self.path.display()
+            ));
+        }
+    }
+
+    fn revert(&mut self, context: &mut dyn CommandContext) {
+        let graph = &mut context.get_mut::<GameSceneContext>().scene.graph;
+        if let Some(materials) = self.old_materials.take() {
+            if let Some(node) = graph.try_get_mut(self.node) {
+                set_node_materials(node, materials);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{
+        command::CommandTrait,
+        fyrox::{
+            asset::{manager::ResourceManager, untyped::ResourceKind},
+            core::{log::Log, pool::Handle, sstorage::ImmutableString},
+            material::{Material, MaterialResource, PropertyValue},
+            scene::{base::BaseBuilder, node::Node, sprite::SpriteBuilder, Scene},
+        },
+        scene::commands::{
+            material::{
+                node_materials, ExtractMaterialPropertiesCommand, SaveMaterialAsResourceCommand,
+                SetMaterialPropertyOnManyCommand, SetMaterialRenderQueueCommand,
+                SwapNodeMaterialsCommand,
+            },
+            GameSceneContext,
+        },
+    };
+    use std::{path::PathBuf, sync::Arc};
+
+    fn embedded_material() -> MaterialResource {
+        MaterialResource::new_ok(ResourceKind::Embedded, Material::standard())
+    }
+
+    fn resource_manager() -> ResourceManager {
+        ResourceManager::new(Arc::new(Default::default()))
+    }
+
+    fn add_sprite(scene: &mut Scene, material: &MaterialResource) -> Handle<Node> {
+        SpriteBuilder::new(BaseBuilder::new())
+            .with_material(material.clone())
+            .build(&mut scene.graph)
+    }
+
+    fn material_of(scene: &Scene, node: Handle<Node>) -> MaterialResource {
+        node_materials(&scene.graph[node]).unwrap().remove(0)
+    }
+
+    fn temp_material_path(name: &str) -> PathBuf {
+        std::env::temp_dir().join(format!("{}_{}.material", name, std::process::id()))
+    }
+
+    #[test]
+    fn test_set_material_property_on_many() {
+        let name = ImmutableString::new("parallaxScale");
+        let materials = [0.25, 0.5].map(|value| {
+            let material = embedded_material();
+            Log::verify(
+                material
+                    .data_ref()
+                    .set_property(&name, PropertyValue::Float(value)),
+            );
+            material
+        });
+        let value = |material: &MaterialResource| material.data_ref().property_ref(&name).cloned();
+
+        let mut command = SetMaterialPropertyOnManyCommand::new(
+            materials.to_vec(),
+            name.clone(),
+            PropertyValue::Float(1.0),
+        );
+
+        GameSceneContext::exec_with_scene(&mut Scene::new(), resource_manager(), |context| {
+            command.execute(context);
+            for material in materials.iter() {
+                assert_eq!(value(material), Some(PropertyValue::Float(1.0)));
+            }
+            // Each material gets its own previous value back.
+            command.revert(context);
+            assert_eq!(value(&materials[0]), Some(PropertyValue::Float(0.25)));
+            assert_eq!(value(&materials[1]), Some(PropertyValue::Float(0.5)));
+        });
+    }
+
+    #[test]
+    fn test_swap_node_materials() {
+        let mut scene = Scene::new();
+        let (a, b) = (embedded_material(), embedded_material());
+        let first = add_sprite(&mut scene, &a);
+        let second = add_sprite(&mut scene, &b);
+        let mut command = SwapNodeMaterialsCommand::new(first, second);
+
+        GameSceneContext::exec_with_scene(&mut scene, resource_manager(), |context| {
+            command.execute(context);
+            assert_eq!(material_of(context.scene, first), b);
+            assert_eq!(material_of(context.scene, second), a);
+            command.revert(context);
+            assert_eq!(material_of(context.scene, first), a);
+            assert_eq!(material_of(context.scene, second), b);
+        });
+    }
+
+    #[test]
+    fn test_extract_material_properties() {
+        let mut scene = Scene::new();
+        let name = ImmutableString::new("parallaxScale");
+        let source = embedded_material();
+        Log::verify(
+            source
+                .data_ref()
+                .set_property(&name, PropertyValue::Float(0.25)),
+        );
+        let node = add_sprite(&mut scene, &source);
+        let path = temp_material_path("extracted");
+        let mut command = ExtractMaterialPropertiesCommand::new(
+            source.clone(),
+            vec![name.clone()],
+            path.clone(),
+            vec![node],
+        );
+
+        GameSceneContext::exec_with_scene(&mut scene, resource_manager(), |context| {
+            command.execute(context);
+            let extracted = material_of(context.scene, node);
+            assert_ne!(extracted, source);
+            assert_eq!(extracted.kind(), ResourceKind::External(path.clone()));
+            assert_eq!(
+                extracted.data_ref().property_ref(&name),
+                Some(&PropertyValue::Float(0.25))
+            );
+
+            command.revert(context);
+            assert_eq!(material_of(context.scene, node), source);
+
+            // The same material is assigned again on redo.
+            command.execute(context);
+            assert_eq!(material_of(context.scene, node), extracted);
+        });
+
+        let _ = std::fs::remove_file(path);
+    }
+
+    #[test]
+    fn test_save_material_as_resource() {
+        let mut scene = Scene::new();
+        let material = embedded_material();
+        let node = add_sprite(&mut scene, &material);
+        let path = temp_material_path("saved");
+        let mut command = SaveMaterialAsResourceCommand::new(node, material.clone(), path.clone());
+
+        GameSceneContext::exec_with_scene(&mut scene, resource_manager(), |context| {
+            command.execute(context);
+            let saved = material_of(context.scene, node);
+            assert_eq!(saved.kind(), ResourceKind::External(path.clone()));
+            assert!(path.exists());
+            assert!(context.resource_manager.state().find(&path).is_some());
+
+            command.revert(context);
+            assert_eq!(material_of(context.scene, node), material);
+        });
+
+        let _ = std::fs::remove_file(path);
+    }
+
+    #[test]
+    fn test_set_material_render_queue() {
+        let material = embedded_material();
+        let mut command = SetMaterialRenderQueueCommand::new(material.clone(), 5);
+
+        GameSceneContext::exec_with_scene(&mut Scene::new(), resource_manager(), |context| {
+            command.execute(context);
+            assert_eq!(material.data_ref().render_queue(), 5);
+            command.revert(context);
+            assert_eq!(material.data_ref().render_queue(), 0);
+            command.execute(context);
+            assert_eq!(material.data_ref().render_queue(), 5);
+        });
+    }
+}
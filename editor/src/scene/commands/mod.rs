@@ -17,6 +17,7 @@ use crate::{
 };
 use std::sync::Arc;
 
+pub mod collider;
 pub mod effect;
 pub mod graph;
 pub mod material;
@@ -72,6 +73,29 @@ impl GameSceneContext {
 
 impl CommandContext for GameSceneContext {}
 
+#[cfg(test)]
+impl GameSceneContext {
+    /// Executes the closure with a context of the given scene, that has empty selection and
+    /// clipboard. Sent messages are discarded. It is used to test commands.
+    pub fn exec_with_scene<F>(scene: &mut Scene, resource_manager: ResourceManager, func: F)
+    where
+        F: FnOnce(&mut GameSceneContext),
+    {
+        let (sender, _) = std::sync::mpsc::channel();
+        let mut scene_content_root = Handle::NONE;
+        Self::exec(
+            &mut Selection::default(),
+            scene,
+            &mut scene_content_root,
+            &mut Clipboard::default(),
+            MessageSender(sender),
+            resource_manager,
+            Arc::new(SerializationContext::new()),
+            func,
+        )
+    }
+}
+
 pub fn selection_to_delete(editor_selection: &Selection, game_scene: &GameScene) -> GraphSelection {
     // Graph's root is non-deletable.
     let mut selection = if let Some(selection) = editor_selection.as_graph() {
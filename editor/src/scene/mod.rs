@@ -41,6 +41,7 @@ use crate::{
             base::BaseBuilder,
             camera::{Camera, Projection},
             debug::{Line, SceneDrawingContext},
+            dim2::collider::find_degenerate_triangle_colliders,
             graph::{Graph, GraphUpdateSwitches},
             light::{point::PointLight, spot::SpotLight},
             mesh::RenderPath,
@@ -233,6 +234,20 @@ impl GameScene {
         pure_scene
     }
 
+    /// Writes a warning for every 2D collider with zero-area or extremely thin triangle shape,
+    /// such colliders behave badly in physics. It does not prevent the scene from saving.
+    fn report_degenerate_triangle_colliders(&self, engine: &Engine) {
+        let graph = &engine.scenes[self.scene].graph;
+        for handle in find_degenerate_triangle_colliders(graph, 1.0e-3) {
+            Log::warn(format!(
+                "2D collider {} ({}) has a degenerate (zero-area or extremely thin) triangle \
+                shape, it will behave badly in physics!",
+                graph[handle].name(),
+                handle
+            ));
+        }
+    }
+
     #[allow(clippy::redundant_clone)] // false positive
     pub fn save(
         &mut self,
@@ -245,6 +260,8 @@ impl GameScene {
         let mut reason = "Scene is not saved, because validation failed:\n".to_owned();
 
         if valid {
+            self.report_degenerate_triangle_colliders(engine);
+
             let mut pure_scene = self.make_purified_scene(engine);
 
             let mut visitor = Visitor::new();
@@ -3,7 +3,7 @@ use crate::{
     fyrox::{
         asset::untyped::UntypedResource,
         core::{algebra::Vector2, algebra::Vector3, pool::Handle, reflect::Reflect, scope_profile},
-        graph::BaseSceneGraph,
+        graph::{BaseSceneGraph, SceneGraph},
         gui::{
             file_browser::FileSelectorMessage,
             menu::{ContextMenuBuilder, MenuItemBuilder, MenuItemContent, MenuItemMessage},
@@ -14,17 +14,21 @@ use crate::{
             window::WindowMessage,
             BuildContext, RcUiNodeHandle, UiNode,
         },
+        scene::{dim2::collider::Collider, node::Node},
     },
     make_save_file_selector,
     menu::{create::CreateEntityMenu, create_menu_item, create_menu_item_shortcut},
     message::MessageSender,
     scene::{
         commands::{
+            collider::FlipCollider2DWindingCommand,
             graph::{
                 AddNodeCommand, LinkNodesCommand, MoveNodeCommand, ReplaceNodeCommand,
                 SetGraphRootCommand, SetNodeTransformCommand,
             },
-            make_delete_selection_command, RevertSceneNodePropertyCommand,
+            make_delete_selection_command,
+            material::SwapNodeMaterialsCommand,
+            RevertSceneNodePropertyCommand,
         },
         controller::SceneController,
         GameScene, Selection,
@@ -50,6 +54,8 @@ pub struct SceneNodeContextMenu {
     make_root: Handle<UiNode>,
     open_asset: Handle<UiNode>,
     reset_inheritable_properties: Handle<UiNode>,
+    flip_collider_winding: Handle<UiNode>,
+    swap_materials: Handle<UiNode>,
 }
 
 impl WorldViewerItemContextMenu for SceneNodeContextMenu {
@@ -58,6 +64,25 @@ impl WorldViewerItemContextMenu for SceneNodeContextMenu {
     }
 }
 
+fn selected_2d_colliders(
+    editor_selection: &Selection,
+    game_scene: &GameScene,
+    engine: &Engine,
+) -> Vec<Handle<Node>> {
+    let graph = &engine.scenes[game_scene.scene].graph;
+    editor_selection
+        .as_graph()
+        .map(|graph_selection| {
+            graph_selection
+                .nodes
+                .iter()
+                .filter(|handle| graph.try_get_of_type::<Collider>(**handle).is_some())
+                .cloned()
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
 fn resource_path_of_first_selected_node(
     editor_selection: &Selection,
     game_scene: &GameScene,
@@ -83,6 +108,8 @@ impl SceneNodeContextMenu {
         let make_root;
         let open_asset;
         let reset_inheritable_properties;
+        let flip_collider_winding;
+        let swap_materials;
 
         let (create_child_entity_menu, create_child_entity_menu_root_items) =
             CreateEntityMenu::new(ctx);
@@ -148,6 +175,15 @@ impl SceneNodeContextMenu {
                             reset_inheritable_properties =
                                 create_menu_item("Reset Inheritable Properties", vec![], ctx);
                             reset_inheritable_properties
+                        })
+                        .with_child({
+                            flip_collider_winding =
+                                create_menu_item("Flip Collider Winding", vec![], ctx);
+                            flip_collider_winding
+                        })
+                        .with_child({
+                            swap_materials = create_menu_item("Swap Materials", vec![], ctx);
+                            swap_materials
                         }),
                 )
                 .build(ctx),
@@ -172,6 +208,8 @@ impl SceneNodeContextMenu {
             make_root,
             open_asset,
             reset_inheritable_properties,
+            flip_collider_winding,
+            swap_materials,
             create_parent_entity_menu,
         }
     }
@@ -374,6 +412,20 @@ impl SceneNodeContextMenu {
                         }
                         sender.do_command(CommandGroup::from(commands));
                     }
+                } else if message.destination() == self.flip_collider_winding {
+                    let commands = selected_2d_colliders(editor_selection, game_scene, engine)
+                        .into_iter()
+                        .map(|collider| Command::new(FlipCollider2DWindingCommand::new(collider)))
+                        .collect::<Vec<_>>();
+                    if !commands.is_empty() {
+                        sender.do_command(CommandGroup::from(commands));
+                    }
+                } else if message.destination() == self.swap_materials {
+                    if let Some(graph_selection) = editor_selection.as_graph() {
+                        if let [first, second] = graph_selection.nodes.as_slice() {
+                            sender.do_command(SwapNodeMaterialsCommand::new(*first, *second));
+                        }
+                    }
                 }
             } else if let Some(PopupMessage::Placement(Placement::Cursor(target))) = message.data()
             {
@@ -403,6 +455,27 @@ impl SceneNodeContextMenu {
                             )
                             .map_or(false, |p| utils::is_native_scene(&p)),
                         ));
+
+                    engine
+                        .user_interfaces
+                        .first()
+                        .send_message(WidgetMessage::enabled(
+                            self.flip_collider_winding,
+                            MessageDirection::ToWidget,
+                            !selected_2d_colliders(editor_selection, game_scene, engine).is_empty(),
+                        ));
+
+                    // Materials can be swapped only between two nodes.
+                    engine
+                        .user_interfaces
+                        .first()
+                        .send_message(WidgetMessage::enabled(
+                            self.swap_materials,
+                            MessageDirection::ToWidget,
+                            editor_selection
+                                .as_graph()
+                                .map_or(false, |selection| selection.nodes.len() == 2),
+                        ));
                 }
             } else if let Some(FileSelectorMessage::Commit(path)) = message.data() {
                 if message.destination() == self.save_as_prefab_dialog {
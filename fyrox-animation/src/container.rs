@@ -4,13 +4,14 @@
 use crate::{
     core::{
         algebra::{Vector2, Vector3, Vector4},
-        math::curve::Curve,
-        math::{quat_from_euler, RotationOrder},
+        math::curve::{Curve, CurveKey, CurveKeyKind},
+        math::{lerpf, quat_from_euler, RotationOrder},
         reflect::prelude::*,
         visitor::prelude::*,
     },
     value::TrackValue,
 };
+use std::sync::OnceLock;
 
 /// The kind of track output value, the animation system works only with numeric properties and the number
 /// of variants is small.
@@ -67,6 +68,94 @@ pub enum InterpolationMode {
     ShortPath,
 }
 
+/// Interpolation of a single component (curve) of a track data container, see
+/// [`TrackDataContainer::set_component_interpolation`] for more info.
+#[derive(Visit, Reflect, Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum ComponentInterpolation {
+    /// The interpolation is defined by the kinds of the keys of the curve (see [`CurveKeyKind`]).
+    #[default]
+    KeyDefined,
+    /// Linear interpolation between keys, the kinds of the keys are ignored.
+    Linear,
+    /// The value of the previous key is held until the next key (stepped interpolation).
+    Step,
+    /// Smooth cubic interpolation between keys with automatically calculated tangents (Catmull-Rom spline).
+    Cubic,
+}
+
+impl ComponentInterpolation {
+    /// Interpolates two values of a component using the given interpolation factor. There's no information about
+    /// neighbouring values, so [`Self::Cubic`] eases in and out of the values. [`Self::KeyDefined`] is linear.
+    pub fn interpolate(self, a: f32, b: f32, t: f32) -> f32 {
+        match self {
+            Self::KeyDefined | Self::Linear => lerpf(a, b, t),
+            Self::Step => {
+                if t < 1.0 {
+                    a
+                } else {
+                    b
+                }
+            }
+            Self::Cubic => {
+                let t = t.clamp(0.0, 1.0);
+                lerpf(a, b, t * t * (3.0 - 2.0 * t))
+            }
+        }
+    }
+
+    fn sample(self, curve: &Curve, time: f32) -> f32 {
+        let keys = curve.keys();
+        let (Some(first), Some(last)) = (keys.first(), keys.last()) else {
+            return 0.0;
+        };
+        if self == Self::KeyDefined || time <= first.location || time >= last.location {
+            return curve.value_at(time);
+        }
+
+        let right = keys.partition_point(|key| key.location < time);
+        let left = right - 1;
+        let (a, b) = (&keys[left], &keys[right]);
+        let length = b.location - a.location;
+        let t = (time - a.location) / length;
+        match self {
+            Self::Cubic => {
+                let slope = |from: &CurveKey, to: &CurveKey| {
+                    (to.value - from.value) / (to.location - from.location)
+                };
+                let before = &keys[left.saturating_sub(1)];
+                let after = keys.get(right + 1).unwrap_or(b);
+                let m0 = if left == 0 {
+                    slope(a, b)
+                } else {
+                    slope(before, b)
+                };
+                let m1 = if after.location == b.location {
+                    slope(a, b)
+                } else {
+                    slope(a, after)
+                };
+                let t2 = t * t;
+                let t3 = t2 * t;
+                (2.0 * t3 - 3.0 * t2 + 1.0) * a.value
+                    + (t3 - 2.0 * t2 + t) * length * m0
+                    + (-2.0 * t3 + 3.0 * t2) * b.value
+                    + (t3 - t2) * length * m1
+            }
+            _ => self.interpolate(a.value, b.value, t),
+        }
+    }
+}
+
+/// A key frame of a track data container - a value of the container at a time position, where at least one of its
+/// curves has a key. See [`TrackDataContainer::keyframes`] for more info.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Keyframe {
+    /// Time position of the key frame (in seconds).
+    pub time: f32,
+    /// Value of the container at the time position of the key frame.
+    pub value: TrackValue,
+}
+
 /// Container for a track data. Strictly speaking, it is just a set of parametric curves which can be
 /// fetched at a given time position simultaneously, producing a value of desired type. Which type of
 /// value is produced is defined by [`TrackValueKind`] enumeration. Usually a container contains up to
@@ -74,13 +163,50 @@ pub enum InterpolationMode {
 ///
 /// Each component is bound to a specific curve. For example, in case of [`Vector3`] its components bound
 /// to the following curve indices: `X = 0`, `Y = 1`, `Z = 2`. This order cannot be changed.
-#[derive(Visit, Reflect, Debug, Clone, Default, PartialEq)]
+#[derive(Reflect, Debug, Clone, Default, PartialEq)]
 pub struct TrackDataContainer {
+    // Curves and the kind must be edited only through the methods of the container, otherwise the cached
+    // constant value won't be invalidated.
+    #[reflect(hidden)]
     curves: Vec<Curve>,
+    #[reflect(hidden)]
     kind: TrackValueKind,
     /// Interpolation mode.
-    #[visit(optional)] // Backward compatibility.
     pub mode: InterpolationMode,
+    /// Interpolation of each component, missing components use [`ComponentInterpolation::KeyDefined`].
+    component_interpolation: Vec<ComponentInterpolation>,
+    #[reflect(hidden)]
+    constant_value: ConstantValueCache,
+}
+
+/// Lazily calculated constant value of a container, see [`TrackDataContainer::constant_value`]. It is derived from the
+/// curves, so it is ignored when containers are compared.
+#[derive(Debug, Clone, Default)]
+struct ConstantValueCache(OnceLock<Option<TrackValue>>);
+
+impl PartialEq for ConstantValueCache {
+    fn eq(&self, _other: &Self) -> bool {
+        true
+    }
+}
+
+impl Visit for TrackDataContainer {
+    fn visit(&mut self, name: &str, visitor: &mut Visitor) -> VisitResult {
+        let mut region = visitor.enter_region(name)?;
+
+        self.curves.visit("Curves", &mut region)?;
+        self.kind.visit("Kind", &mut region)?;
+        let _ = self.mode.visit("Mode", &mut region); // Backward compatibility.
+        let _ = self
+            .component_interpolation
+            .visit("ComponentInterpolation", &mut region); // Backward compatibility.
+
+        if region.is_reading() {
+            self.invalidate_constant_value();
+        }
+
+        Ok(())
+    }
 }
 
 impl TrackDataContainer {
@@ -98,12 +224,15 @@ impl TrackDataContainer {
                 .map(|_| Curve::default())
                 .collect(),
             mode: Default::default(),
+            component_interpolation: Default::default(),
+            constant_value: Default::default(),
         }
     }
 
     /// Adds a new curve to the container. Keep in mind, that the actual useful amount of curves has soft limit
     /// of four due to [`TrackValueKind`], any excessive curves will be ignored.
     pub fn add_curve(&mut self, curve: Curve) {
+        self.invalidate_constant_value();
         self.curves.push(curve)
     }
 
@@ -114,6 +243,7 @@ impl TrackDataContainer {
 
     /// Tries to borrow a curve at a given index.
     pub fn curve_mut(&mut self, index: usize) -> Option<&mut Curve> {
+        self.invalidate_constant_value();
         self.curves.get_mut(index)
     }
 
@@ -124,12 +254,14 @@ impl TrackDataContainer {
 
     /// Tries to borrow a curve at a given index.
     pub fn curves_mut(&mut self) -> &mut [Curve] {
+        self.invalidate_constant_value();
         &mut self.curves
     }
 
     /// Sets new kind of output value. Keep in mind, that the curves will remain unchanged, if you need
     /// to re-use the container you might need to re-create/re-fill the curves too.
     pub fn set_value_kind(&mut self, kind: TrackValueKind) {
+        self.invalidate_constant_value();
         self.kind = kind;
     }
 
@@ -138,50 +270,378 @@ impl TrackDataContainer {
         self.kind
     }
 
+    /// Sets the interpolation of the component (curve) with the given index, overriding the kinds of the keys of its
+    /// curve. It allows to mix smooth and snapping motion in a single track, for example a position track could have
+    /// linear `X` and `Y` components and stepped `Z` component. Components of rotation tracks are Euler angles, short
+    /// path interpolation (see [`InterpolationMode::ShortPath`]) is used only for [`ComponentInterpolation::KeyDefined`]
+    /// components.
+    pub fn set_component_interpolation(
+        &mut self,
+        index: usize,
+        interpolation: ComponentInterpolation,
+    ) {
+        self.invalidate_constant_value();
+        if self.component_interpolation.len() <= index {
+            self.component_interpolation
+                .resize(index + 1, ComponentInterpolation::KeyDefined);
+        }
+        self.component_interpolation[index] = interpolation;
+    }
+
+    /// Returns the interpolation of the component (curve) with the given index. See
+    /// [`Self::set_component_interpolation`] for more info.
+    pub fn component_interpolation(&self, index: usize) -> ComponentInterpolation {
+        self.component_interpolation
+            .get(index)
+            .copied()
+            .unwrap_or_default()
+    }
+
+    /// Returns the interpolations of every component (curve) of the container.
+    pub fn component_interpolations(&self) -> Vec<ComponentInterpolation> {
+        (0..self.curves.len())
+            .map(|index| self.component_interpolation(index))
+            .collect()
+    }
+
+    fn component_at(&self, index: usize, time: f32) -> Option<f32> {
+        let curve = self.curves.get(index)?;
+        Some(self.sample_component(index, curve, time))
+    }
+
+    /// Samples the given curve as if it was the curve of the component with the given index.
+    fn sample_component(&self, index: usize, curve: &Curve, time: f32) -> f32 {
+        match self.component_interpolation(index) {
+            ComponentInterpolation::KeyDefined
+                if self.kind == TrackValueKind::UnitQuaternion
+                    && self.mode == InterpolationMode::ShortPath =>
+            {
+                curve.angle_at(time)
+            }
+            interpolation => interpolation.sample(curve, time),
+        }
+    }
+
+    fn invalidate_constant_value(&mut self) {
+        self.constant_value = Default::default();
+    }
+
+    /// Returns the value of the container, if it produces the same value at any time. It is the case, when every curve
+    /// has one key or all the keys of a curve have the same value (and the interpolation between them cannot overshoot).
+    /// Such containers are quite common (for example, static bones of a rig) and [`Self::fetch`] returns the value of
+    /// them without any interpolation. The value is detected automatically on first request after any modification of
+    /// the container.
+    pub fn constant_value(&self) -> Option<&TrackValue> {
+        self.constant_value
+            .0
+            .get_or_init(|| {
+                let mut time = None;
+                for curve in self.curves.iter().take(self.kind.components_count()) {
+                    let (first, rest) = curve.keys().split_first()?;
+                    let is_constant = rest.iter().all(|key| key.value == first.value)
+                        && (curve.keys().len() == 1
+                            || curve.keys().iter().all(|key| match key.kind {
+                                // Tangents of cubic keys could make the curve go away from the values of its keys.
+                                CurveKeyKind::Cubic {
+                                    left_tangent,
+                                    right_tangent,
+                                } => left_tangent == 0.0 && right_tangent == 0.0,
+                                CurveKeyKind::Constant | CurveKeyKind::Linear => true,
+                            }));
+                    if !is_constant {
+                        return None;
+                    }
+                    time = Some(first.location);
+                }
+                self.fetch_interpolated(time?)
+            })
+            .as_ref()
+    }
+
     /// Tries to get a value at a given time. The method could fail if the internal set of curves is malformed
     /// and cannot produce a desired value (for example, [`Vector3`] can be fetched only if the amount of curves
     /// is 3).
     pub fn fetch(&self, time: f32) -> Option<TrackValue> {
+        if let Some(constant_value) = self.constant_value() {
+            return Some(constant_value.clone());
+        }
+        self.fetch_interpolated(time)
+    }
+
+    fn fetch_interpolated(&self, time: f32) -> Option<TrackValue> {
         match self.kind {
-            TrackValueKind::Real => Some(TrackValue::Real(self.curves.first()?.value_at(time))),
+            TrackValueKind::Real => Some(TrackValue::Real(self.component_at(0, time)?)),
             TrackValueKind::Vector2 => Some(TrackValue::Vector2(Vector2::new(
-                self.curves.first()?.value_at(time),
-                self.curves.get(1)?.value_at(time),
+                self.component_at(0, time)?,
+                self.component_at(1, time)?,
             ))),
             TrackValueKind::Vector3 => Some(TrackValue::Vector3(Vector3::new(
-                self.curves.first()?.value_at(time),
-                self.curves.get(1)?.value_at(time),
-                self.curves.get(2)?.value_at(time),
+                self.component_at(0, time)?,
+                self.component_at(1, time)?,
+                self.component_at(2, time)?,
             ))),
             TrackValueKind::Vector4 => Some(TrackValue::Vector4(Vector4::new(
-                self.curves.first()?.value_at(time),
-                self.curves.get(1)?.value_at(time),
-                self.curves.get(2)?.value_at(time),
-                self.curves.get(3)?.value_at(time),
+                self.component_at(0, time)?,
+                self.component_at(1, time)?,
+                self.component_at(2, time)?,
+                self.component_at(3, time)?,
             ))),
             TrackValueKind::UnitQuaternion => {
                 // Convert Euler angles to quaternion
-                let (x, y, z) = match self.mode {
-                    InterpolationMode::Default => (
-                        self.curves.first()?.value_at(time),
-                        self.curves.get(1)?.value_at(time),
-                        self.curves.get(2)?.value_at(time),
-                    ),
-                    InterpolationMode::ShortPath => (
-                        self.curves.first()?.angle_at(time),
-                        self.curves.get(1)?.angle_at(time),
-                        self.curves.get(2)?.angle_at(time),
-                    ),
-                };
-
                 Some(TrackValue::UnitQuaternion(quat_from_euler(
-                    Vector3::new(x, y, z),
+                    Vector3::new(
+                        self.component_at(0, time)?,
+                        self.component_at(1, time)?,
+                        self.component_at(2, time)?,
+                    ),
                     RotationOrder::XYZ,
                 )))
             }
         }
     }
 
+    /// Makes the values produced by the container smooth by converting the keys of every curve into cubic (Hermite) keys
+    /// with automatically calculated tangents (see [`Curve::set_smooth_tangents`] for more info). Existing data is not
+    /// changed until this method is called, linear keys produce linear interpolation as before. Containers of
+    /// [`TrackValueKind::UnitQuaternion`] kind are left unchanged, rotations are interpolated using their own rules.
+    pub fn set_smooth_tangents(&mut self) {
+        self.invalidate_constant_value();
+        if self.kind == TrackValueKind::UnitQuaternion {
+            return;
+        }
+
+        for curve in self.curves.iter_mut() {
+            curve.set_smooth_tangents();
+        }
+    }
+
+    /// Returns sorted time positions of every key frame of the container. Keys of different curves at the same time
+    /// position form a single key frame.
+    pub fn keyframe_times(&self) -> Vec<f32> {
+        let mut times = self
+            .curves
+            .iter()
+            .flat_map(|curve| curve.keys().iter().map(|key| key.location))
+            .collect::<Vec<_>>();
+        times.sort_by(|a, b| a.total_cmp(b));
+        times.dedup();
+        times
+    }
+
+    /// Returns every key frame of the container sorted by time. Curves of the container could have keys at different
+    /// time positions, in this case the components of a key frame, which curves do not have a key at the time position,
+    /// are interpolated. Returns an empty vector if the container is malformed (see [`Self::fetch`]).
+    pub fn keyframes(&self) -> Vec<Keyframe> {
+        self.keyframe_times()
+            .into_iter()
+            .map_while(|time| self.fetch(time).map(|value| Keyframe { time, value }))
+            .collect()
+    }
+
+    /// Splits the value into values for each curve of the container. Returns [`None`] if the value cannot be produced
+    /// by the container.
+    fn value_components(&self, value: &TrackValue) -> Option<Vec<f32>> {
+        let components = match (self.kind, value) {
+            (TrackValueKind::Real, TrackValue::Real(v)) => vec![*v],
+            (TrackValueKind::Vector2, TrackValue::Vector2(v)) => v.as_slice().to_vec(),
+            (TrackValueKind::Vector3, TrackValue::Vector3(v)) => v.as_slice().to_vec(),
+            (TrackValueKind::Vector4, TrackValue::Vector4(v)) => v.as_slice().to_vec(),
+            (TrackValueKind::UnitQuaternion, TrackValue::UnitQuaternion(v)) => {
+                // Euler angles in XYZ order.
+                let (x, y, z) = v.euler_angles();
+                vec![x, y, z]
+            }
+            _ => return None,
+        };
+        (self.curves.len() >= components.len()).then_some(components)
+    }
+
+    /// Inserts a new key frame with the given kind of interpolation, keys are kept sorted by time. If there's a key frame
+    /// at the same time position already, its values are replaced. Returns the index of the key frame (in the list of
+    /// [`Self::keyframes`]) or [`None`] if the value cannot be produced by the container (for example, the value is
+    /// [`TrackValue::Real`], while the container produces [`TrackValue::Vector3`]).
+    pub fn insert_keyframe(
+        &mut self,
+        time: f32,
+        value: &TrackValue,
+        kind: CurveKeyKind,
+    ) -> Option<usize> {
+        let components = self.value_components(value)?;
+        self.invalidate_constant_value();
+        for (curve, component) in self.curves.iter_mut().zip(components) {
+            if let Some(key) = curve.keys.iter_mut().find(|key| key.location == time) {
+                key.value = component;
+                key.kind = kind.clone();
+            } else {
+                curve.add_key(CurveKey::new(time, component, kind.clone()));
+            }
+        }
+        self.keyframe_times().iter().position(|t| *t == time)
+    }
+
+    /// Moves the key frame at the given index (in the list of [`Self::keyframes`]) to a new time position and sets a
+    /// new value for it. Keys are re-sorted, ids and interpolation kinds of existing keys are preserved. Returns the new
+    /// index of the key frame, or [`None`] if there's no such key frame or the value cannot be produced by the container.
+    pub fn set_keyframe(&mut self, index: usize, time: f32, value: &TrackValue) -> Option<usize> {
+        let old_time = *self.keyframe_times().get(index)?;
+        let components = self.value_components(value)?;
+        self.invalidate_constant_value();
+        for (curve, component) in self.curves.iter_mut().zip(components) {
+            let key_index = curve.keys.iter().position(|key| key.location == old_time);
+            let new_key_index = curve.keys.iter().position(|key| key.location == time);
+            match (key_index, new_key_index) {
+                (Some(key_index), Some(new_key_index)) if key_index != new_key_index => {
+                    // The key frame is moved on top of another one, which is replaced.
+                    curve.keys[new_key_index].value = component;
+                    curve.keys.remove(key_index);
+                }
+                (Some(key_index), _) => {
+                    curve.keys[key_index].value = component;
+                    curve.move_key(key_index, time);
+                }
+                (None, Some(new_key_index)) => curve.keys[new_key_index].value = component,
+                (None, None) => {
+                    let kind = curve
+                        .keys
+                        .first()
+                        .map(|key| key.kind.clone())
+                        .unwrap_or(CurveKeyKind::Linear);
+                    curve.add_key(CurveKey::new(time, component, kind))
+                }
+            }
+        }
+        self.keyframe_times().iter().position(|t| *t == time)
+    }
+
+    /// Removes the key frame at the given index (in the list of [`Self::keyframes`]) from every curve and returns it.
+    /// Returns [`None`] if there's no such key frame. Removing the last key frame leaves the container with empty curves,
+    /// which produce default values.
+    pub fn remove_keyframe(&mut self, index: usize) -> Option<Keyframe> {
+        let time = *self.keyframe_times().get(index)?;
+        let keyframe = self.fetch(time).map(|value| Keyframe { time, value });
+        self.invalidate_constant_value();
+        for curve in self.curves.iter_mut() {
+            curve.keys.retain(|key| key.location != time);
+        }
+        keyframe
+    }
+
+    /// Replaces the rotation of every key frame of the container with the nearest axis-aligned rotation (see
+    /// [`TrackValue::snapped_to_axes`]), which quantizes the rotations to 90 degree steps. Does nothing if the container
+    /// does not produce [`TrackValue::UnitQuaternion`] values.
+    pub fn snap_rotations_to_axes(&mut self) {
+        if self.kind != TrackValueKind::UnitQuaternion {
+            return;
+        }
+
+        for (index, keyframe) in self.keyframes().into_iter().enumerate() {
+            self.set_keyframe(index, keyframe.time, &keyframe.value.snapped_to_axes());
+        }
+    }
+
+    /// Removes redundant key frames of the container (curve simplification). A key frame is redundant, if the values
+    /// interpolated between its neighbours match the original values of the container at the time positions of the key
+    /// frame and every key frame removed before it, with the given tolerance. The values are compared per curve using
+    /// the interpolation of each component (see [`Self::set_component_interpolation`]), so the tolerance is in the units
+    /// of the components (radians of Euler angles for rotations). The first and the last key frames are always
+    /// preserved. Returns the amount of removed key frames.
+    pub fn remove_redundant_keyframes(&mut self, tolerance: f32) -> usize {
+        let times = self.keyframe_times();
+        if times.len() <= 2 {
+            return 0;
+        }
+
+        let is_cubic = (0..self.curves.len())
+            .any(|index| self.component_interpolation(index) == ComponentInterpolation::Cubic);
+
+        // The curves are not modified until every key frame is checked, so they hold the original values. Indices
+        // (in the list of keys) of the preserved keys are stored per curve, because some curves could have no keys
+        // at some of the key frames.
+        let mut retained_keys = vec![Vec::new(); self.curves.len()];
+        let retain = |time: f32, retained_keys: &mut [Vec<usize>]| {
+            for (curve, retained_keys) in self.curves.iter().zip(retained_keys.iter_mut()) {
+                let index = curve.keys().partition_point(|key| key.location < time);
+                if curve
+                    .keys()
+                    .get(index)
+                    .is_some_and(|key| key.location == time)
+                {
+                    retained_keys.push(index);
+                }
+            }
+        };
+        retain(times[0], &mut retained_keys);
+
+        let mut redundant = vec![false; times.len()];
+        let mut removed = 0;
+        // Indices (in the list of key frames) of the key frames that were preserved.
+        let mut kept = vec![0];
+        for index in 1..times.len() - 1 {
+            // Removal of a key frame affects only a few segments around it, so each curve is simplified only in the
+            // neighbourhood of the key frame: up to three preserved keys before it and three original keys after it
+            // are enough to sample any interpolation in the checked time positions.
+            let candidates = self
+                .curves
+                .iter()
+                .zip(retained_keys.iter())
+                .map(|(curve, retained_keys)| {
+                    let keys = curve.keys();
+                    let next = keys.partition_point(|key| key.location <= times[index]);
+                    let before = retained_keys[retained_keys.len().saturating_sub(3)..]
+                        .iter()
+                        .map(|key| keys[*key].clone());
+                    let after = keys[next..(next + 3).min(keys.len())].iter().cloned();
+                    Curve::from(before.chain(after).collect::<Vec<_>>())
+                })
+                .collect::<Vec<_>>();
+
+            let fits = |time: f32| {
+                candidates.iter().enumerate().all(|(component, candidate)| {
+                    let candidate = self.sample_component(component, candidate, time);
+                    let original = self.component_at(component, time).unwrap_or_default();
+                    (candidate - original).abs() <= tolerance
+                })
+            };
+
+            let last_kept = *kept.last().unwrap();
+            let fits_window = if is_cubic {
+                // Tangents of Catmull-Rom splines depend on two neighbouring keys at each side, so the removal changes
+                // the curve from the preserved key frame before the last one up to the second key frame after the
+                // removed one. Values between the key frames are checked too, because the shape of the segments
+                // changes even if there were no key frames removed from them.
+                let window =
+                    &times[kept[kept.len().saturating_sub(2)]..=(index + 2).min(times.len() - 1)];
+                window.iter().all(|time| fits(*time))
+                    && window
+                        .windows(2)
+                        .all(|pair| fits((pair[0] + pair[1]) * 0.5))
+            } else {
+                // Other interpolations depend only on the neighbouring keys, so it is enough to check the time
+                // positions between the last preserved key frame and the next one.
+                times[last_kept + 1..=index].iter().all(|time| fits(*time))
+            };
+
+            if fits_window {
+                redundant[index] = true;
+                removed += 1;
+            } else {
+                kept.push(index);
+                retain(times[index], &mut retained_keys);
+            }
+        }
+
+        if removed > 0 {
+            self.invalidate_constant_value();
+            for curve in self.curves.iter_mut() {
+                curve.keys.retain(|key| {
+                    let index = times.partition_point(|time| *time < key.location);
+                    !(redundant[index] && times[index] == key.location)
+                });
+            }
+        }
+        removed
+    }
+
     /// Find a right-most key on one of the curves in the container and returns its position. This position
     /// can be treated as a maximum "length" of the container.
     pub fn time_length(&self) -> f32 {
@@ -195,3 +655,328 @@ impl TrackDataContainer {
         length
     }
 }
+
+#[cfg(test)]
+mod test {
+    use crate::{
+        container::{
+            ComponentInterpolation, InterpolationMode, Keyframe, TrackDataContainer, TrackValueKind,
+        },
+        core::{
+            algebra::{UnitQuaternion, Vector3},
+            math::curve::{Curve, CurveKey, CurveKeyKind},
+            reflect::prelude::*,
+            visitor::prelude::*,
+        },
+        value::TrackValue,
+    };
+
+    #[test]
+    fn test_keyframes_editing() {
+        let value = |x: f32| TrackValue::Vector3(Vector3::new(x, 0.0, 0.0));
+        let keyframe = |time: f32, x: f32| Keyframe {
+            time,
+            value: value(x),
+        };
+
+        let mut container = TrackDataContainer::new(TrackValueKind::Vector3);
+        assert!(container.keyframes().is_empty());
+
+        // Out-of-order insertion must keep the key frames sorted.
+        assert_eq!(
+            container.insert_keyframe(2.0, &value(2.0), CurveKeyKind::Linear),
+            Some(0)
+        );
+        assert_eq!(
+            container.insert_keyframe(1.0, &value(1.0), CurveKeyKind::Linear),
+            Some(0)
+        );
+        assert_eq!(
+            container.insert_keyframe(3.0, &value(3.0), CurveKeyKind::Linear),
+            Some(2)
+        );
+        assert_eq!(
+            container.insert_keyframe(1.0, &TrackValue::Real(1.0), CurveKeyKind::Linear),
+            None
+        );
+        assert_eq!(
+            container.keyframes(),
+            vec![keyframe(1.0, 1.0), keyframe(2.0, 2.0), keyframe(3.0, 3.0)]
+        );
+
+        // Moving a key frame past its neighbour re-sorts the key frames.
+        assert_eq!(container.set_keyframe(0, 2.5, &value(5.0)), Some(1));
+        assert_eq!(
+            container.keyframes(),
+            vec![keyframe(2.0, 2.0), keyframe(2.5, 5.0), keyframe(3.0, 3.0)]
+        );
+        assert_eq!(container.set_keyframe(3, 0.0, &value(0.0)), None);
+
+        assert_eq!(container.remove_keyframe(1), Some(keyframe(2.5, 5.0)));
+        assert_eq!(container.remove_keyframe(5), None);
+        assert_eq!(container.remove_keyframe(0), Some(keyframe(2.0, 2.0)));
+        assert_eq!(container.remove_keyframe(0), Some(keyframe(3.0, 3.0)));
+        assert!(container.keyframes().is_empty());
+        assert_eq!(container.remove_keyframe(0), None);
+    }
+
+    #[test]
+    fn test_rotation_keyframe() {
+        let rotation = UnitQuaternion::from_euler_angles(0.1, 0.2, 0.3);
+        let mut container = TrackDataContainer::new(TrackValueKind::UnitQuaternion);
+        container.insert_keyframe(
+            0.0,
+            &TrackValue::UnitQuaternion(rotation),
+            CurveKeyKind::Linear,
+        );
+        let TrackValue::UnitQuaternion(fetched) = container.keyframes()[0].value else {
+            unreachable!()
+        };
+        assert!(fetched.angle_to(&rotation) < 1.0e-5);
+    }
+
+    #[test]
+    fn test_remove_redundant_keyframes() {
+        let mut container = TrackDataContainer::new(TrackValueKind::Vector3);
+        for (time, x) in [(0.0, 0.0), (1.0, 1.0), (2.0, 2.0), (3.0, 3.001), (4.0, 1.0)] {
+            container.insert_keyframe(
+                time,
+                &TrackValue::Vector3(Vector3::new(x, 0.0, 0.0)),
+                CurveKeyKind::Linear,
+            );
+        }
+
+        // Nothing is removed with zero tolerance, except the exactly collinear key frame.
+        let mut exact = container.clone();
+        assert_eq!(exact.remove_redundant_keyframes(0.0), 1);
+        assert_eq!(exact.keyframe_times(), vec![0.0, 2.0, 3.0, 4.0]);
+
+        // The errors are checked against the original values, so they can't accumulate.
+        assert_eq!(container.remove_redundant_keyframes(0.01), 2);
+        assert_eq!(container.keyframe_times(), vec![0.0, 3.0, 4.0]);
+
+        let mut single = TrackDataContainer::new(TrackValueKind::Real);
+        single.insert_keyframe(0.0, &TrackValue::Real(1.0), CurveKeyKind::Linear);
+        single.insert_keyframe(1.0, &TrackValue::Real(1.0), CurveKeyKind::Linear);
+        assert_eq!(single.remove_redundant_keyframes(1.0), 0);
+    }
+
+    #[test]
+    fn test_remove_redundant_keyframes_with_component_interpolation() {
+        let keyframes = [(0.0, 0.0), (1.0, 1.0), (2.0, 2.0), (3.0, 3.0), (4.0, 0.0)];
+        let real = |interpolation| {
+            let mut container = TrackDataContainer::new(TrackValueKind::Real);
+            for (time, value) in keyframes {
+                container.insert_keyframe(time, &TrackValue::Real(value), CurveKeyKind::Linear);
+            }
+            container.set_component_interpolation(0, interpolation);
+            container
+        };
+
+        // Collinear keys are redundant only for linear interpolation, stepped interpolation holds their values.
+        let mut linear = real(ComponentInterpolation::Linear);
+        assert_eq!(linear.remove_redundant_keyframes(0.01), 2);
+        let mut step = real(ComponentInterpolation::Step);
+        assert_eq!(step.remove_redundant_keyframes(0.01), 0);
+
+        // Removal of a key frame changes tangents of its neighbours, the result must still match the original curve.
+        let original = real(ComponentInterpolation::Cubic);
+        let mut cubic = original.clone();
+        cubic.remove_redundant_keyframes(0.01);
+        for i in 0..=400 {
+            let time = i as f32 * 0.01;
+            let (Some(TrackValue::Real(a)), Some(TrackValue::Real(b))) =
+                (original.fetch(time), cubic.fetch(time))
+            else {
+                unreachable!()
+            };
+            assert!((a - b).abs() <= 0.02, "{time}: {a} vs {b}");
+        }
+    }
+
+    #[test]
+    fn test_remove_redundant_keyframes_of_sparse_curves() {
+        let curve = |keys: &[(f32, f32)]| {
+            Curve::from(
+                keys.iter()
+                    .map(|(time, value)| CurveKey::new(*time, *value, CurveKeyKind::Linear))
+                    .collect::<Vec<_>>(),
+            )
+        };
+        let mut original = TrackDataContainer::new(TrackValueKind::Vector2);
+        // The second curve has no keys at some of the key frames.
+        original.add_curve(curve(&[
+            (0.0, 0.0),
+            (1.0, 1.0),
+            (2.0, 2.0),
+            (3.0, 3.0),
+            (4.0, 2.0),
+        ]));
+        original.add_curve(curve(&[(0.0, 0.0), (2.0, 4.0), (4.0, 0.0), (5.0, 0.0)]));
+
+        for interpolation in [
+            ComponentInterpolation::Linear,
+            ComponentInterpolation::Cubic,
+        ] {
+            let mut container = original.clone();
+            container.set_component_interpolation(0, interpolation);
+            container.set_component_interpolation(1, interpolation);
+            let reference = container.clone();
+
+            assert!(container.remove_redundant_keyframes(0.01) > 0);
+            for i in 0..=500 {
+                let time = i as f32 * 0.01;
+                let (Some(TrackValue::Vector2(a)), Some(TrackValue::Vector2(b))) =
+                    (reference.fetch(time), container.fetch(time))
+                else {
+                    unreachable!()
+                };
+                assert!((a - b).amax() <= 0.02, "{time}: {a:?} vs {b:?}");
+            }
+        }
+    }
+
+    #[test]
+    fn test_component_interpolation() {
+        let mut container = TrackDataContainer::new(TrackValueKind::Vector3);
+        for (time, value) in [(0.0, 0.0), (1.0, 1.0), (2.0, 4.0)] {
+            container.insert_keyframe(
+                time,
+                &TrackValue::Vector3(Vector3::repeat(value)),
+                CurveKeyKind::Linear,
+            );
+        }
+        container.set_component_interpolation(1, ComponentInterpolation::Cubic);
+        container.set_component_interpolation(2, ComponentInterpolation::Step);
+        assert_eq!(
+            container.component_interpolations(),
+            vec![
+                ComponentInterpolation::KeyDefined,
+                ComponentInterpolation::Cubic,
+                ComponentInterpolation::Step
+            ]
+        );
+
+        let TrackValue::Vector3(value) = container.fetch(0.5).unwrap() else {
+            unreachable!()
+        };
+        assert_eq!(value.x, 0.5);
+        // Catmull-Rom tangents: 1.0 at the first key (one-sided) and 2.0 at the second key.
+        assert_eq!(value.y, 0.375);
+        assert_eq!(value.z, 0.0);
+
+        // Keys are passed through exactly.
+        assert_eq!(
+            container.fetch(1.0),
+            Some(TrackValue::Vector3(Vector3::repeat(1.0)))
+        );
+
+        // The modes are serialized per component.
+        let mut visitor = Visitor::new();
+        container.visit("Container", &mut visitor).unwrap();
+        let data = visitor.save_binary_to_vec().unwrap();
+        let mut visitor = Visitor::load_from_memory(&data).unwrap();
+        let mut loaded = TrackDataContainer::default();
+        loaded.visit("Container", &mut visitor).unwrap();
+        assert_eq!(loaded, container);
+
+        assert_eq!(ComponentInterpolation::Step.interpolate(1.0, 2.0, 0.9), 1.0);
+        assert_eq!(
+            ComponentInterpolation::Cubic.interpolate(1.0, 2.0, 0.5),
+            1.5
+        );
+    }
+
+    #[test]
+    fn test_constant_value() {
+        let value = |x: f32| TrackValue::Vector3(Vector3::new(x, 1.0, 2.0));
+
+        let mut container = TrackDataContainer::new(TrackValueKind::Vector3);
+        assert_eq!(container.constant_value(), None);
+
+        container.insert_keyframe(1.0, &value(3.0), CurveKeyKind::Linear);
+        assert_eq!(container.constant_value(), Some(&value(3.0)));
+        assert_eq!(container.fetch(10.0), Some(value(3.0)));
+
+        container.insert_keyframe(2.0, &value(3.0), CurveKeyKind::Linear);
+        assert_eq!(container.constant_value(), Some(&value(3.0)));
+
+        container.insert_keyframe(3.0, &value(4.0), CurveKeyKind::Linear);
+        assert_eq!(container.constant_value(), None);
+        assert_eq!(container.fetch(2.5), Some(value(3.5)));
+
+        assert!(container.remove_keyframe(2).is_some());
+        assert_eq!(container.constant_value(), Some(&value(3.0)));
+
+        // Cubic keys with non-zero tangents overshoot between equal values.
+        container.curve_mut(0).unwrap().keys[0].kind = CurveKeyKind::Cubic {
+            left_tangent: 1.0,
+            right_tangent: 1.0,
+        };
+        assert_eq!(container.constant_value(), None);
+
+        // The value is detected again after loading.
+        let mut visitor = Visitor::new();
+        container.visit("Container", &mut visitor).unwrap();
+        let data = visitor.save_binary_to_vec().unwrap();
+        let mut visitor = Visitor::load_from_memory(&data).unwrap();
+        let mut loaded = TrackDataContainer::new(TrackValueKind::Vector3);
+        loaded.insert_keyframe(0.0, &value(5.0), CurveKeyKind::Linear);
+        assert!(loaded.constant_value().is_some());
+        loaded.visit("Container", &mut visitor).unwrap();
+        assert_eq!(loaded.constant_value(), None);
+        assert_eq!(loaded, container);
+    }
+
+    #[test]
+    fn test_constant_value_reflection() {
+        let value = |x: f32| TrackValue::Vector3(Vector3::new(x, 1.0, 2.0));
+
+        let mut container = TrackDataContainer::new(TrackValueKind::Vector3);
+        container.insert_keyframe(0.0, &value(3.0), CurveKeyKind::Linear);
+        assert_eq!(container.fetch(0.0), Some(value(3.0)));
+
+        // Curves and the kind can't be edited through reflection, it would bypass invalidation of the cached value.
+        for path in ["curves[0].keys[0].value", "kind"] {
+            let mut resolved = false;
+            container.resolve_path_mut(path, &mut |result| resolved = result.is_ok());
+            assert!(!resolved);
+        }
+
+        // Reflected fields can be edited, the cached value remains valid.
+        container.set_field(
+            "mode",
+            Box::new(InterpolationMode::ShortPath),
+            &mut |result| assert!(result.is_ok()),
+        );
+        assert_eq!(container.fetch(0.0), Some(value(3.0)));
+
+        container.curves_mut()[0].keys[0].value = 4.0;
+        assert_eq!(container.fetch(0.0), Some(value(4.0)));
+    }
+
+    #[test]
+    fn test_snap_rotations_to_axes() {
+        let mut container = TrackDataContainer::new(TrackValueKind::UnitQuaternion);
+        let rotation = |x: f32, y: f32| {
+            TrackValue::UnitQuaternion(UnitQuaternion::from_euler_angles(x, y, 0.0))
+        };
+        container.insert_keyframe(0.0, &rotation(0.1, 1.4), CurveKeyKind::Linear);
+        container.insert_keyframe(1.0, &rotation(3.0, -0.2), CurveKeyKind::Linear);
+
+        container.snap_rotations_to_axes();
+
+        let expected = [
+            UnitQuaternion::from_euler_angles(0.0, std::f32::consts::FRAC_PI_2, 0.0),
+            UnitQuaternion::from_euler_angles(std::f32::consts::PI, 0.0, 0.0),
+        ];
+        let keyframes = container.keyframes();
+        assert_eq!(keyframes.len(), 2);
+        for (keyframe, expected) in keyframes.iter().zip(expected) {
+            let TrackValue::UnitQuaternion(rotation) = keyframe.value else {
+                unreachable!()
+            };
+            assert!(rotation.angle_to(&expected) < 1.0e-3);
+        }
+    }
+}
@@ -0,0 +1,169 @@
+//! Export of animations to a human-readable JSON, that could be used for debugging, diffing of two animations or
+//! verification of imported animations. See [`Animation::export_json`] docs for more info.
+
+use crate::{
+    track::Track,
+    value::{TrackValue, ValueBinding},
+    Animation, EntityId,
+};
+use std::fmt::Write;
+
+fn write_json_string(out: &mut String, string: &str) {
+    out.push('"');
+    for c in string.chars() {
+        match c {
+            '"' => out.push_str("\\\""),
+            '\\' => out.push_str("\\\\"),
+            '\n' => out.push_str("\\n"),
+            '\r' => out.push_str("\\r"),
+            '\t' => out.push_str("\\t"),
+            c if c.is_control() => {
+                let _ = write!(out, "\\u{:04x}", c as u32);
+            }
+            c => out.push(c),
+        }
+    }
+    out.push('"');
+}
+
+fn write_json_number(out: &mut String, number: f32) {
+    // JSON does not support NaN and infinite numbers.
+    if number.is_finite() {
+        let _ = write!(out, "{}", number);
+    } else {
+        out.push_str("null");
+    }
+}
+
+fn write_json_array(out: &mut String, numbers: &[f32]) {
+    out.push('[');
+    for (i, number) in numbers.iter().enumerate() {
+        if i > 0 {
+            out.push_str(", ");
+        }
+        write_json_number(out, *number);
+    }
+    out.push(']');
+}
+
+fn write_track_value(out: &mut String, value: &TrackValue) {
+    match value {
+        TrackValue::Real(v) => write_json_number(out, *v),
+        TrackValue::Vector2(v) => write_json_array(out, v.as_slice()),
+        TrackValue::Vector3(v) => write_json_array(out, v.as_slice()),
+        TrackValue::Vector4(v) => write_json_array(out, v.as_slice()),
+        // Quaternions are written as [i, j, k, w].
+        TrackValue::UnitQuaternion(v) => write_json_array(out, v.coords.as_slice()),
+    }
+}
+
+fn write_track<T: EntityId>(out: &mut String, track: &Track<T>) {
+    out.push_str("    {\n      \"target\": ");
+    write_json_string(out, &format!("{:?}", track.target()));
+    out.push_str(",\n      \"binding\": ");
+    write_json_string(out, &track.binding().to_string());
+    if let ValueBinding::Property { value_type, .. } = track.binding() {
+        out.push_str(",\n      \"value_type\": ");
+        write_json_string(out, &format!("{:?}", value_type));
+    }
+    let _ = write!(out, ",\n      \"enabled\": {}", track.is_enabled());
+    out.push_str(",\n      \"keyframes\": [");
+    for (i, keyframe) in track.keyframes().iter().enumerate() {
+        out.push_str(if i > 0 { ",\n" } else { "\n" });
+        out.push_str("        { \"time\": ");
+        write_json_number(out, keyframe.time);
+        out.push_str(", \"value\": ");
+        write_track_value(out, &keyframe.value);
+        out.push_str(" }");
+    }
+    out.push_str("\n      ]\n    }");
+}
+
+impl<T: EntityId> Animation<T> {
+    /// Exports the tracks of the animation (their targets, bindings and key frames) to a human-readable JSON. It is
+    /// intended for debugging: to compare two animations, to verify correctness of an imported animation, or to
+    /// inspect the animation in external tools. Targets are written using their [`std::fmt::Debug`] representation,
+    /// vectors are written as arrays of their components, quaternions - as `[i, j, k, w]` arrays. Non-finite numbers
+    /// are written as `null`. The output cannot be imported back.
+    pub fn export_json(&self) -> String {
+        let mut out = String::new();
+        out.push_str("{\n  \"name\": ");
+        write_json_string(&mut out, self.name());
+        let time_slice = self.time_slice();
+        out.push_str(",\n  \"time_slice\": ");
+        write_json_array(&mut out, &[time_slice.start, time_slice.end]);
+        out.push_str(",\n  \"tracks\": [");
+        for (i, track) in self.tracks().iter().enumerate() {
+            out.push_str(if i > 0 { ",\n" } else { "\n" });
+            write_track(&mut out, track);
+        }
+        out.push_str("\n  ]\n}\n");
+        out
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{
+        container::{TrackDataContainer, TrackValueKind},
+        core::{
+            algebra::{UnitQuaternion, Vector3},
+            math::curve::CurveKeyKind,
+            pool::ErasedHandle,
+        },
+        track::Track,
+        value::{TrackValue, ValueBinding, ValueType},
+        Animation,
+    };
+
+    #[test]
+    fn test_export_json() {
+        let mut animation = Animation::<ErasedHandle>::default();
+        animation.set_name("Walk \"fast\"");
+        animation.set_time_slice(0.0..1.0);
+
+        let mut frames = TrackDataContainer::new(TrackValueKind::Vector3);
+        frames.insert_keyframe(
+            0.0,
+            &TrackValue::Vector3(Vector3::new(1.0, 2.0, 3.0)),
+            CurveKeyKind::Linear,
+        );
+        frames.insert_keyframe(
+            1.0,
+            &TrackValue::Vector3(Vector3::new(4.0, 5.0, 6.0)),
+            CurveKeyKind::Linear,
+        );
+        animation.add_track(
+            Track::new(frames, ValueBinding::Position).with_target(ErasedHandle::new(1, 1)),
+        );
+
+        let mut frames = TrackDataContainer::new(TrackValueKind::UnitQuaternion);
+        frames.insert_keyframe(
+            0.5,
+            &TrackValue::UnitQuaternion(UnitQuaternion::identity()),
+            CurveKeyKind::Linear,
+        );
+        animation.add_track(Track::new(frames, ValueBinding::Rotation));
+
+        let mut frames = TrackDataContainer::new(TrackValueKind::Real);
+        frames.insert_keyframe(0.25, &TrackValue::Real(f32::NAN), CurveKeyKind::Linear);
+        animation.add_track(Track::new(
+            frames,
+            ValueBinding::Property {
+                name: "weight".to_string(),
+                value_type: ValueType::F32,
+            },
+        ));
+
+        let json = animation.export_json();
+        assert!(json.contains("\"name\": \"Walk \\\"fast\\\"\""));
+        assert!(json.contains("\"time_slice\": [0, 1]"));
+        assert!(json.contains("\"binding\": \"Position\""));
+        assert!(json.contains("{ \"time\": 0, \"value\": [1, 2, 3] }"));
+        assert!(json.contains("{ \"time\": 1, \"value\": [4, 5, 6] }"));
+        assert!(json.contains("{ \"time\": 0.5, \"value\": [0, 0, 0, 1] }"));
+        assert!(json.contains("\"binding\": \"weight\""));
+        assert!(json.contains("\"value_type\": \"F32\""));
+        assert!(json.contains("{ \"time\": 0.25, \"value\": null }"));
+    }
+}
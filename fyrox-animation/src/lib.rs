@@ -18,7 +18,7 @@ use core::ImmutableString;
 use fyrox_core::{NameProvider, TypeUuidProvider};
 use std::hash::Hash;
 use std::{
-    collections::VecDeque,
+    collections::{HashMap, VecDeque},
     fmt::Debug,
     ops::{Index, IndexMut, Range},
 };
@@ -29,11 +29,13 @@ use fyrox_core::uuid::uuid;
 
 pub use pose::{AnimationPose, NodePose};
 pub use signal::{AnimationEvent, AnimationSignal};
-use value::{nlerp, TrackValue, ValueBinding};
+use value::{nlerp, BoundValue, BoundValueCollection, TrackValue, ValueBinding};
 
 pub mod container;
+pub mod export;
 pub mod machine;
 pub mod pose;
+pub mod retarget;
 pub mod signal;
 pub mod spritesheet;
 pub mod track;
@@ -173,6 +175,11 @@ pub struct Animation<T: EntityId> {
     time_slice: Range<f32>,
     speed: f32,
     looped: bool,
+    #[visit(optional)]
+    #[reflect(min_value = 0.0, step = 0.05)]
+    loop_crossfade: f32,
+    #[visit(optional)]
+    derive_rotation_from_position: bool,
     enabled: bool,
     signals: Vec<AnimationSignal>,
 
@@ -183,6 +190,11 @@ pub struct Animation<T: EntityId> {
     #[visit(skip)]
     root_motion: Option<RootMotion>,
 
+    // Non-serialized
+    #[reflect(hidden)]
+    #[visit(skip)]
+    binding_remap: HashMap<ValueBinding, ValueBinding>,
+
     // Non-serialized
     #[reflect(hidden)]
     #[visit(skip)]
@@ -262,6 +274,8 @@ impl<T: EntityId> Clone for Animation<T> {
             speed: self.speed,
             time_position: self.time_position,
             looped: self.looped,
+            loop_crossfade: self.loop_crossfade,
+            derive_rotation_from_position: self.derive_rotation_from_position,
             enabled: self.enabled,
             pose: Default::default(),
             signals: self.signals.clone(),
@@ -269,6 +283,7 @@ impl<T: EntityId> Clone for Animation<T> {
             events: Default::default(),
             time_slice: self.time_slice.clone(),
             root_motion: self.root_motion.clone(),
+            binding_remap: self.binding_remap.clone(),
         }
     }
 }
@@ -333,13 +348,16 @@ impl<T: EntityId> Animation<T> {
     /// - If the animation is **not** looping and the new time position is outside of the time slice of the animation,
     /// then the actual time position will be clamped to the time clice of the animation.
     pub fn set_time_position(&mut self, time: f32) -> &mut Self {
+        self.time_position = self.fit_time_to_slice(time);
+        self
+    }
+
+    fn fit_time_to_slice(&self, time: f32) -> f32 {
         if self.looped {
-            self.time_position = wrapf(time, self.time_slice.start, self.time_slice.end);
+            wrapf(time, self.time_slice.start, self.time_slice.end)
         } else {
-            self.time_position = time.clamp(self.time_slice.start, self.time_slice.end);
+            time.clamp(self.time_slice.start, self.time_slice.end)
         }
-
-        self
     }
 
     /// Sets new time slice of the animation in seconds. It defines a time interval in which the animation will
@@ -404,7 +422,7 @@ impl<T: EntityId> Animation<T> {
         fn fetch_position_at_time<T: EntityId>(tracks: &[Track<T>], time: f32) -> Vector3<f32> {
             tracks
                 .iter()
-                .find(|track| track.binding() == &ValueBinding::Position)
+                .find(|track| track.is_enabled() && track.binding() == &ValueBinding::Position)
                 .and_then(|track| track.fetch(time))
                 .and_then(|value| {
                     if let TrackValue::Vector3(position) = value.value {
@@ -422,7 +440,7 @@ impl<T: EntityId> Animation<T> {
         ) -> UnitQuaternion<f32> {
             tracks
                 .iter()
-                .find(|track| track.binding() == &ValueBinding::Rotation)
+                .find(|track| track.is_enabled() && track.binding() == &ValueBinding::Rotation)
                 .and_then(|track| track.fetch(time))
                 .and_then(|value| {
                     if let TrackValue::UnitQuaternion(rotation) = value.value {
@@ -600,6 +618,24 @@ impl<T: EntityId> Animation<T> {
         self.time_position
     }
 
+    /// Returns current time position of the animation normalized to `[0; 1]` range relative to current time slice
+    /// of the animation. It could be useful to build timeline or progress widgets. Always returns `0.0` for
+    /// animations with zero length.
+    pub fn normalized_time_position(&self) -> f32 {
+        let length = self.length();
+        if length > 0.0 {
+            ((self.time_position - self.time_slice.start) / length).clamp(0.0, 1.0)
+        } else {
+            0.0
+        }
+    }
+
+    /// Returns `true` if the animation is being played at the moment, which means that it is enabled, has non-zero
+    /// speed and it has not ended yet. See [`Self::has_ended`] for more info.
+    pub fn is_playing(&self) -> bool {
+        self.enabled && self.speed != 0.0 && !self.has_ended()
+    }
+
     /// Sets new speed multiplier for the animation. By default it is set to 1.0. Negative values can be used
     /// to play the animation in reverse.
     pub fn set_speed(&mut self, speed: f32) -> &mut Self {
@@ -623,6 +659,101 @@ impl<T: EntityId> Animation<T> {
         self.looped
     }
 
+    /// Sets a duration (in seconds) of a window at the end of the time slice, in which the pose of the animation is
+    /// smoothly blended toward the pose at the start of the time slice. It removes the "pop" at the loop boundary of
+    /// looping animations, whose last frame differs from the first one. The duration is limited by the length of
+    /// the animation, zero (default) means hard looping. Has no effect on non-looping animations.
+    pub fn set_loop_crossfade(&mut self, duration: f32) -> &mut Self {
+        self.loop_crossfade = duration.max(0.0);
+        self
+    }
+
+    /// Returns current duration of the loop crossfade. See [`Self::set_loop_crossfade`] for more info.
+    pub fn loop_crossfade(&self) -> f32 {
+        self.loop_crossfade
+    }
+
+    /// Enables or disables derivation of rotations from positions. When enabled, every target that has a position
+    /// track is rotated to face its direction of travel, which is computed from the tangent of the position track.
+    /// The derived rotation replaces the rotation track of the target (if any), so there's no need to key rotations
+    /// by hand for directional motion, such as projectiles.
+    ///
+    /// # Up vector convention
+    ///
+    /// The derived rotation maps the local **+Z** axis of the target to the direction of travel, while the local
+    /// **+Y** axis is kept as close as possible to the **+Y** axis of the parent space (up). If the target moves
+    /// (almost) vertically or stands still, the direction is undefined and the rotation from the rotation track is
+    /// used (or no rotation at all, if there's no such track).
+    pub fn set_derive_rotation_from_position(&mut self, enabled: bool) -> &mut Self {
+        self.derive_rotation_from_position = enabled;
+        self
+    }
+
+    /// Returns `true` if rotations are derived from positions, `false` - otherwise. See
+    /// [`Self::set_derive_rotation_from_position`] for more info.
+    pub fn is_rotation_derived_from_position(&self) -> bool {
+        self.derive_rotation_from_position
+    }
+
+    /// Computes the rotation that faces the direction of travel of the given target at the given time. See
+    /// [`Self::set_derive_rotation_from_position`] for more info.
+    fn derived_rotation(&self, target: T, time: f32) -> Option<UnitQuaternion<f32>> {
+        // A half of the time step for the central difference, that is used to calculate the tangent.
+        const HALF_STEP: f32 = 0.5 / 240.0;
+
+        // Only the position track of the target is sampled, other tracks do not affect the direction of travel.
+        let track = self.tracks.iter().find(|track| {
+            track.is_enabled()
+                && track.target() == target
+                && *self.remapped_binding(track.binding()) == ValueBinding::Position
+        })?;
+        let position_at = |time: f32| match track.fetch(time)?.value {
+            TrackValue::Vector3(position) => Some(position),
+            _ => None,
+        };
+
+        let direction = position_at(time + HALF_STEP)? - position_at(time - HALF_STEP)?;
+        let direction = direction.try_normalize(f32::EPSILON)?;
+        if direction.cross(&Vector3::y()).norm() <= 1.0e-4 {
+            return None;
+        }
+        Some(UnitQuaternion::face_towards(&direction, &Vector3::y()))
+    }
+
+    /// Replaces the rotation in the given values with the derived one (if any), see
+    /// [`Self::set_derive_rotation_from_position`] for more info.
+    fn apply_derived_rotation(&self, target: T, time: f32, values: &mut BoundValueCollection) {
+        if !self.derive_rotation_from_position {
+            return;
+        }
+
+        if let Some(rotation) = self.derived_rotation(target, time) {
+            let value = TrackValue::UnitQuaternion(rotation);
+            if let Some(bound_value) = values
+                .values
+                .iter_mut()
+                .find(|bound_value| bound_value.binding == ValueBinding::Rotation)
+            {
+                bound_value.value = value;
+            } else {
+                values.insert(BoundValue {
+                    binding: ValueBinding::Rotation,
+                    value,
+                });
+            }
+        }
+    }
+
+    /// Returns the weight of the start pose at the given time, if the time is in the loop crossfade window.
+    fn loop_crossfade_weight(&self, time: f32) -> Option<f32> {
+        let window = self.loop_crossfade.min(self.length());
+        if !self.looped || window <= 0.0 {
+            return None;
+        }
+        let window_start = self.time_slice.end - window;
+        (time > window_start).then(|| ((time - window_start) / window).min(1.0))
+    }
+
     /// Returns `true` if the animation was played until the end of current time slice of the animation, `false` -
     /// otherwise. Looping animations will always return `false`.
     pub fn has_ended(&self) -> bool {
@@ -739,15 +870,243 @@ impl<T: EntityId> Animation<T> {
         self.tracks.clear();
     }
 
-    fn update_pose(&mut self) {
-        self.pose.reset();
+    /// Reverses the animation in place: key frames of every track and the signals are mirrored in time around the
+    /// center of the time slice of the animation, so the animation will look like it is played backwards. Tangents
+    /// of cubic key frames are flipped accordingly (see [`crate::core::math::curve::Curve::mirror`] docs for more
+    /// info). Current time position is left unchanged. This is useful to create a reversed version of an animation
+    /// without re-authoring it, for example a "close" animation from an "open" one.
+    pub fn reverse(&mut self) {
+        let center = (self.time_slice.start + self.time_slice.end) * 0.5;
+
+        for track in self.tracks.iter_mut() {
+            for curve in track.data_container_mut().curves_mut() {
+                curve.mirror(center);
+            }
+        }
+
+        for signal in self.signals.iter_mut() {
+            signal.time = 2.0 * center - signal.time;
+        }
+    }
+
+    /// Multiplies every position key of the animation (tracks with [`ValueBinding::Position`] binding and
+    /// [`TrackValueKind::Vector3`](container::TrackValueKind::Vector3) values) by the given factor. Rotations, scale
+    /// and properties are left untouched, as well as root motion, which is extracted from the positions at sampling.
+    /// It is useful to fix animations, that were imported with different units, for example centimeters instead of
+    /// meters (use `0.01` factor in this case).
+    pub fn scale_positions(&mut self, factor: f32) {
+        for track in self.tracks.iter_mut() {
+            if *track.binding() != ValueBinding::Position
+                || track.data_container().value_kind() != container::TrackValueKind::Vector3
+            {
+                continue;
+            }
+
+            for curve in track.data_container_mut().curves_mut() {
+                curve.scale_values(factor);
+            }
+        }
+    }
+
+    /// Creates a reversed copy of the animation. See [`Self::reverse`] docs for more info.
+    pub fn reversed(&self) -> Self {
+        let mut reversed = self.clone();
+        reversed.reverse();
+        reversed
+    }
+
+    /// Sets a new binding remapping table, that redirects bindings of the tracks to some other bindings when sampling
+    /// the animation. For example, it could redirect `Property("old_name")` to `Property("new_name")`, which allows you
+    /// to reuse the same animation for objects whose property names differ. Tracks whose bindings are not in the table
+    /// are left untouched. The table is empty by default and it is not serialized.
+    pub fn set_binding_remap(&mut self, remap: HashMap<ValueBinding, ValueBinding>) {
+        self.binding_remap = remap;
+    }
+
+    /// Returns a reference to the current binding remapping table.
+    pub fn binding_remap(&self) -> &HashMap<ValueBinding, ValueBinding> {
+        &self.binding_remap
+    }
+
+    fn fetch_remapped(&self, track: &Track<T>, time: f32) -> Option<BoundValue> {
+        let mut bound_value = track.fetch(time)?;
+        if !self.binding_remap.is_empty() {
+            if let Some(binding) = self.binding_remap.get(&bound_value.binding) {
+                bound_value.binding = binding.clone();
+            }
+        }
+        Some(bound_value)
+    }
+
+    fn remapped_binding<'a>(&'a self, binding: &'a ValueBinding) -> &'a ValueBinding {
+        self.binding_remap.get(binding).unwrap_or(binding)
+    }
+
+    fn sample_tracks_into(&self, time: f32, pose: &mut AnimationPose<T>, rotations_only: bool) {
+        pose.reset();
+        for track in self.tracks.iter() {
+            if !track.is_enabled()
+                || (rotations_only
+                    && *self.remapped_binding(track.binding()) != ValueBinding::Rotation)
+            {
+                continue;
+            }
+
+            if let Some(bound_value) = self.fetch_remapped(track, time) {
+                pose.add_to_node_pose(track.target(), bound_value, track.weight());
+            }
+        }
+
+        if self.derive_rotation_from_position {
+            if rotations_only {
+                // Position tracks were skipped, but their targets still must receive derived rotations.
+                for track in self.tracks.iter() {
+                    if track.is_enabled()
+                        && *self.remapped_binding(track.binding()) == ValueBinding::Position
+                    {
+                        let node = track.target();
+                        pose.poses_mut()
+                            .entry(node)
+                            .or_insert_with(|| NodePose::new(node));
+                    }
+                }
+            }
+
+            for (node, node_pose) in pose.poses_mut().iter_mut() {
+                self.apply_derived_rotation(*node, time, &mut node_pose.values);
+            }
+
+            if rotations_only {
+                pose.poses_mut()
+                    .retain(|_, node_pose| !node_pose.values.values.is_empty());
+            }
+        }
+    }
+
+    fn sample_into(&self, time: f32, pose: &mut AnimationPose<T>, rotations_only: bool) {
+        self.sample_tracks_into(time, pose, rotations_only);
+
+        if let Some(weight) = self.loop_crossfade_weight(time) {
+            let mut start_pose = AnimationPose::default();
+            self.sample_tracks_into(self.time_slice.start, &mut start_pose, rotations_only);
+            for (node, node_pose) in pose.poses_mut().iter_mut() {
+                if let Some(start_node_pose) = start_pose.poses().get(node) {
+                    node_pose.blend_with(start_node_pose, weight);
+                }
+            }
+        }
+    }
+
+    fn sample_target_tracks(&self, target: T, time: f32) -> BoundValueCollection {
+        let mut values = BoundValueCollection::default();
         for track in self.tracks.iter() {
-            if track.is_enabled() {
-                if let Some(bound_value) = track.fetch(self.time_position) {
-                    self.pose.add_to_node_pose(track.target(), bound_value);
+            if track.is_enabled() && track.target() == target {
+                if let Some(bound_value) = self.fetch_remapped(track, time) {
+                    values.insert(bound_value);
                 }
             }
         }
+        self.apply_derived_rotation(target, time, &mut values);
+        values
+    }
+
+    fn update_pose(&mut self) {
+        let mut pose = std::mem::take(&mut self.pose);
+        self.sample_into(self.time_position, &mut pose, false);
+        self.pose = pose;
+    }
+
+    /// Samples every enabled track of the animation at the given (possibly fractional) time and returns the resulting
+    /// pose, without modifying the state of the animation. Values between keyframes are interpolated, times before the
+    /// first keyframe or after the last keyframe of a track are clamped to the respective keyframe. This is useful for
+    /// sub-stepping or motion blur, when the animation must be evaluated at times that differ from its time position.
+    pub fn sample(&self, time: f32) -> AnimationPose<T> {
+        let mut pose = AnimationPose::default();
+        self.sample_into(time, &mut pose, false);
+        pose
+    }
+
+    /// Same as [`Self::sample`], but samples only the tracks with [`ValueBinding::Rotation`] binding (after
+    /// remapping, see [`Self::set_binding_remap`]), so the resulting pose contains only rotations. Other tracks are
+    /// not sampled at all, which makes this method cheaper than [`Self::sample`] when only rotations are needed (for
+    /// example, for aiming or look-at systems).
+    pub fn sample_rotations(&self, time: f32) -> AnimationPose<T> {
+        let mut pose = AnimationPose::default();
+        self.sample_into(time, &mut pose, true);
+        pose
+    }
+
+    /// Same as [`Self::sample`], but returns values only for the given target.
+    pub fn sample_target(&self, target: T, time: f32) -> BoundValueCollection {
+        let mut values = self.sample_target_tracks(target, time);
+        if let Some(weight) = self.loop_crossfade_weight(time) {
+            values.blend_with(
+                &self.sample_target_tracks(target, self.time_slice.start),
+                weight,
+            );
+        }
+        values
+    }
+
+    /// Samples the given target at multiple times around the given time: `time + offset` for every offset. The times
+    /// are wrapped (for looping animations) or clamped to the time slice of the animation, the same way as it is done
+    /// by [`Self::set_time_position`]. The result contains one collection per offset, in the same order. This is useful
+    /// for onion skinning, when the poses at previous and next frames are shown as semi-transparent "ghosts".
+    pub fn sample_target_at_offsets(
+        &self,
+        target: T,
+        time: f32,
+        offsets: &[f32],
+    ) -> Vec<BoundValueCollection> {
+        offsets
+            .iter()
+            .map(|offset| self.sample_target(target, self.fit_time_to_slice(time + offset)))
+            .collect()
+    }
+
+    /// Samples the animation at `sample_count` evenly spaced sub-times within the frame that starts at the given
+    /// time and lasts `frame_duration` seconds: `time + frame_duration * i / sample_count` for `i` in
+    /// `0..sample_count`. The times are wrapped (for looping animations) or clamped to the time slice of the animation,
+    /// the same way as it is done by [`Self::set_time_position`]. The result contains the poses in time order and it
+    /// could be used to render motion blur by accumulating multiple sub-frames. See [`Self::sample_frame_averaged`] if
+    /// you need a single time-averaged pose.
+    pub fn sample_frame(
+        &self,
+        time: f32,
+        frame_duration: f32,
+        sample_count: usize,
+    ) -> Vec<AnimationPose<T>> {
+        (0..sample_count)
+            .map(|i| {
+                let sub_time = time + frame_duration * i as f32 / sample_count as f32;
+                self.sample(self.fit_time_to_slice(sub_time))
+            })
+            .collect()
+    }
+
+    /// Same as [`Self::sample_frame`], but returns a single pose, that is the average of all the sub-sampled poses
+    /// (each sub-sample has equal weight). Rotations are averaged using nlerp, see
+    /// [`value::TrackValue::blend_with`] for more info. At least one sub-sample is taken.
+    pub fn sample_frame_averaged(
+        &self,
+        time: f32,
+        frame_duration: f32,
+        sample_count: usize,
+    ) -> AnimationPose<T> {
+        let mut samples = self
+            .sample_frame(time, frame_duration, sample_count.max(1))
+            .into_iter();
+        let mut average = samples.next().unwrap_or_default();
+        for (i, sample) in samples.enumerate() {
+            // Running average, the weight of the new sample is 1 / (amount of samples so far).
+            let weight = 1.0 / (i + 2) as f32;
+            for (node, node_pose) in average.poses_mut().iter_mut() {
+                if let Some(sample_node_pose) = sample.poses().get(node) {
+                    node_pose.blend_with(sample_node_pose, weight);
+                }
+            }
+        }
+        average
     }
 
     /// Returns current pose of the animation (a final result that can be applied to a scene graph).
@@ -765,12 +1124,15 @@ impl<T: EntityId> Default for Animation<T> {
             time_position: 0.0,
             enabled: true,
             looped: true,
+            loop_crossfade: 0.0,
+            derive_rotation_from_position: false,
             pose: Default::default(),
             signals: Default::default(),
             root_motion_settings: None,
             events: Default::default(),
             time_slice: Default::default(),
             root_motion: None,
+            binding_remap: Default::default(),
         }
     }
 }
@@ -961,3 +1323,440 @@ impl<T: EntityId> IndexMut<Handle<Animation<T>>> for AnimationContainer<T> {
         &mut self.pool[index]
     }
 }
+
+#[cfg(test)]
+mod test {
+    use crate::{
+        container::{TrackDataContainer, TrackValueKind},
+        core::{
+            algebra::{UnitQuaternion, Vector3},
+            math::curve::{Curve, CurveKey, CurveKeyKind},
+            pool::ErasedHandle,
+        },
+        track::Track,
+        value::{TrackValue, ValueBinding, ValueType},
+        Animation, AnimationSignal,
+    };
+    use std::collections::HashMap;
+
+    fn position_track(target: ErasedHandle) -> Track<ErasedHandle> {
+        let mut frames = TrackDataContainer::new(TrackValueKind::Vector3);
+        frames.curves_mut()[0] = Curve::from(vec![
+            CurveKey::new(1.0, 0.0, CurveKeyKind::Linear),
+            CurveKey::new(2.0, 10.0, CurveKeyKind::Linear),
+        ]);
+        Track::new(frames, ValueBinding::Position).with_target(target)
+    }
+
+    #[test]
+    fn test_scale_positions() {
+        let target = ErasedHandle::new(1, 1);
+        let mut animation = Animation::<ErasedHandle>::default();
+        animation.add_track(position_track(target));
+        let mut rotation = TrackDataContainer::new(TrackValueKind::UnitQuaternion);
+        rotation.curves_mut()[0] = Curve::from(vec![CurveKey::new(1.0, 0.5, CurveKeyKind::Linear)]);
+        animation.add_track(Track::new(rotation, ValueBinding::Rotation).with_target(target));
+
+        animation.scale_positions(0.5);
+
+        let positions = animation.tracks()[0].keyframes();
+        assert_eq!(
+            positions[0].value,
+            TrackValue::Vector3(Vector3::new(0.0, 0.0, 0.0))
+        );
+        assert_eq!(
+            positions[1].value,
+            TrackValue::Vector3(Vector3::new(5.0, 0.0, 0.0))
+        );
+        assert_eq!(
+            animation.tracks()[1].data_container().curves_ref()[0].keys()[0].value,
+            0.5
+        );
+    }
+
+    #[test]
+    fn test_sample() {
+        let target = ErasedHandle::new(1, 1);
+        let mut animation = Animation::<ErasedHandle>::default();
+        animation.add_track(position_track(target));
+
+        let x_at = |time: f32| match animation.sample_target(target, time).values[0].value {
+            TrackValue::Vector3(v) => v.x,
+            _ => unreachable!(),
+        };
+
+        // Before the first keyframe, at keyframes, in between and after the last keyframe.
+        assert_eq!(x_at(0.0), 0.0);
+        assert_eq!(x_at(1.0), 0.0);
+        assert_eq!(x_at(1.25), 2.5);
+        assert_eq!(x_at(2.0), 10.0);
+        assert_eq!(x_at(100.0), 10.0);
+
+        let pose = animation.sample(1.5);
+        assert_eq!(pose.poses().len(), 1);
+        assert_eq!(
+            pose.poses()[&target].values.values[0].value,
+            TrackValue::Vector3(crate::core::algebra::Vector3::new(5.0, 0.0, 0.0))
+        );
+
+        assert!(animation
+            .sample_target(ErasedHandle::new(2, 1), 1.5)
+            .values
+            .is_empty());
+    }
+
+    #[test]
+    fn test_disabled_track_is_muted() {
+        let target = ErasedHandle::new(1, 1);
+        let mut animation = Animation::<ErasedHandle>::default();
+        animation.add_track(position_track(target));
+        animation.add_track(
+            Track::new(
+                TrackDataContainer::new(TrackValueKind::Vector3),
+                ValueBinding::Scale,
+            )
+            .with_target(target)
+            .with_enabled(false),
+        );
+
+        let bindings = |animation: &Animation<ErasedHandle>| {
+            animation
+                .sample_target(target, 1.5)
+                .values
+                .into_iter()
+                .map(|value| value.binding)
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(bindings(&animation), vec![ValueBinding::Position]);
+
+        animation.tracks_mut()[0].set_enabled(false);
+        assert!(bindings(&animation).is_empty());
+        assert!(animation.sample(1.5).poses().is_empty());
+
+        animation.tracks_mut()[1].set_enabled(true);
+        assert_eq!(bindings(&animation), vec![ValueBinding::Scale]);
+    }
+
+    #[test]
+    fn test_track_weights() {
+        let target = ErasedHandle::new(1, 1);
+
+        let mut scale = TrackDataContainer::new(TrackValueKind::Vector3);
+        scale.curves_mut()[0] = Curve::from(vec![CurveKey::new(0.0, 10.0, CurveKeyKind::Linear)]);
+        let mut layer = Animation::<ErasedHandle>::default();
+        layer.add_track(position_track(target).with_weight(0.5));
+        layer.add_track(Track::new(scale, ValueBinding::Scale).with_target(target));
+        assert_eq!(layer.tracks()[0].weight(), 0.5);
+        assert_eq!(layer.tracks()[1].weight(), 1.0);
+
+        let layer_pose = layer.sample(2.0);
+        let node_pose = &layer_pose.poses()[&target];
+        assert_eq!(node_pose.weight(&ValueBinding::Position), 0.5);
+        assert_eq!(node_pose.weight(&ValueBinding::Scale), 1.0);
+
+        let mut base = Animation::<ErasedHandle>::default();
+        base.add_track(
+            Track::new(
+                TrackDataContainer::new(TrackValueKind::Vector3),
+                ValueBinding::Position,
+            )
+            .with_target(target),
+        );
+        base.add_track(
+            Track::new(
+                TrackDataContainer::new(TrackValueKind::Vector3),
+                ValueBinding::Scale,
+            )
+            .with_target(target),
+        );
+        let mut pose = base.sample(0.0);
+        pose.blend_with_track_weights(&layer_pose, 1.0);
+
+        let values = &pose.poses()[&target].values;
+        assert_eq!(
+            values.find(&ValueBinding::Position).unwrap().value,
+            TrackValue::Vector3(Vector3::new(5.0, 0.0, 0.0))
+        );
+        assert_eq!(
+            values.find(&ValueBinding::Scale).unwrap().value,
+            TrackValue::Vector3(Vector3::new(10.0, 0.0, 0.0))
+        );
+
+        // Weights are clamped.
+        let mut track = position_track(target);
+        track.set_weight(2.0);
+        assert_eq!(track.weight(), 1.0);
+        track.set_weight(-1.0);
+        assert_eq!(track.weight(), 0.0);
+    }
+
+    #[test]
+    fn test_auto_smooth_tangents() {
+        let mut track = Track::<ErasedHandle>::new_position();
+        for (time, x) in [(0.0, 0.0), (1.0, 1.0), (2.0, 0.0)] {
+            track.insert_keyframe(
+                time,
+                &TrackValue::Vector3(Vector3::new(x, 0.0, 0.0)),
+                CurveKeyKind::Linear,
+            );
+        }
+        let linear = track.fetch(0.5).unwrap().value;
+        assert_eq!(linear, TrackValue::Vector3(Vector3::new(0.5, 0.0, 0.0)));
+
+        track.set_auto_smooth_tangents(true);
+        assert!(track.is_auto_smooth_tangents());
+        let TrackValue::Vector3(smooth) = track.fetch(0.5).unwrap().value else {
+            unreachable!()
+        };
+        assert!(smooth.x > 0.5);
+
+        // New keys are smoothed automatically.
+        track.insert_keyframe(
+            3.0,
+            &TrackValue::Vector3(Vector3::new(1.0, 0.0, 0.0)),
+            CurveKeyKind::Linear,
+        );
+        assert!(track.data_container().curves_ref()[0]
+            .keys()
+            .iter()
+            .all(|key| matches!(key.kind, CurveKeyKind::Cubic { .. })));
+    }
+
+    #[test]
+    fn test_sample_rotations() {
+        let target = ErasedHandle::new(1, 1);
+        let mut rotation = TrackDataContainer::new(TrackValueKind::UnitQuaternion);
+        rotation.insert_keyframe(
+            0.0,
+            &TrackValue::UnitQuaternion(UnitQuaternion::identity()),
+            CurveKeyKind::Linear,
+        );
+        rotation.insert_keyframe(
+            2.0,
+            &TrackValue::UnitQuaternion(UnitQuaternion::from_euler_angles(0.0, 1.0, 0.0)),
+            CurveKeyKind::Linear,
+        );
+        let mut animation = Animation::<ErasedHandle>::default();
+        animation.add_track(position_track(target));
+        animation.add_track(Track::new(rotation, ValueBinding::Rotation).with_target(target));
+        // A node without rotation tracks.
+        animation.add_track(position_track(ErasedHandle::new(2, 1)));
+
+        let full = animation.sample(1.0);
+        let rotations = animation.sample_rotations(1.0);
+        assert_eq!(rotations.poses().len(), 1);
+        let values = &rotations.poses()[&target].values;
+        assert_eq!(values.values.len(), 1);
+        assert_eq!(
+            values.find(&ValueBinding::Rotation),
+            full.poses()[&target].values.find(&ValueBinding::Rotation)
+        );
+
+        // Derived rotations are sampled too.
+        animation.set_derive_rotation_from_position(true);
+        let rotations = animation.sample_rotations(1.5);
+        assert_eq!(rotations.poses().len(), 2);
+        assert!(rotations
+            .poses()
+            .values()
+            .all(|pose| pose.values.find(&ValueBinding::Position).is_none()));
+    }
+
+    #[test]
+    fn test_binding_remap() {
+        let target = ErasedHandle::new(1, 1);
+        let mut animation = Animation::<ErasedHandle>::default();
+        animation.add_track(position_track(target));
+        animation.set_binding_remap(HashMap::from([(
+            ValueBinding::Position,
+            ValueBinding::Property {
+                name: "offset".into(),
+                value_type: ValueType::Vector3F32,
+            },
+        )]));
+
+        let values = animation.sample_target(target, 1.5).values;
+        assert_eq!(values.len(), 1);
+        assert_eq!(
+            values[0].binding,
+            ValueBinding::Property {
+                name: "offset".into(),
+                value_type: ValueType::Vector3F32,
+            }
+        );
+
+        animation.set_binding_remap(Default::default());
+        assert_eq!(
+            animation.sample_target(target, 1.5).values[0].binding,
+            ValueBinding::Position
+        );
+    }
+
+    #[test]
+    fn test_sample_target_at_offsets() {
+        let target = ErasedHandle::new(1, 1);
+        let mut animation = Animation::<ErasedHandle>::default();
+        animation.add_track(position_track(target));
+        animation.set_time_slice(1.0..2.0);
+
+        let xs = |animation: &Animation<ErasedHandle>| {
+            animation
+                .sample_target_at_offsets(target, 1.5, &[-0.25, 0.0, 0.75])
+                .into_iter()
+                .map(|values| match values.values[0].value {
+                    TrackValue::Vector3(v) => v.x,
+                    _ => unreachable!(),
+                })
+                .collect::<Vec<_>>()
+        };
+
+        // The last offset wraps around for looping animations.
+        assert_eq!(xs(&animation), vec![2.5, 5.0, 2.5]);
+
+        animation.set_loop(false);
+        assert_eq!(xs(&animation), vec![2.5, 5.0, 10.0]);
+    }
+
+    #[test]
+    fn test_sample_frame() {
+        let target = ErasedHandle::new(1, 1);
+        let mut animation = Animation::<ErasedHandle>::default();
+        animation.add_track(position_track(target));
+        animation.set_time_slice(1.0..2.0);
+        animation.set_loop(false);
+
+        let x = |pose: &crate::AnimationPose<ErasedHandle>| match pose.poses()[&target]
+            .values
+            .values[0]
+            .value
+        {
+            TrackValue::Vector3(v) => v.x,
+            _ => unreachable!(),
+        };
+
+        let poses = animation.sample_frame(1.5, 0.5, 4);
+        assert_eq!(
+            poses.iter().map(x).collect::<Vec<_>>(),
+            vec![5.0, 6.25, 7.5, 8.75]
+        );
+
+        let average = animation.sample_frame_averaged(1.5, 0.5, 4);
+        assert!((x(&average) - 6.875).abs() < 1.0e-5);
+        assert_eq!(x(&animation.sample_frame_averaged(1.5, 0.5, 0)), 5.0);
+    }
+
+    #[test]
+    fn test_derive_rotation_from_position() {
+        let target = ErasedHandle::new(1, 1);
+        let mut animation = Animation::<ErasedHandle>::default();
+        animation.add_track(position_track(target));
+
+        let rotation = |animation: &Animation<ErasedHandle>| {
+            animation
+                .sample_target(target, 1.5)
+                .find(&ValueBinding::Rotation)
+                .map(|bound_value| match bound_value.value {
+                    TrackValue::UnitQuaternion(rotation) => rotation,
+                    _ => unreachable!(),
+                })
+        };
+
+        assert_eq!(rotation(&animation), None);
+
+        animation.set_derive_rotation_from_position(true);
+        let rotation = rotation(&animation).unwrap();
+        // The target moves along X axis, so its local Z axis must look along X axis.
+        let forward = rotation * Vector3::z();
+        assert!((forward - Vector3::x()).norm() < 1.0e-5);
+        assert!((rotation * Vector3::y() - Vector3::y()).norm() < 1.0e-5);
+
+        let pose = animation.sample(1.5);
+        assert!(pose.poses()[&target]
+            .values
+            .find(&ValueBinding::Rotation)
+            .is_some());
+    }
+
+    #[test]
+    fn test_loop_crossfade() {
+        let target = ErasedHandle::new(1, 1);
+        let mut animation = Animation::<ErasedHandle>::default();
+        animation.add_track(position_track(target));
+        animation.set_time_slice(1.0..2.0);
+        animation.set_loop_crossfade(0.5);
+
+        let x_at = |animation: &Animation<ErasedHandle>, time: f32| match animation
+            .sample_target(target, time)
+            .values[0]
+            .value
+        {
+            TrackValue::Vector3(v) => v.x,
+            _ => unreachable!(),
+        };
+
+        // Outside of the window, in the middle of it and at the end, where it matches the start.
+        assert_eq!(x_at(&animation, 1.25), 2.5);
+        assert_eq!(x_at(&animation, 1.75), 3.75);
+        assert_eq!(x_at(&animation, 2.0), 0.0);
+        assert_eq!(
+            animation.sample(1.75).poses()[&target].values.values[0].value,
+            TrackValue::Vector3(crate::core::algebra::Vector3::new(3.75, 0.0, 0.0))
+        );
+
+        animation.set_loop(false);
+        assert_eq!(x_at(&animation, 1.75), 7.5);
+    }
+
+    #[test]
+    fn test_reversed() {
+        let target = ErasedHandle::new(1, 1);
+        let mut animation = Animation::<ErasedHandle>::default();
+        animation.add_track(position_track(target));
+        animation.set_time_slice(0.0..2.0);
+        animation.add_signal(AnimationSignal::new(Default::default(), "Signal", 0.5));
+
+        let x_at = |animation: &Animation<ErasedHandle>, time: f32| match animation
+            .sample_target(target, time)
+            .values[0]
+            .value
+        {
+            TrackValue::Vector3(v) => v.x,
+            _ => unreachable!(),
+        };
+
+        let reversed = animation.reversed();
+        for time in [0.0, 0.5, 0.75, 1.0, 1.5, 2.0] {
+            assert_eq!(x_at(&reversed, time), x_at(&animation, 2.0 - time));
+        }
+        assert_eq!(reversed.signals()[0].time, 1.5);
+
+        assert_eq!(reversed.reversed(), animation);
+    }
+
+    #[test]
+    fn test_normalized_time_position() {
+        let mut animation = Animation::<ErasedHandle>::default();
+        animation.set_time_slice(1.0..3.0);
+        animation.set_time_position(2.0);
+        assert_eq!(animation.normalized_time_position(), 0.5);
+
+        animation.set_time_slice(0.0..0.0);
+        assert_eq!(animation.normalized_time_position(), 0.0);
+    }
+
+    #[test]
+    fn test_is_playing() {
+        let mut animation = Animation::<ErasedHandle>::default();
+        animation.set_loop(false).set_time_slice(0.0..1.0);
+        assert!(animation.is_playing());
+
+        animation.set_time_position(1.0);
+        assert!(!animation.is_playing());
+
+        animation.set_loop(true).set_enabled(false);
+        assert!(!animation.is_playing());
+
+        animation.set_enabled(true).set_speed(0.0);
+        assert!(!animation.is_playing());
+    }
+}
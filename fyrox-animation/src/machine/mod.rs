@@ -302,7 +302,7 @@ impl<T: EntityId> Machine<T> {
             let weight = layer.weight();
             let pose = layer.evaluate_pose(animations, &self.parameters, dt);
 
-            self.final_pose.blend_with(pose, weight);
+            self.final_pose.blend_with_track_weights(pose, weight);
         }
 
         &self.final_pose
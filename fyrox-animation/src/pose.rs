@@ -1,6 +1,9 @@
 //! Pose is a set of property values of a node ([`NodePose`]) or a set of nodes ([`AnimationPose`]).
 
-use crate::{value::BoundValue, value::BoundValueCollection, EntityId, RootMotion};
+use crate::{
+    value::{BoundValue, BoundValueCollection, ValueBinding},
+    EntityId, RootMotion,
+};
 use fxhash::FxHashMap;
 use std::collections::hash_map::Entry;
 
@@ -13,13 +16,70 @@ pub struct NodePose<T: EntityId> {
 
     /// A set of property values.
     pub values: BoundValueCollection,
+
+    /// Blend weights of the values, that differ from `1.0`. See [`Self::weight`] docs for more info.
+    weights: Vec<(ValueBinding, f32)>,
 }
 
 impl<T: EntityId> NodePose<T> {
+    /// Creates a new empty pose of the given node.
+    pub(crate) fn new(node: T) -> Self {
+        Self {
+            node,
+            values: Default::default(),
+            weights: Default::default(),
+        }
+    }
+
     /// Performs a blending of the current with some other pose. See [`super::value::TrackValue::blend_with`] docs for more
     /// info.
     pub fn blend_with(&mut self, other: &NodePose<T>, weight: f32) {
-        self.values.blend_with(&other.values, weight)
+        self.values.blend_with(&other.values, weight);
+        self.merge_weights(other);
+    }
+
+    /// Returns blend weight of a value with the given binding. The weight is taken from a track that produced the value
+    /// (see [`crate::track::Track::set_weight`]), it is `1.0` by default.
+    pub fn weight(&self, binding: &ValueBinding) -> f32 {
+        self.weights
+            .iter()
+            .find_map(|(existing, weight)| (existing == binding).then_some(*weight))
+            .unwrap_or(1.0)
+    }
+
+    /// Sets blend weight of a value with the given binding. See [`Self::weight`] docs for more info.
+    pub fn set_weight(&mut self, binding: &ValueBinding, weight: f32) {
+        let index = self
+            .weights
+            .iter()
+            .position(|(existing, _)| existing == binding);
+        match (index, weight == 1.0) {
+            (Some(index), false) => self.weights[index].1 = weight,
+            (Some(index), true) => {
+                self.weights.remove(index);
+            }
+            (None, false) => self.weights.push((binding.clone(), weight)),
+            (None, true) => (),
+        }
+    }
+
+    // Weights of the current pose have priority, the other weights are added only for the missing bindings.
+    fn merge_weights(&mut self, other: &NodePose<T>) {
+        for (binding, weight) in other.weights.iter() {
+            if !self.weights.iter().any(|(existing, _)| existing == binding) {
+                self.weights.push((binding.clone(), *weight));
+            }
+        }
+    }
+
+    /// Same as [`Self::blend_with`], but the weight is defined per binding. See
+    /// [`BoundValueCollection::blend_with_weights`] docs for more info.
+    pub fn blend_with_weights<F>(&mut self, other: &NodePose<T>, weight: F)
+    where
+        F: FnMut(&ValueBinding) -> f32,
+    {
+        self.values.blend_with_weights(&other.values, weight);
+        self.merge_weights(other);
     }
 }
 
@@ -67,24 +127,66 @@ impl<T: EntityId> AnimationPose<T> {
             .blend_with(&other.root_motion.clone().unwrap_or_default(), weight);
     }
 
-    fn add_node_pose(&mut self, local_pose: NodePose<T>) {
-        self.poses.insert(local_pose.node, local_pose);
+    /// Same as [`Self::blend_with`], but the weight is defined per node and per binding by the given closure. It is
+    /// useful for masked blending, for example to blend an upper body animation with a gradient along the spine. Root
+    /// motion is blended using the separate weight.
+    pub fn blend_with_weights<F>(
+        &mut self,
+        other: &AnimationPose<T>,
+        root_motion_weight: f32,
+        mut weight: F,
+    ) where
+        F: FnMut(T, &ValueBinding) -> f32,
+    {
+        for (handle, other_pose) in other.poses.iter() {
+            if let Some(current_pose) = self.poses.get_mut(handle) {
+                current_pose.blend_with_weights(other_pose, |binding| weight(*handle, binding));
+            } else {
+                self.add_node_pose(other_pose.clone());
+            }
+        }
+
+        self.root_motion
+            .get_or_insert_with(Default::default)
+            .blend_with(
+                &other.root_motion.clone().unwrap_or_default(),
+                root_motion_weight,
+            );
     }
 
-    pub(super) fn add_to_node_pose(&mut self, node: T, bound_value: BoundValue) {
-        match self.poses.entry(node) {
-            Entry::Occupied(entry) => {
-                entry.into_mut().values.values.push(bound_value);
-            }
-            Entry::Vacant(entry) => {
-                entry.insert(NodePose {
-                    node,
-                    values: BoundValueCollection {
-                        values: vec![bound_value],
-                    },
-                });
+    /// Same as [`Self::blend_with`], but the weight is multiplied with blend weights of the values of the other pose
+    /// (see [`NodePose::weight`]), which come from the weights of the tracks (see [`crate::track::Track::set_weight`]).
+    /// It is used to blend animation layers, so a layer could affect different channels differently.
+    pub fn blend_with_track_weights(&mut self, other: &AnimationPose<T>, weight: f32) {
+        for (handle, other_pose) in other.poses.iter() {
+            if let Some(current_pose) = self.poses.get_mut(handle) {
+                current_pose
+                    .blend_with_weights(other_pose, |binding| weight * other_pose.weight(binding));
+            } else {
+                self.add_node_pose(other_pose.clone());
             }
         }
+
+        self.root_motion
+            .get_or_insert_with(Default::default)
+            .blend_with(&other.root_motion.clone().unwrap_or_default(), weight);
+    }
+
+    fn add_node_pose(&mut self, local_pose: NodePose<T>) {
+        self.poses.insert(local_pose.node, local_pose);
+    }
+
+    pub(super) fn add_to_node_pose(&mut self, node: T, bound_value: BoundValue, weight: f32) {
+        let node_pose = match self.poses.entry(node) {
+            Entry::Occupied(entry) => entry.into_mut(),
+            Entry::Vacant(entry) => entry.insert(NodePose {
+                node,
+                values: Default::default(),
+                weights: Default::default(),
+            }),
+        };
+        node_pose.set_weight(&bound_value.binding, weight);
+        node_pose.values.insert(bound_value);
     }
 
     /// Clears the pose.
@@ -0,0 +1,291 @@
+//! Animation retargeting allows you to transfer an animation authored for one skeleton to another skeleton, that has
+//! different bone names and rest poses. See [`Animation::retargeted`] docs for more info.
+
+use crate::{
+    core::{
+        algebra::{Isometry3, Translation3, UnitQuaternion, Vector3},
+        math::ieee_remainder,
+    },
+    track::Track,
+    value::{BoundValue, ValueBinding},
+    Animation, EntityId,
+};
+use std::collections::HashMap;
+
+/// Rest pose of a bone - its local transform (relative to its parent), when it is not animated.
+#[derive(Clone, Debug, PartialEq)]
+pub struct RestPose<T: EntityId> {
+    /// A handle of the bone.
+    pub handle: T,
+    /// Local position of the bone in the rest pose.
+    pub position: Vector3<f32>,
+    /// Local rotation of the bone in the rest pose.
+    pub rotation: UnitQuaternion<f32>,
+}
+
+/// A set of named bones of a skeleton with their rest poses, that is used for animation retargeting. See
+/// [`Animation::retargeted`] for more info.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct RetargetSkeleton<T: EntityId> {
+    bones: HashMap<String, RestPose<T>>,
+}
+
+impl<T: EntityId> RetargetSkeleton<T> {
+    /// Creates a new empty skeleton.
+    pub fn new() -> Self {
+        Self {
+            bones: Default::default(),
+        }
+    }
+
+    /// Adds a new bone with the given name and rest pose to the skeleton. A bone with the same name is replaced.
+    pub fn with_bone<S: AsRef<str>>(mut self, name: S, rest_pose: RestPose<T>) -> Self {
+        self.add_bone(name, rest_pose);
+        self
+    }
+
+    /// Adds a new bone with the given name and rest pose to the skeleton and returns the previous bone with the same
+    /// name, if any.
+    pub fn add_bone<S: AsRef<str>>(
+        &mut self,
+        name: S,
+        rest_pose: RestPose<T>,
+    ) -> Option<RestPose<T>> {
+        self.bones.insert(name.as_ref().to_owned(), rest_pose)
+    }
+
+    /// Tries to find a bone by its name.
+    pub fn bone<S: AsRef<str>>(&self, name: S) -> Option<&RestPose<T>> {
+        self.bones.get(name.as_ref())
+    }
+}
+
+fn retarget_track<T: EntityId>(
+    track: &Track<T>,
+    source: &RestPose<T>,
+    target: &RestPose<T>,
+) -> Track<T> {
+    let mut track = track.clone();
+    track.set_target(target.handle);
+
+    // Positions keep their offsets from the rest position, while rotations keep their local deltas from the rest
+    // rotation: `target_rest * source_rest⁻¹ * rotation`.
+    let offset = match track.binding() {
+        ValueBinding::Position => Isometry3::from_parts(
+            Translation3::from(target.position - source.position),
+            UnitQuaternion::identity(),
+        ),
+        ValueBinding::Rotation => Isometry3::from_parts(
+            Translation3::identity(),
+            target.rotation * source.rotation.inverse(),
+        ),
+        _ => return track,
+    };
+
+    let binding = track.binding().clone();
+    let container = track.data_container_mut();
+    for (index, keyframe) in container.keyframes().into_iter().enumerate() {
+        let value = BoundValue {
+            binding: binding.clone(),
+            value: keyframe.value,
+        }
+        .transformed(&offset)
+        .value;
+        container.set_keyframe(index, keyframe.time, &value);
+    }
+
+    if binding == ValueBinding::Rotation {
+        // Euler angles of the transformed rotations are in `-π..π` range, so the angles of adjacent keys could land on
+        // the opposite sides of `±π` and they would be interpolated the long way around. Unwrap the angles, so the
+        // difference between adjacent keys never exceeds `π`.
+        for curve in container.curves_mut() {
+            for index in 1..curve.keys.len() {
+                let previous = curve.keys[index - 1].value;
+                let key = &mut curve.keys[index];
+                key.value =
+                    previous + ieee_remainder(key.value - previous, 2.0 * std::f32::consts::PI);
+            }
+        }
+    }
+
+    track
+}
+
+impl<T: EntityId> Animation<T> {
+    /// Creates a copy of the animation, that animates the `target` skeleton instead of the `source` one. `mapping` maps
+    /// the names of the bones of the source skeleton to the names of the bones of the target skeleton. Tracks of the
+    /// mapped bones are re-bound to the target bones, their position and rotation keys are transformed into the local
+    /// spaces of the target bones: positions keep their offsets from the rest positions and rotations keep their
+    /// deltas from the rest rotations. Other tracks of the mapped bones (scale, properties) are copied as is. Tracks of
+    /// the bones, that have no mapping (or the mapped bone is missing in one of the skeletons), are not copied. Root
+    /// motion settings are re-bound too, or removed if the root motion node has no mapping.
+    ///
+    /// The retargeting assumes, that the bones of both skeletons have similar orientation of their local axes relative
+    /// to the rest pose, which is true for most humanoid rigs.
+    pub fn retargeted(
+        &self,
+        source: &RetargetSkeleton<T>,
+        target: &RetargetSkeleton<T>,
+        mapping: &HashMap<String, String>,
+    ) -> Self {
+        let bones = mapping
+            .iter()
+            .filter_map(|(source_name, target_name)| {
+                let source_bone = source.bone(source_name)?;
+                let target_bone = target.bone(target_name)?;
+                Some((source_bone.handle, (source_bone, target_bone)))
+            })
+            .collect::<HashMap<_, _>>();
+
+        let mut animation = self.clone();
+
+        animation.tracks = self
+            .tracks
+            .iter()
+            .filter_map(|track| {
+                let (source_bone, target_bone) = bones.get(&track.target())?;
+                Some(retarget_track(track, source_bone, target_bone))
+            })
+            .collect();
+
+        animation.root_motion_settings =
+            self.root_motion_settings.clone().and_then(|mut settings| {
+                let (_, target_bone) = bones.get(&settings.node)?;
+                settings.node = target_bone.handle;
+                Some(settings)
+            });
+
+        animation
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{
+        container::{TrackDataContainer, TrackValueKind},
+        core::{
+            algebra::{UnitQuaternion, Vector3},
+            math::curve::CurveKeyKind,
+            pool::ErasedHandle,
+        },
+        retarget::{RestPose, RetargetSkeleton},
+        track::Track,
+        value::{TrackValue, ValueBinding},
+        Animation,
+    };
+    use std::collections::HashMap;
+
+    fn bone(index: u32, position: Vector3<f32>, angle: f32) -> RestPose<ErasedHandle> {
+        RestPose {
+            handle: ErasedHandle::new(index, 1),
+            position,
+            rotation: UnitQuaternion::from_euler_angles(0.0, angle, 0.0),
+        }
+    }
+
+    fn track(
+        target: ErasedHandle,
+        binding: ValueBinding,
+        value: TrackValue,
+    ) -> Track<ErasedHandle> {
+        let kind = match value {
+            TrackValue::UnitQuaternion(_) => TrackValueKind::UnitQuaternion,
+            _ => TrackValueKind::Vector3,
+        };
+        let mut frames = TrackDataContainer::new(kind);
+        frames.insert_keyframe(1.0, &value, CurveKeyKind::Linear);
+        Track::new(frames, binding).with_target(target)
+    }
+
+    #[test]
+    fn test_retarget_two_bones() {
+        let source = RetargetSkeleton::new()
+            .with_bone("Hips", bone(1, Vector3::new(0.0, 1.0, 0.0), 0.0))
+            .with_bone("Spine", bone(2, Vector3::new(0.0, 0.2, 0.0), 0.3));
+        let target = RetargetSkeleton::new()
+            .with_bone("pelvis", bone(10, Vector3::new(0.0, 0.8, 0.0), 0.5))
+            .with_bone("spine_01", bone(11, Vector3::new(0.0, 0.3, 0.1), -0.2));
+        let mapping = HashMap::from([
+            ("Hips".to_owned(), "pelvis".to_owned()),
+            ("Spine".to_owned(), "spine_01".to_owned()),
+        ]);
+
+        let delta = UnitQuaternion::from_euler_angles(0.4, 0.0, 0.0);
+        let source_hips = source.bone("Hips").unwrap();
+        let source_spine = source.bone("Spine").unwrap();
+
+        let mut animation = Animation::<ErasedHandle>::default();
+        animation.add_track(track(
+            source_hips.handle,
+            ValueBinding::Rotation,
+            TrackValue::UnitQuaternion(source_hips.rotation * delta),
+        ));
+        animation.add_track(track(
+            source_spine.handle,
+            ValueBinding::Position,
+            TrackValue::Vector3(source_spine.position + Vector3::new(0.0, 0.5, 0.0)),
+        ));
+        // A bone without mapping.
+        animation.add_track(track(
+            ErasedHandle::new(3, 1),
+            ValueBinding::Position,
+            TrackValue::Vector3(Vector3::default()),
+        ));
+
+        let retargeted = animation.retargeted(&source, &target, &mapping);
+        let tracks = retargeted.tracks();
+        assert_eq!(tracks.len(), 2);
+
+        let target_pelvis = target.bone("pelvis").unwrap();
+        assert_eq!(tracks[0].target(), target_pelvis.handle);
+        let TrackValue::UnitQuaternion(rotation) = tracks[0].keyframes()[0].value else {
+            unreachable!()
+        };
+        assert!(rotation.angle_to(&(target_pelvis.rotation * delta)) < 1.0e-4);
+
+        let target_spine = target.bone("spine_01").unwrap();
+        assert_eq!(tracks[1].target(), target_spine.handle);
+        let TrackValue::Vector3(position) = tracks[1].keyframes()[0].value else {
+            unreachable!()
+        };
+        assert!((position - (target_spine.position + Vector3::new(0.0, 0.5, 0.0))).norm() < 1.0e-5);
+    }
+
+    #[test]
+    fn test_retarget_rotation_keys() {
+        let rest = |index: u32, angle: f32| RestPose {
+            handle: ErasedHandle::new(index, 1),
+            position: Vector3::default(),
+            rotation: UnitQuaternion::from_euler_angles(angle, 0.0, 0.0),
+        };
+        let source = RetargetSkeleton::new().with_bone("Hips", rest(1, 0.0));
+        let target = RetargetSkeleton::new().with_bone("pelvis", rest(10, 3.0));
+        let mapping = HashMap::from([("Hips".to_owned(), "pelvis".to_owned())]);
+
+        // The retargeted keys are on the opposite sides of `±π`.
+        let delta = |angle: f32| UnitQuaternion::from_euler_angles(angle, 0.0, 0.0);
+        let mut frames = TrackDataContainer::new(TrackValueKind::UnitQuaternion);
+        for (time, angle) in [(0.0, 0.0), (1.0, 0.2), (2.0, 0.4)] {
+            frames.insert_keyframe(
+                time,
+                &TrackValue::UnitQuaternion(delta(angle)),
+                CurveKeyKind::Linear,
+            );
+        }
+        let mut animation = Animation::<ErasedHandle>::default();
+        animation.add_track(
+            Track::new(frames, ValueBinding::Rotation).with_target(ErasedHandle::new(1, 1)),
+        );
+
+        let retargeted = animation.retargeted(&source, &target, &mapping);
+        let track = &retargeted.tracks()[0];
+        let target_rotation = target.bone("pelvis").unwrap().rotation;
+        for time in [0.0, 0.25, 0.5, 0.75, 1.0, 1.5, 2.0] {
+            let TrackValue::UnitQuaternion(rotation) = track.fetch(time).unwrap().value else {
+                unreachable!()
+            };
+            // Interpolation goes through the short path between the keys.
+            assert!(rotation.angle_to(&(target_rotation * delta(time * 0.2))) < 1.0e-4);
+        }
+    }
+}
@@ -1,9 +1,9 @@
 //! Track is responsible in animating a property of a single scene node. See [`Track`] docs for more info.
 
 use crate::{
-    container::{TrackDataContainer, TrackValueKind},
-    core::{reflect::prelude::*, uuid::Uuid, visitor::prelude::*},
-    value::{BoundValue, ValueBinding},
+    container::{Keyframe, TrackDataContainer, TrackValueKind},
+    core::{math::curve::CurveKeyKind, reflect::prelude::*, uuid::Uuid, visitor::prelude::*},
+    value::{BoundValue, TrackValue, ValueBinding},
     EntityId,
 };
 use std::fmt::Debug;
@@ -18,6 +18,9 @@ pub struct Track<T: EntityId> {
     enabled: bool,
     target: T,
     id: Uuid,
+    #[reflect(min_value = 0.0, max_value = 1.0, step = 0.05)]
+    weight: f32,
+    auto_smooth_tangents: bool,
 }
 
 impl<T: EntityId> Visit for Track<T> {
@@ -30,6 +33,10 @@ impl<T: EntityId> Visit for Track<T> {
         let _ = self.binding.visit("Binding", &mut region); // Backward compatibility
         let _ = self.id.visit("Id", &mut region); // Backward compatibility
         let _ = self.frames.visit("Frames", &mut region); // Backward compatibility
+        let _ = self.weight.visit("Weight", &mut region); // Backward compatibility
+        let _ = self
+            .auto_smooth_tangents
+            .visit("AutoSmoothTangents", &mut region); // Backward compatibility
 
         Ok(())
     }
@@ -43,6 +50,8 @@ impl<T: EntityId> Default for Track<T> {
             enabled: true,
             target: Default::default(),
             id: Uuid::new_v4(),
+            weight: 1.0,
+            auto_smooth_tangents: false,
         }
     }
 }
@@ -91,6 +100,24 @@ impl<T: EntityId> Track<T> {
         self
     }
 
+    /// Enables or disables the track. See [`Self::set_enabled`] docs for more info.
+    pub fn with_enabled(mut self, enabled: bool) -> Self {
+        self.enabled = enabled;
+        self
+    }
+
+    /// Sets blend weight of the track. See [`Self::set_weight`] docs for more info.
+    pub fn with_weight(mut self, weight: f32) -> Self {
+        self.set_weight(weight);
+        self
+    }
+
+    /// Enables or disables automatic smoothing of tangents. See [`Self::set_auto_smooth_tangents`] docs for more info.
+    pub fn with_auto_smooth_tangents(mut self, enabled: bool) -> Self {
+        self.set_auto_smooth_tangents(enabled);
+        self
+    }
+
     /// Sets new track binding. See [`ValueBinding`] docs for more info.
     pub fn set_binding(&mut self, binding: ValueBinding) {
         self.binding = binding;
@@ -121,6 +148,68 @@ impl<T: EntityId> Track<T> {
         &mut self.frames
     }
 
+    /// Returns every key frame of the track sorted by time. Use [`Self::data_container_mut`] to edit the key frames. See
+    /// [`TrackDataContainer::keyframes`] for more info.
+    pub fn keyframes(&self) -> Vec<Keyframe> {
+        self.frames.keyframes()
+    }
+
+    /// Removes redundant key frames of the track, that could be restored by interpolation of their neighbours with the
+    /// given tolerance. The first and the last key frames are preserved. Returns the amount of removed key frames. See
+    /// [`TrackDataContainer::remove_redundant_keyframes`] for more info.
+    pub fn remove_redundant_keyframes(&mut self, tolerance: f32) -> usize {
+        self.frames.remove_redundant_keyframes(tolerance)
+    }
+
+    /// Converts the keys of the track into cubic keys with tangents, that are calculated from the neighbouring keys
+    /// (Catmull-Rom style), so the values flow smoothly through the keys. See [`TrackDataContainer::set_smooth_tangents`]
+    /// for more info.
+    pub fn set_smooth_tangents(&mut self) {
+        self.frames.set_smooth_tangents();
+    }
+
+    /// Enables or disables automatic smoothing of tangents. When enabled, the tangents are re-calculated (see
+    /// [`Self::set_smooth_tangents`]) every time a key frame is inserted or changed using [`Self::insert_keyframe`] or
+    /// [`Self::set_keyframe`], and once when the smoothing is enabled. Direct changes of the data container (see
+    /// [`Self::data_container_mut`]) are not tracked. Disabled by default.
+    pub fn set_auto_smooth_tangents(&mut self, enabled: bool) {
+        self.auto_smooth_tangents = enabled;
+        if enabled {
+            self.set_smooth_tangents();
+        }
+    }
+
+    /// Returns `true` if automatic smoothing of tangents is enabled, `false` - otherwise. See
+    /// [`Self::set_auto_smooth_tangents`] docs for more info.
+    pub fn is_auto_smooth_tangents(&self) -> bool {
+        self.auto_smooth_tangents
+    }
+
+    /// Inserts a new key frame to the track. Tangents are re-calculated if automatic smoothing of tangents is enabled.
+    /// See [`TrackDataContainer::insert_keyframe`] for more info.
+    pub fn insert_keyframe(
+        &mut self,
+        time: f32,
+        value: &TrackValue,
+        kind: CurveKeyKind,
+    ) -> Option<usize> {
+        let index = self.frames.insert_keyframe(time, value, kind);
+        if self.auto_smooth_tangents {
+            self.set_smooth_tangents();
+        }
+        index
+    }
+
+    /// Changes the key frame at the given index. Tangents are re-calculated if automatic smoothing of tangents is
+    /// enabled. See [`TrackDataContainer::set_keyframe`] for more info.
+    pub fn set_keyframe(&mut self, index: usize, time: f32, value: &TrackValue) -> Option<usize> {
+        let index = self.frames.set_keyframe(index, time, value);
+        if self.auto_smooth_tangents {
+            self.set_smooth_tangents();
+        }
+        index
+    }
+
     /// Sets new data container and returns the previous one.
     pub fn set_data_container(&mut self, container: TrackDataContainer) -> TrackDataContainer {
         std::mem::replace(&mut self.frames, container)
@@ -134,7 +223,8 @@ impl<T: EntityId> Track<T> {
         })
     }
 
-    /// Enables or disables the track. Disabled tracks won't animate their nodes/properties.
+    /// Enables or disables the track. Disabled tracks won't animate their nodes/properties, they contribute nothing
+    /// to sampled poses and root motion. The track is kept in the animation, so it could be enabled back at any time.
     pub fn set_enabled(&mut self, enabled: bool) {
         self.enabled = enabled;
     }
@@ -144,6 +234,20 @@ impl<T: EntityId> Track<T> {
         self.enabled
     }
 
+    /// Sets blend weight of the track in `[0; 1]` range, the value is clamped to this range. The weight is stored in
+    /// sampled poses and it is multiplied with the weight of an animation layer, when the layer is blended with the
+    /// layers below it (see [`crate::AnimationPose::blend_with_track_weights`]). It allows a layer to affect different
+    /// channels differently, for example a "wave" animation could affect the arm at full weight and the spine at half
+    /// weight. Default weight is `1.0`.
+    pub fn set_weight(&mut self, weight: f32) {
+        self.weight = weight.clamp(0.0, 1.0);
+    }
+
+    /// Returns blend weight of the track. See [`Self::set_weight`] docs for more info.
+    pub fn weight(&self) -> f32 {
+        self.weight
+    }
+
     /// Returns length of the track in seconds.
     pub fn time_length(&self) -> f32 {
         self.frames.time_length()
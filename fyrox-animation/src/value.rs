@@ -1,18 +1,29 @@
 //! A module that contains everything related to numeric values of animation tracks. See [`TrackValue`] docs
 //! for more info.
 
-use crate::core::{
-    algebra::{Unit, UnitQuaternion, Vector2, Vector3, Vector4},
-    math::lerpf,
-    num_traits::AsPrimitive,
-    reflect::prelude::*,
-    visitor::prelude::*,
+use crate::{
+    container::ComponentInterpolation,
+    core::{
+        algebra::{Isometry3, Quaternion, Unit, UnitQuaternion, Vector2, Vector3, Vector4},
+        math::lerpf,
+        num_traits::AsPrimitive,
+        parking_lot::Mutex,
+        reflect::prelude::*,
+        visitor::prelude::*,
+    },
 };
+use fxhash::FxHashSet;
 use fyrox_core::log::Log;
-use std::fmt::{Debug, Display, Formatter};
+use std::{
+    any::Any,
+    collections::HashMap,
+    fmt::{Debug, Display, Formatter},
+    sync::OnceLock,
+};
+use strum_macros::FromRepr;
 
 /// An actual type of a property value.
-#[derive(Visit, Reflect, Clone, Copy, Debug, PartialEq, Eq, Hash)]
+#[derive(Visit, Reflect, Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash, FromRepr)]
 pub enum ValueType {
     /// `bool`
     Bool,
@@ -118,6 +129,101 @@ impl Default for ValueType {
     }
 }
 
+/// Value types grouped by their scalar type, each row contains types with 1, 2, 3 and 4 components.
+const VALUE_TYPES_BY_DIMENSION: [[ValueType; 4]; 11] = [
+    [
+        ValueType::Bool,
+        ValueType::Vector2Bool,
+        ValueType::Vector3Bool,
+        ValueType::Vector4Bool,
+    ],
+    [
+        ValueType::F32,
+        ValueType::Vector2F32,
+        ValueType::Vector3F32,
+        ValueType::Vector4F32,
+    ],
+    [
+        ValueType::F64,
+        ValueType::Vector2F64,
+        ValueType::Vector3F64,
+        ValueType::Vector4F64,
+    ],
+    [
+        ValueType::U64,
+        ValueType::Vector2U64,
+        ValueType::Vector3U64,
+        ValueType::Vector4U64,
+    ],
+    [
+        ValueType::I64,
+        ValueType::Vector2I64,
+        ValueType::Vector3I64,
+        ValueType::Vector4I64,
+    ],
+    [
+        ValueType::U32,
+        ValueType::Vector2U32,
+        ValueType::Vector3U32,
+        ValueType::Vector4U32,
+    ],
+    [
+        ValueType::I32,
+        ValueType::Vector2I32,
+        ValueType::Vector3I32,
+        ValueType::Vector4I32,
+    ],
+    [
+        ValueType::U16,
+        ValueType::Vector2U16,
+        ValueType::Vector3U16,
+        ValueType::Vector4U16,
+    ],
+    [
+        ValueType::I16,
+        ValueType::Vector2I16,
+        ValueType::Vector3I16,
+        ValueType::Vector4I16,
+    ],
+    [
+        ValueType::U8,
+        ValueType::Vector2U8,
+        ValueType::Vector3U8,
+        ValueType::Vector4U8,
+    ],
+    [
+        ValueType::I8,
+        ValueType::Vector2I8,
+        ValueType::Vector3I8,
+        ValueType::Vector4I8,
+    ],
+];
+
+impl ValueType {
+    /// Returns the row of [`VALUE_TYPES_BY_DIMENSION`] with this type and the number of components of the type.
+    /// Returns [`None`] for quaternions.
+    fn dimension_row(self) -> Option<(&'static [ValueType; 4], usize)> {
+        VALUE_TYPES_BY_DIMENSION.iter().find_map(|row| {
+            row.iter()
+                .position(|value_type| *value_type == self)
+                .map(|position| (row, position + 1))
+        })
+    }
+}
+
+/// Defines what happens when a value cannot be applied to a property as is. See
+/// [`BoundValue::apply_to_object_with_coercion`] for more info.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq, Hash)]
+pub enum ValueCoercion {
+    /// A value is applied only if its type matches the type of the property, otherwise an error is written to the
+    /// log and the property keeps its old value.
+    #[default]
+    Strict,
+    /// Compatible conversions of a value are attempted before giving up. See
+    /// [`BoundValue::apply_to_object_with_coercion`] for the list of conversions.
+    BestEffort,
+}
+
 /// A real value that can be produced by an animation track. Animations always operate on real numbers (`f32`) for any kind
 /// of machine numeric types (including `bool`). This is needed to be able to blend values; final blending result is then
 /// converted to an actual machine type of a target property.
@@ -139,18 +245,222 @@ pub enum TrackValue {
     UnitQuaternion(UnitQuaternion<f32>),
 }
 
+/// Defines how rotations ([`TrackValue::UnitQuaternion`]) are blended.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq, Hash)]
+pub enum RotationBlendPath {
+    /// Blending always takes the shortest arc between two rotations. A quaternion and its negation represent the same
+    /// rotation, so the sign of one of the operands is flipped if their dot product is negative.
+    #[default]
+    Shortest,
+    /// Quaternions are blended as is, which could result in taking the long way around if the quaternions have opposite
+    /// signs. Use this only if the long path is intended.
+    Direct,
+}
+
 impl TrackValue {
+    fn variant_name(&self) -> &'static str {
+        match self {
+            Self::Real(_) => "Real",
+            Self::Vector2(_) => "Vector2",
+            Self::Vector3(_) => "Vector3",
+            Self::Vector4(_) => "Vector4",
+            Self::UnitQuaternion(_) => "UnitQuaternion",
+        }
+    }
+
+    /// Returns `true` if every component of the value is a finite number (not NaN or infinity), `false` - otherwise.
+    pub fn is_finite(&self) -> bool {
+        match self {
+            Self::Real(v) => v.is_finite(),
+            Self::Vector2(v) => v.iter().all(|c| c.is_finite()),
+            Self::Vector3(v) => v.iter().all(|c| c.is_finite()),
+            Self::Vector4(v) => v.iter().all(|c| c.is_finite()),
+            Self::UnitQuaternion(v) => v.coords.iter().all(|c| c.is_finite()),
+        }
+    }
+
+    /// Returns a copy of the value scaled by the given weight. Numbers and vectors are multiplied by the weight, while
+    /// rotations are scaled by spherical interpolation from identity (`identity.slerp(q, weight)`, using the shortest
+    /// arc), so the weight `0.0` results in no rotation and the weight `1.0` results in the full rotation. This makes
+    /// weighted rotations consistent with weighted vectors, when composing multiple weighted poses.
+    pub fn weighted_clone(&self, weight: f32) -> Self {
+        match self {
+            Self::Real(v) => Self::Real(*v * weight),
+            Self::Vector2(v) => Self::Vector2(v.scale(weight)),
+            Self::Vector3(v) => Self::Vector3(v.scale(weight)),
+            Self::Vector4(v) => Self::Vector4(v.scale(weight)),
+            Self::UnitQuaternion(q) => {
+                let q = if q.w < 0.0 {
+                    negate_unit_quaternion(q)
+                } else {
+                    *q
+                };
+                Self::UnitQuaternion(UnitQuaternion::identity().slerp(&q, weight))
+            }
+        }
+    }
+
+    /// Returns a copy of the value, where a rotation is replaced with the nearest axis-aligned rotation (one of 24
+    /// rotations, that map coordinate axes to coordinate axes, see [`nearest_axis_aligned_rotation`]). Other kinds of
+    /// values are returned as is. Could be used to post-process rotation tracks for stylized or robotic motion.
+    pub fn snapped_to_axes(&self) -> Self {
+        match self {
+            Self::UnitQuaternion(q) => Self::UnitQuaternion(nearest_axis_aligned_rotation(q)),
+            _ => self.clone(),
+        }
+    }
+
+    /// Computes the difference between the value and the given base value (`self - base`). Numbers and vectors are
+    /// subtracted, while for rotations the relative rotation `base.inverse() * self` is computed. The difference could
+    /// be added back using [`Self::add_difference`]. Returns [`None`] if the values have different types.
+    pub fn difference(&self, base: &Self) -> Option<Self> {
+        match (self, base) {
+            (Self::Real(a), Self::Real(b)) => Some(Self::Real(*a - *b)),
+            (Self::Vector2(a), Self::Vector2(b)) => Some(Self::Vector2(a - b)),
+            (Self::Vector3(a), Self::Vector3(b)) => Some(Self::Vector3(a - b)),
+            (Self::Vector4(a), Self::Vector4(b)) => Some(Self::Vector4(a - b)),
+            (Self::UnitQuaternion(a), Self::UnitQuaternion(b)) => {
+                Some(Self::UnitQuaternion(b.inverse() * a))
+            }
+            _ => None,
+        }
+    }
+
+    /// Adds the difference (see [`Self::difference`]) to the value. Numbers and vectors are added, while rotations
+    /// are post-multiplied by the relative rotation. Does nothing if the values have different types.
+    pub fn add_difference(&mut self, difference: &Self) {
+        match (self, difference) {
+            (Self::Real(a), Self::Real(b)) => *a += *b,
+            (Self::Vector2(a), Self::Vector2(b)) => *a += b,
+            (Self::Vector3(a), Self::Vector3(b)) => *a += b,
+            (Self::Vector4(a), Self::Vector4(b)) => *a += b,
+            (Self::UnitQuaternion(a), Self::UnitQuaternion(b)) => *a *= b,
+            _ => (),
+        }
+    }
+
+    /// Interpolates the current value with an other value using the given interpolation factor and returns a new
+    /// value. See [`Self::blend_with`] docs for more info.
+    pub fn interpolate(&self, other: &Self, t: f32) -> Self {
+        self.interpolate_with_path(other, t, RotationBlendPath::Shortest)
+    }
+
+    /// Same as [`Self::interpolate`], but each component of a number or a vector is interpolated using its own
+    /// interpolation from the given slice (see [`ComponentInterpolation::interpolate`]), missing components are
+    /// interpolated linearly. Rotations and values of different types are interpolated as in [`Self::interpolate`].
+    pub fn interpolate_per_component(
+        &self,
+        other: &Self,
+        t: f32,
+        interpolations: &[ComponentInterpolation],
+    ) -> Self {
+        let component = |index: usize, a: f32, b: f32| {
+            interpolations
+                .get(index)
+                .copied()
+                .unwrap_or_default()
+                .interpolate(a, b, t)
+        };
+        match (self, other) {
+            (Self::Real(a), Self::Real(b)) => Self::Real(component(0, *a, *b)),
+            (Self::Vector2(a), Self::Vector2(b)) => {
+                Self::Vector2(Vector2::from_fn(|i, _| component(i, a[i], b[i])))
+            }
+            (Self::Vector3(a), Self::Vector3(b)) => {
+                Self::Vector3(Vector3::from_fn(|i, _| component(i, a[i], b[i])))
+            }
+            (Self::Vector4(a), Self::Vector4(b)) => {
+                Self::Vector4(Vector4::from_fn(|i, _| component(i, a[i], b[i])))
+            }
+            _ => self.interpolate(other, t),
+        }
+    }
+
+    /// Same as [`Self::interpolate`], but allows you to specify how rotations are interpolated.
+    pub fn interpolate_with_path(&self, other: &Self, t: f32, path: RotationBlendPath) -> Self {
+        let mut result = self.clone();
+        result.blend_with_path(other, t, path);
+        result
+    }
+
     /// Mixes (blends) the current value with an other value using the given weight. Blending is possible only if the types
-    /// are the same.
-    pub fn blend_with(&mut self, other: &Self, weight: f32) {
+    /// are the same. Rotations are blended using the shortest arc, see [`Self::blend_with_path`] if you need the other
+    /// behaviour.
+    ///
+    /// ## Non-finite values
+    ///
+    /// If one of the values has non-finite components (NaN or infinity), which usually happens because of bad imported
+    /// data, then the other value is used as the result and `false` is returned. This prevents spreading of such values
+    /// over the entire blending chain. Nothing is written to the log, because the same bad value is usually blended
+    /// every frame - [`BoundValue::blend_with`] warns about it once per binding.
+    pub fn blend_with(&mut self, other: &Self, weight: f32) -> bool {
+        self.blend_with_path(other, weight, RotationBlendPath::Shortest)
+    }
+
+    /// Same as [`Self::blend_with`], but allows you to specify how rotations are blended.
+    pub fn blend_with_path(&mut self, other: &Self, weight: f32, path: RotationBlendPath) -> bool {
+        if std::mem::discriminant(self) == std::mem::discriminant(other) {
+            let is_self_finite = self.is_finite();
+            let is_other_finite = other.is_finite();
+            if !is_self_finite || !is_other_finite {
+                if !is_self_finite {
+                    *self = other.clone();
+                }
+                return false;
+            }
+        }
+
         match (self, other) {
             (Self::Real(a), Self::Real(b)) => *a = lerpf(*a, *b, weight),
             (Self::Vector2(a), Self::Vector2(b)) => *a = a.lerp(b, weight),
             (Self::Vector3(a), Self::Vector3(b)) => *a = a.lerp(b, weight),
             (Self::Vector4(a), Self::Vector4(b)) => *a = a.lerp(b, weight),
-            (Self::UnitQuaternion(a), Self::UnitQuaternion(b)) => *a = nlerp(*a, b, weight),
+            (Self::UnitQuaternion(a), Self::UnitQuaternion(b)) => {
+                *a = match path {
+                    RotationBlendPath::Shortest => nlerp(*a, b, weight),
+                    RotationBlendPath::Direct => a.nlerp(b, weight),
+                }
+            }
             _ => (),
         }
+        true
+    }
+
+    /// Tries to convert the value to a value with the given number of components (`1` is [`Self::Real`], `2` is
+    /// [`Self::Vector2`] and so on). Real values are broadcasted to every component of a vector, vectors are narrowed
+    /// by dropping the last components (a real number is the first component of a vector). Widening of vectors and
+    /// any conversion of quaternions is not supported and [`None`] is returned in this case.
+    pub fn coerce_to_dimension(&self, dimension: usize) -> Option<Self> {
+        let components: &[f32] = match self {
+            Self::Real(v) => std::slice::from_ref(v),
+            Self::Vector2(v) => v.as_slice(),
+            Self::Vector3(v) => v.as_slice(),
+            Self::Vector4(v) => v.as_slice(),
+            Self::UnitQuaternion(_) => return None,
+        };
+        let component = |i: usize| {
+            if components.len() == 1 {
+                Some(components[0])
+            } else {
+                components.get(i).cloned()
+            }
+        };
+        match dimension {
+            1 => Some(Self::Real(component(0)?)),
+            2 => Some(Self::Vector2(Vector2::new(component(0)?, component(1)?))),
+            3 => Some(Self::Vector3(Vector3::new(
+                component(0)?,
+                component(1)?,
+                component(2)?,
+            ))),
+            4 => Some(Self::Vector4(Vector4::new(
+                component(0)?,
+                component(1)?,
+                component(2)?,
+                component(3)?,
+            ))),
+            _ => None,
+        }
     }
 
     /// Tries to perform a numeric type casting of the current value to some other and returns a boxed value, that can
@@ -260,8 +570,11 @@ impl TrackValue {
 /// Value binding tells the animation system to which of the many properties to set track's value. It has special
 /// cases for the most used properties and a generic one for arbitrary properties. Arbitrary properties are set using
 /// reflection system, while the special cases handles bindings to standard properties (such as position, scaling, or
-/// rotation) for optimization. Reflection is quite slow to be used as the universal property setting mechanism.  
-#[derive(Clone, Visit, Reflect, Debug, PartialEq, Eq)]
+/// rotation) for optimization. Reflection is quite slow to be used as the universal property setting mechanism.
+///
+/// Bindings are totally ordered: `Position < Scale < Rotation < Property`, properties are ordered by their names
+/// first and then by their types. This order is used to keep [`BoundValueCollection`] deterministic.
+#[derive(Clone, Visit, Reflect, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
 pub enum ValueBinding {
     /// A binding to position of a scene node.
     Position,
@@ -278,6 +591,22 @@ pub enum ValueBinding {
     },
 }
 
+impl ValueBinding {
+    /// Returns `true` if the binding is one of the transform channels of a scene node (position, scale or rotation),
+    /// `false` - if it is a binding to an arbitrary property.
+    pub fn is_transform_channel(&self) -> bool {
+        !matches!(self, ValueBinding::Property { .. })
+    }
+
+    /// Returns the path of the property if the binding is [`ValueBinding::Property`], [`None`] - otherwise.
+    pub fn as_property_name(&self) -> Option<&str> {
+        match self {
+            ValueBinding::Property { name, .. } => Some(name),
+            _ => None,
+        }
+    }
+}
+
 impl Display for ValueBinding {
     fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
         match self {
@@ -289,6 +618,87 @@ impl Display for ValueBinding {
     }
 }
 
+/// A problem with a bound value, that prevents it from being applied to a target. See
+/// [`BoundValue::validate_for_object`] docs for more info.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum BindingError {
+    /// A value has a type, that cannot be used with the binding (for example a position binding with a real value).
+    ValueMismatch {
+        /// The binding of the value.
+        binding: ValueBinding,
+        /// Name of the expected type of the value.
+        expected: &'static str,
+    },
+    /// A target does not support the binding.
+    UnsupportedBinding {
+        /// The binding of the value.
+        binding: ValueBinding,
+    },
+    /// There's no property with the given path.
+    InvalidPath {
+        /// Path of the property.
+        property_name: String,
+        /// The reason why the path cannot be resolved.
+        reason: String,
+    },
+    /// A value cannot be assigned to the property, because of type mismatch.
+    TypeMismatch {
+        /// Path of the property.
+        property_name: String,
+        /// Name of the actual type of the property.
+        property_type: &'static str,
+        /// Type of the value.
+        value_type: ValueType,
+    },
+}
+
+impl Display for BindingError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            BindingError::ValueMismatch { binding, expected } => {
+                write!(f, "Value of binding {binding} must be {expected}!")
+            }
+            BindingError::UnsupportedBinding { binding } => {
+                write!(f, "Binding {binding} is not supported by the target!")
+            }
+            BindingError::InvalidPath {
+                property_name,
+                reason,
+            } => {
+                write!(f, "Invalid path of property {property_name}: {reason}")
+            }
+            BindingError::TypeMismatch {
+                property_name,
+                property_type,
+                value_type,
+            } => {
+                write!(
+                    f,
+                    "Property {property_name} has type {property_type}, which is not compatible with {value_type:?}!"
+                )
+            }
+        }
+    }
+}
+
+/// Writes a warning about a non-finite value of the given binding to the log. Bad values are usually produced by the
+/// same track every frame, so the warning is written only once per binding.
+fn warn_non_finite_value(binding: &ValueBinding, value: &TrackValue) {
+    static WARNED_BINDINGS: OnceLock<Mutex<FxHashSet<ValueBinding>>> = OnceLock::new();
+
+    if WARNED_BINDINGS
+        .get_or_init(Default::default)
+        .lock()
+        .insert(binding.clone())
+    {
+        Log::warn(format!(
+            "Attempt to blend non-finite {} animation value of {}! The non-finite value will be ignored.",
+            value.variant_name(),
+            binding
+        ));
+    }
+}
+
 /// A value that is bound to a property.
 #[derive(Clone, Debug, PartialEq)]
 pub struct BoundValue {
@@ -303,43 +713,218 @@ impl BoundValue {
     /// more info.
     pub fn blend_with(&mut self, other: &Self, weight: f32) {
         assert_eq!(self.binding, other.binding);
-        self.value.blend_with(&other.value, weight);
+        if !self.value.blend_with(&other.value, weight) {
+            warn_non_finite_value(&self.binding, &self.value);
+        }
+    }
+
+    /// Transforms the value by the given offset transform and returns a new value. Positions are rotated and then
+    /// translated by the offset, rotations are pre-multiplied by the rotation part of the offset. Values with any other
+    /// binding (scale, properties) are returned untouched, as well as values of unexpected types. This is a building
+    /// block for animation retargeting, when the animation is keyed in a space that differs from the target one.
+    pub fn transformed(&self, offset: &Isometry3<f32>) -> Self {
+        let value = match (&self.binding, &self.value) {
+            (ValueBinding::Position, TrackValue::Vector3(position)) => {
+                TrackValue::Vector3(offset.transform_point(&(*position).into()).coords)
+            }
+            (ValueBinding::Rotation, TrackValue::UnitQuaternion(rotation)) => {
+                TrackValue::UnitQuaternion(offset.rotation * rotation)
+            }
+            _ => self.value.clone(),
+        };
+        Self {
+            binding: self.binding.clone(),
+            value,
+        }
     }
 
-    /// Sets a property of the given object.
+    /// Checks whether the value can be applied to a property of the given object using [`Self::apply_to_object`],
+    /// without applying anything. It checks that the property exists and has a type that is compatible with the given
+    /// value type.
+    pub fn validate_for_object(
+        &self,
+        object: &dyn Reflect,
+        property_name: &str,
+        value_type: ValueType,
+    ) -> Result<(), BindingError> {
+        let type_mismatch = |property_type| BindingError::TypeMismatch {
+            property_name: property_name.to_string(),
+            property_type,
+            value_type,
+        };
+
+        let Some(casted) = self.value.numeric_type_cast(value_type) else {
+            return Err(type_mismatch("unknown"));
+        };
+
+        let mut casted_type_id = None;
+        casted.as_any(&mut |any| casted_type_id = Some(Any::type_id(any)));
+
+        let mut result = Ok(());
+        object.as_reflect(&mut |object_ref| {
+            object_ref.resolve_path(property_name, &mut |resolve_result| match resolve_result {
+                Err(reason) => {
+                    result = Err(BindingError::InvalidPath {
+                        property_name: property_name.to_string(),
+                        reason: reason.to_string(),
+                    })
+                }
+                Ok(property) => {
+                    // Inheritable variables accept values of their inner type.
+                    let mut property_type_ids = Vec::with_capacity(2);
+                    property.as_any(&mut |any| property_type_ids.push(Any::type_id(any)));
+                    property.as_inheritable_variable(&mut |variable| {
+                        if let Some(variable) = variable {
+                            variable
+                                .inner_value_ref()
+                                .as_any(&mut |any| property_type_ids.push(Any::type_id(any)));
+                        }
+                    });
+
+                    if !matches!(casted_type_id, Some(id) if property_type_ids.contains(&id)) {
+                        result = Err(type_mismatch(property.type_name()));
+                    }
+                }
+            })
+        });
+        result
+    }
+
+    /// Sets a property of the given object. Same as [`Self::apply_to_object_with_coercion`] with
+    /// [`ValueCoercion::Strict`].
     pub fn apply_to_object(
         &self,
         object: &mut dyn Reflect,
         property_name: &str,
         value_type: ValueType,
+    ) {
+        self.apply_to_object_with_coercion(object, property_name, value_type, ValueCoercion::Strict)
+    }
+
+    /// Sets a property of the given object. With [`ValueCoercion::BestEffort`] the following conversions are attempted
+    /// (in this order), if the value cannot be applied as is (its kind does not match the given value type, or the
+    /// value type does not match the actual type of the property):
+    ///
+    /// - The value is converted to the kind, that matches the given value type.
+    /// - The value is converted to vectors of the same scalar type as the given value type (real number first, then 2,
+    ///   3 and 4 dimensional vectors).
+    ///
+    /// See [`TrackValue::coerce_to_dimension`] for the supported conversions (broadcasting of real numbers and
+    /// narrowing of vectors). Quaternions are never converted. An error is written to the log if none of the
+    /// conversions succeeded.
+    pub fn apply_to_object_with_coercion(
+        &self,
+        object: &mut dyn Reflect,
+        property_name: &str,
+        value_type: ValueType,
+        coercion: ValueCoercion,
+    ) {
+        object.as_reflect_mut(&mut |object_ref| {
+            self.apply_to_reflect(object_ref, property_name, value_type, coercion)
+        })
+    }
+
+    /// Same as [`Self::apply_to_object_with_coercion`], but works with an object that was already borrowed via
+    /// [`Reflect::as_reflect_mut`], so multiple values could be applied within a single borrow.
+    fn apply_to_reflect(
+        &self,
+        object_ref: &mut dyn Reflect,
+        property_name: &str,
+        value_type: ValueType,
+        coercion: ValueCoercion,
+    ) {
+        if coercion == ValueCoercion::Strict {
+            self.apply_to_reflect_strict(object_ref, property_name, value_type);
+            return;
+        }
+
+        let mut candidates = vec![(value_type, self.value.clone())];
+        if let Some((row, dimension)) = value_type.dimension_row() {
+            if let Some(value) = self.value.coerce_to_dimension(dimension) {
+                if value != self.value {
+                    candidates.push((value_type, value));
+                }
+            }
+            for (i, other_type) in row.iter().enumerate() {
+                if i + 1 != dimension {
+                    if let Some(value) = self.value.coerce_to_dimension(i + 1) {
+                        candidates.push((*other_type, value));
+                    }
+                }
+            }
+        }
+
+        for (candidate_type, candidate_value) in candidates {
+            let Some(casted) = candidate_value.numeric_type_cast(candidate_type) else {
+                continue;
+            };
+            let mut set_result = None;
+            object_ref.set_field_by_path(property_name, casted, &mut |result| {
+                set_result = Some(match result {
+                    Ok(_) => Ok(()),
+                    Err(SetFieldByPathError::InvalidPath { reason, .. }) => {
+                        Err(Some(reason.to_string()))
+                    }
+                    Err(SetFieldByPathError::InvalidValue(_)) => Err(None),
+                })
+            });
+            match set_result {
+                Some(Ok(())) => return,
+                Some(Err(Some(reason))) => {
+                    Log::err(format!(
+                        "Failed to set property {}! Invalid path: {}",
+                        property_name, reason
+                    ));
+                    return;
+                }
+                _ => (),
+            }
+        }
+
+        Log::err(format!(
+            "Failed to set property {}! Types mismatch, no compatible conversion was found!",
+            property_name
+        ));
+    }
+
+    fn apply_to_reflect_strict(
+        &self,
+        object_ref: &mut dyn Reflect,
+        property_name: &str,
+        value_type: ValueType,
     ) {
         if let Some(casted) = self.value.numeric_type_cast(value_type) {
-            let mut casted = Some(casted);
-            object.as_reflect_mut(&mut |object_ref| {
-                object_ref.set_field_by_path(property_name, casted.take().unwrap(), &mut |result| {
-                    if let Err(err) = result {
-                        match err {
-                            SetFieldByPathError::InvalidPath { reason, .. } => {
-                                Log::err(format!(
-                                    "Failed to set property {}! Invalid path: {}",
-                                    property_name, reason
-                                ));
-                            }
-                            SetFieldByPathError::InvalidValue(_) => {
-                                Log::err(format!(
-                                    "Failed to set property {}! Types mismatch!",
-                                    property_name
-                                ));
-                            }
+            object_ref.set_field_by_path(property_name, casted, &mut |result| {
+                if let Err(err) = result {
+                    match err {
+                        SetFieldByPathError::InvalidPath { reason, .. } => {
+                            Log::err(format!(
+                                "Failed to set property {}! Invalid path: {}",
+                                property_name, reason
+                            ));
+                        }
+                        SetFieldByPathError::InvalidValue(_) => {
+                            Log::err(format!(
+                                "Failed to set property {}! Types mismatch!",
+                                property_name
+                            ));
                         }
                     }
-                })
+                }
             })
         }
     }
 }
 
 /// A collection of values that are bounds to some properties.
+///
+/// # Ordering
+///
+/// Every binding can be present in the collection only once and the values are kept sorted by their bindings (see
+/// [`ValueBinding`] docs for the order). This makes blending results independent of the order in which the values
+/// were added and allows fast lookups by bindings. [`Self::insert`] maintains this invariant automatically; if you fill
+/// [`Self::values`] directly, call [`Self::sort_and_deduplicate`] afterwards, otherwise lookups and blending could
+/// miss some values.
 #[derive(Clone, Debug, Default, PartialEq)]
 pub struct BoundValueCollection {
     /// Actual values collection.
@@ -347,15 +932,521 @@ pub struct BoundValueCollection {
 }
 
 impl BoundValueCollection {
+    /// Replaces bindings of the values using the given remapping table. Values whose bindings are not in the table are
+    /// left untouched. It could be used to redirect property bindings, for example `Property("old_name")` to
+    /// `Property("new_name")`, when the same animation is used for objects whose property names differ. Does nothing
+    /// if the table is empty.
+    pub fn remap_bindings(&mut self, remap: &HashMap<ValueBinding, ValueBinding>) {
+        if remap.is_empty() {
+            return;
+        }
+        for value in self.values.iter_mut() {
+            if let Some(new_binding) = remap.get(&value.binding) {
+                value.binding = new_binding.clone();
+            }
+        }
+        self.sort_and_deduplicate();
+    }
+
+    /// Sets every value with [`ValueBinding::Property`] binding to the respective property of the given object, other
+    /// values are ignored. Unlike calling [`BoundValue::apply_to_object_with_coercion`] for each value, the object is
+    /// borrowed via [`Reflect::as_reflect_mut`] only once for all the values, which is faster for objects with lots of
+    /// animated properties.
+    pub fn apply_properties_to_object(&self, object: &mut dyn Reflect, coercion: ValueCoercion) {
+        if !self
+            .values
+            .iter()
+            .any(|value| !value.binding.is_transform_channel())
+        {
+            return;
+        }
+
+        object.as_reflect_mut(&mut |object_ref| {
+            for bound_value in self.values.iter() {
+                if let ValueBinding::Property {
+                    name: ref property_name,
+                    value_type,
+                } = bound_value.binding
+                {
+                    bound_value.apply_to_reflect(object_ref, property_name, value_type, coercion);
+                }
+            }
+        })
+    }
+
+    /// Inserts a new value in the collection, keeping the values sorted by their bindings. Duplicate bindings are
+    /// rejected (with a warning) and in this case the method returns `false`.
+    pub fn insert(&mut self, value: BoundValue) -> bool {
+        match self
+            .values
+            .binary_search_by(|probe| probe.binding.cmp(&value.binding))
+        {
+            Ok(_) => {
+                Log::warn(format!(
+                    "Duplicate binding {} was rejected by bound value collection!",
+                    value.binding
+                ));
+                false
+            }
+            Err(position) => {
+                self.values.insert(position, value);
+                true
+            }
+        }
+    }
+
+    /// Sorts the values by their bindings and removes every value with duplicate binding, except the first one. A
+    /// warning is written to the log for every removed value.
+    pub fn sort_and_deduplicate(&mut self) {
+        // Stable sort keeps the first of duplicates first.
+        self.values.sort_by(|a, b| a.binding.cmp(&b.binding));
+        self.values.dedup_by(|current, previous| {
+            if current.binding == previous.binding {
+                Log::warn(format!(
+                    "Duplicate binding {} was removed from bound value collection!",
+                    current.binding
+                ));
+                true
+            } else {
+                false
+            }
+        });
+    }
+
+    /// Tries to find a value with the given binding. The values must be sorted by their bindings (see
+    /// [`Self::sort_and_deduplicate`]).
+    pub fn find(&self, binding: &ValueBinding) -> Option<&BoundValue> {
+        self.values
+            .binary_search_by(|probe| probe.binding.cmp(binding))
+            .ok()
+            .map(|index| &self.values[index])
+    }
+
     /// Tries to blend each value of the current collection with a respective (by binding) value in the other collection.
-    /// See [`TrackValue::blend_with`] docs for more info.
+    /// See [`TrackValue::blend_with`] docs for more info. The values of the other collection are looked up using
+    /// [`Self::find`], so it must be sorted by bindings.
     pub fn blend_with(&mut self, other: &Self, weight: f32) {
+        self.blend_with_weights(other, |_| weight)
+    }
+
+    /// Same as [`Self::blend_with`], but the weight is defined per binding by the given closure. It allows you to do
+    /// smooth masked blending, for example to blend more at the spine and less at the arms. A weight map could be used
+    /// like so: `|binding| weights.get(binding).cloned().unwrap_or(default_weight)`.
+    pub fn blend_with_weights<F>(&mut self, other: &Self, mut weight: F)
+    where
+        F: FnMut(&ValueBinding) -> f32,
+    {
         for value in self.values.iter_mut() {
-            if let Some(other_value) = other.values.iter().find(|v| v.binding == value.binding) {
+            if let Some(other_value) = other.find(&value.binding) {
+                let weight = weight(&value.binding);
                 value.blend_with(other_value, weight);
             }
         }
     }
+
+    /// Computes the difference pose between the collection and the given base collection (`self - base`) for authoring
+    /// of additive animations, see [`TrackValue::difference`] for more info. Values, that do not have a respective
+    /// value (by binding) of the same type in the base collection, are skipped. Adding the result to the base
+    /// collection using [`Self::add_difference`] gives the current collection.
+    pub fn difference(&self, base: &Self) -> Self {
+        let mut result = Self::default();
+        for value in self.values.iter() {
+            if let Some(difference) = base
+                .find(&value.binding)
+                .and_then(|base_value| value.value.difference(&base_value.value))
+            {
+                result.insert(BoundValue {
+                    binding: value.binding.clone(),
+                    value: difference,
+                });
+            }
+        }
+        result
+    }
+
+    /// Adds the given difference pose (see [`Self::difference`]) to the collection. Values of the difference pose, that
+    /// do not have a respective value (by binding) in the collection, are ignored.
+    pub fn add_difference(&mut self, difference: &Self) {
+        for value in self.values.iter_mut() {
+            if let Some(difference) = difference.find(&value.binding) {
+                value.value.add_difference(&difference.value);
+            }
+        }
+    }
+
+    /// Interpolates the current collection with an other collection using the given interpolation factor and returns
+    /// a new collection. See [`Self::blend_with`] docs for more info.
+    pub fn interpolate(&self, other: &Self, t: f32) -> Self {
+        let mut result = self.clone();
+        result.blend_with(other, t);
+        result
+    }
+
+    /// Blends an arbitrary amount of collections using the given weights. The weights are normalized, so they don't
+    /// need to sum up to one - for example `[2.0, 1.0, 1.0]` is the same as `[0.5, 0.25, 0.25]`. Normalization is done
+    /// for every binding separately, which means that if a binding is missing in some collections, then its value is
+    /// calculated using the weights of the collections that have it. Collections with non-positive weights are ignored.
+    pub fn blend_weighted(collections: &[(BoundValueCollection, f32)]) -> Self {
+        let mut result = Self::default();
+        let mut accumulated_weights = Vec::<f32>::new();
+
+        for (collection, weight) in collections {
+            if *weight <= 0.0 {
+                continue;
+            }
+
+            for value in collection.values.iter() {
+                match result
+                    .values
+                    .binary_search_by(|probe| probe.binding.cmp(&value.binding))
+                {
+                    Ok(index) => {
+                        let accumulated_weight = &mut accumulated_weights[index];
+                        *accumulated_weight += *weight;
+                        result.values[index].blend_with(value, *weight / *accumulated_weight);
+                    }
+                    Err(index) => {
+                        result.values.insert(index, value.clone());
+                        accumulated_weights.insert(index, *weight);
+                    }
+                }
+            }
+        }
+
+        result
+    }
+
+    /// Performs bilinear interpolation of four collections, that are placed at the corners of a unit square. `weight`
+    /// defines a point in the square, where `(0.0, 0.0)` corresponds to `bottom_left` and `(1.0, 1.0)` corresponds to
+    /// `top_right`. It could be used to implement 2D blend spaces, for example for directional movement. Rotations are
+    /// interpolated using nlerp, just like in [`Self::blend_with`].
+    pub fn bilinear_interpolate(
+        bottom_left: &Self,
+        bottom_right: &Self,
+        top_left: &Self,
+        top_right: &Self,
+        weight: Vector2<f32>,
+    ) -> Self {
+        let bottom = bottom_left.interpolate(bottom_right, weight.x);
+        let top = top_left.interpolate(top_right, weight.x);
+        bottom.interpolate(&top, weight.y)
+    }
+
+    /// Encodes the collection into a flat binary snapshot, that could be sent over the network or stored in a
+    /// save-state. The values are written in the order of their bindings (see [`ValueBinding`] docs), so equal
+    /// collections always produce equal snapshots and snapshots of the same pose layout could be compared byte by byte
+    /// (for example, for delta-compression). Numbers are written in little-endian order, property bindings are written
+    /// with their names. Use [`Self::from_snapshot`] to restore the collection.
+    pub fn to_snapshot(&self) -> Vec<u8> {
+        let mut sorted = self.clone();
+        sorted.sort_and_deduplicate();
+
+        let mut data = Vec::new();
+        data.extend_from_slice(&(sorted.values.len() as u32).to_le_bytes());
+        for value in sorted.values.iter() {
+            match &value.binding {
+                ValueBinding::Position => data.push(0),
+                ValueBinding::Scale => data.push(1),
+                ValueBinding::Rotation => data.push(2),
+                ValueBinding::Property { name, value_type } => {
+                    data.push(3);
+                    data.extend_from_slice(&(name.len() as u32).to_le_bytes());
+                    data.extend_from_slice(name.as_bytes());
+                    data.push(*value_type as u8);
+                }
+            }
+
+            let (tag, components) = match &value.value {
+                TrackValue::Real(v) => (0, std::slice::from_ref(v)),
+                TrackValue::Vector2(v) => (1, v.as_slice()),
+                TrackValue::Vector3(v) => (2, v.as_slice()),
+                TrackValue::Vector4(v) => (3, v.as_slice()),
+                TrackValue::UnitQuaternion(v) => (4, v.coords.as_slice()),
+            };
+            data.push(tag);
+            for component in components {
+                data.extend_from_slice(&component.to_le_bytes());
+            }
+        }
+        data
+    }
+
+    /// Restores a collection from a snapshot produced by [`Self::to_snapshot`]. Returns [`None`] if the snapshot is
+    /// malformed.
+    pub fn from_snapshot(snapshot: &[u8]) -> Option<Self> {
+        let mut reader = SnapshotReader { data: snapshot };
+
+        let count = reader.read_u32()?;
+        let mut collection = Self::default();
+        for _ in 0..count {
+            let binding = match reader.read_u8()? {
+                0 => ValueBinding::Position,
+                1 => ValueBinding::Scale,
+                2 => ValueBinding::Rotation,
+                3 => {
+                    let len = reader.read_u32()? as usize;
+                    let name = String::from_utf8(reader.read_bytes(len)?.to_vec()).ok()?;
+                    let value_type = ValueType::from_repr(reader.read_u8()? as usize)?;
+                    ValueBinding::Property { name, value_type }
+                }
+                _ => return None,
+            };
+
+            let value = match reader.read_u8()? {
+                0 => TrackValue::Real(reader.read_f32()?),
+                1 => TrackValue::Vector2(Vector2::new(reader.read_f32()?, reader.read_f32()?)),
+                2 => TrackValue::Vector3(Vector3::new(
+                    reader.read_f32()?,
+                    reader.read_f32()?,
+                    reader.read_f32()?,
+                )),
+                3 => TrackValue::Vector4(Vector4::new(
+                    reader.read_f32()?,
+                    reader.read_f32()?,
+                    reader.read_f32()?,
+                    reader.read_f32()?,
+                )),
+                4 => {
+                    // Components of quaternions are stored in `i, j, k, w` order.
+                    let coords = Vector4::new(
+                        reader.read_f32()?,
+                        reader.read_f32()?,
+                        reader.read_f32()?,
+                        reader.read_f32()?,
+                    );
+                    TrackValue::UnitQuaternion(UnitQuaternion::new_unchecked(Quaternion::from(
+                        coords,
+                    )))
+                }
+                _ => return None,
+            };
+
+            collection.values.push(BoundValue { binding, value });
+        }
+
+        // Trailing bytes mean that the snapshot was produced by something else.
+        if !reader.data.is_empty() {
+            return None;
+        }
+
+        collection.sort_and_deduplicate();
+        Some(collection)
+    }
+}
+
+/// A cursor over the bytes of a snapshot of [`BoundValueCollection`], every read returns [`None`] if there's not enough
+/// data.
+struct SnapshotReader<'a> {
+    data: &'a [u8],
+}
+
+impl<'a> SnapshotReader<'a> {
+    fn read_bytes(&mut self, len: usize) -> Option<&'a [u8]> {
+        if self.data.len() < len {
+            return None;
+        }
+        let (bytes, rest) = self.data.split_at(len);
+        self.data = rest;
+        Some(bytes)
+    }
+
+    fn read_u8(&mut self) -> Option<u8> {
+        Some(self.read_bytes(1)?[0])
+    }
+
+    fn read_u32(&mut self) -> Option<u32> {
+        Some(u32::from_le_bytes(self.read_bytes(4)?.try_into().ok()?))
+    }
+
+    fn read_f32(&mut self) -> Option<f32> {
+        Some(f32::from_le_bytes(self.read_bytes(4)?.try_into().ok()?))
+    }
+}
+
+/// Smooths bound values over time, which is useful for properties that would otherwise snap between values (for example
+/// if they are animated by tracks with constant keys). The smoother remembers the last produced value per binding and on
+/// each call moves it towards a new target value exponentially using a time constant of the binding - the time in which
+/// the value covers ~63% of the distance to the target. Bindings without a time constant are not smoothed.
+///
+/// Typical usage is to call [`Self::smooth`] with a pose of a node every frame and apply the result instead of the pose.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct BoundValueSmoother {
+    time_constants: Vec<(ValueBinding, f32)>,
+    previous: BoundValueCollection,
+}
+
+impl BoundValueSmoother {
+    /// Sets a time constant (in seconds) for the given binding. [`None`] or non-positive value disables smoothing of the
+    /// binding.
+    pub fn set_time_constant(&mut self, binding: ValueBinding, time_constant: Option<f32>) {
+        let index = self
+            .time_constants
+            .iter()
+            .position(|(existing, _)| *existing == binding);
+        match (
+            index,
+            time_constant.filter(|time_constant| *time_constant > 0.0),
+        ) {
+            (Some(index), Some(time_constant)) => self.time_constants[index].1 = time_constant,
+            (Some(index), None) => {
+                self.time_constants.remove(index);
+            }
+            (None, Some(time_constant)) => self.time_constants.push((binding, time_constant)),
+            (None, None) => (),
+        }
+    }
+
+    /// Returns a time constant (in seconds) of the given binding, if any.
+    pub fn time_constant(&self, binding: &ValueBinding) -> Option<f32> {
+        self.time_constants
+            .iter()
+            .find_map(|(existing, time_constant)| (existing == binding).then_some(*time_constant))
+    }
+
+    /// Moves previously produced values towards the given target values and returns the result. Values of the bindings
+    /// that were not produced before (or have no time constant) are taken from the target as is. The result is remembered
+    /// and used as a starting point for the next call.
+    pub fn smooth(&mut self, target: &BoundValueCollection, dt: f32) -> BoundValueCollection {
+        let mut result = BoundValueCollection::default();
+        for target_value in target.values.iter() {
+            let mut value = target_value.clone();
+            if let (Some(time_constant), Some(previous)) = (
+                self.time_constant(&target_value.binding),
+                self.previous.find(&target_value.binding),
+            ) {
+                let k = 1.0 - (-dt.max(0.0) / time_constant).exp();
+                value.value = previous.value.interpolate(&target_value.value, k);
+            }
+            result.insert(value);
+        }
+        self.previous = result.clone();
+        result
+    }
+
+    /// Forgets previously produced values, so the next call of [`Self::smooth`] will return target values as is.
+    pub fn reset(&mut self) {
+        self.previous.values.clear();
+    }
+}
+
+/// Moves bound values towards target values using critically damped springs, which gives smooth and natural looking
+/// transitions without overshooting the target. Unlike [`BoundValueSmoother`], the spring remembers the velocity of
+/// each channel, so a target that changes every frame (for example, a soft aim or a follow behavior) is tracked
+/// without sudden changes of the speed. Rotations are moved along the shortest arc using an angular velocity.
+///
+/// Typical usage is to call [`Self::step`] with a target pose every frame and apply the result instead of the pose.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct BoundValueSpring {
+    current: BoundValueCollection,
+    // Quaternions use the first three components for the angular velocity (as a scaled axis).
+    velocities: Vec<(ValueBinding, Vector4<f32>)>,
+}
+
+impl BoundValueSpring {
+    /// Moves previously produced values towards the given target values and returns the result. `stiffness` defines
+    /// the speed of the springs - it is the natural frequency of the springs (in radians per second), the value covers
+    /// ~90% of the distance to a still target in `4 / stiffness` seconds. Values of the bindings that were not produced
+    /// before (or changed their type) are taken from the target as is. The result is remembered and used as a starting
+    /// point for the next call.
+    pub fn step(
+        &mut self,
+        target: &BoundValueCollection,
+        stiffness: f32,
+        dt: f32,
+    ) -> BoundValueCollection {
+        let stiffness = stiffness.max(0.0);
+        let dt = dt.max(0.0);
+
+        let mut result = BoundValueCollection::default();
+        let mut velocities = Vec::with_capacity(target.values.len());
+        for target_value in target.values.iter() {
+            let mut value = target_value.clone();
+            let mut velocity = self.velocity(&target_value.binding).unwrap_or_default();
+            match self
+                .current
+                .find(&target_value.binding)
+                .and_then(|current| {
+                    spring_step(
+                        &current.value,
+                        &target_value.value,
+                        &mut velocity,
+                        stiffness,
+                        dt,
+                    )
+                }) {
+                Some(new_value) => value.value = new_value,
+                None => velocity = Vector4::default(),
+            }
+            velocities.push((target_value.binding.clone(), velocity));
+            result.insert(value);
+        }
+        self.current = result.clone();
+        self.velocities = velocities;
+        result
+    }
+
+    /// Returns current velocity of the given binding, if any. Rotations store their angular velocity (as a scaled axis)
+    /// in the first three components.
+    pub fn velocity(&self, binding: &ValueBinding) -> Option<Vector4<f32>> {
+        self.velocities
+            .iter()
+            .find_map(|(existing, velocity)| (existing == binding).then_some(*velocity))
+    }
+
+    /// Forgets previously produced values and velocities, so the next call of [`Self::step`] will return target values
+    /// as is.
+    pub fn reset(&mut self) {
+        self.current.values.clear();
+        self.velocities.clear();
+    }
+}
+
+/// Advances a critically damped spring, that pulls the given offset from a target towards zero. Returns the new offset.
+fn damp_offset(
+    offset: Vector4<f32>,
+    velocity: &mut Vector4<f32>,
+    stiffness: f32,
+    dt: f32,
+) -> Vector4<f32> {
+    let decay = (-stiffness * dt).exp();
+    let temp = (*velocity + offset.scale(stiffness)).scale(dt);
+    *velocity = (*velocity - temp.scale(stiffness)).scale(decay);
+    (offset + temp).scale(decay)
+}
+
+/// Moves the current value towards the target one using a critically damped spring. Returns [`None`] if the values have
+/// different types.
+fn spring_step(
+    current: &TrackValue,
+    target: &TrackValue,
+    velocity: &mut Vector4<f32>,
+    stiffness: f32,
+    dt: f32,
+) -> Option<TrackValue> {
+    let mut damp = |offset: Vector4<f32>| damp_offset(offset, velocity, stiffness, dt);
+    let value = match (current, target) {
+        (TrackValue::Real(current), TrackValue::Real(target)) => {
+            TrackValue::Real(target + damp(Vector4::new(current - target, 0.0, 0.0, 0.0)).x)
+        }
+        (TrackValue::Vector2(current), TrackValue::Vector2(target)) => {
+            let offset = current - target;
+            TrackValue::Vector2(target + damp(Vector4::new(offset.x, offset.y, 0.0, 0.0)).xy())
+        }
+        (TrackValue::Vector3(current), TrackValue::Vector3(target)) => {
+            TrackValue::Vector3(target + damp((current - target).push(0.0)).xyz())
+        }
+        (TrackValue::Vector4(current), TrackValue::Vector4(target)) => {
+            TrackValue::Vector4(target + damp(current - target))
+        }
+        (TrackValue::UnitQuaternion(current), TrackValue::UnitQuaternion(target)) => {
+            // The offset is the rotation from the target to the current rotation, it always takes the shortest arc.
+            let offset = (current * target.inverse()).scaled_axis();
+            let offset = damp(offset.push(0.0)).xyz();
+            TrackValue::UnitQuaternion(UnitQuaternion::from_scaled_axis(offset) * target)
+        }
+        _ => return None,
+    };
+    Some(value)
 }
 
 /// Interpolates from `a` to `b` using nlerp, including an additional check to ensure
@@ -371,3 +1462,721 @@ pub fn nlerp(mut a: UnitQuaternion<f32>, b: &UnitQuaternion<f32>, w: f32) -> Uni
 pub fn negate_unit_quaternion(a: &UnitQuaternion<f32>) -> UnitQuaternion<f32> {
     Unit::new_unchecked(-a.as_ref())
 }
+
+/// Returns the nearest (by rotation angle) of 24 axis-aligned rotations, that map coordinate axes to coordinate axes:
+/// identity, rotations by 90, 180 and 270 degrees around the coordinate axes, rotations by 180 degrees around face
+/// diagonals and rotations by 120 and 240 degrees around body diagonals. The sign of the result is chosen to be in the
+/// same hemisphere as the given rotation, so sequences of snapped rotations interpolate along the shortest arc.
+pub fn nearest_axis_aligned_rotation(q: &UnitQuaternion<f32>) -> UnitQuaternion<f32> {
+    let coords = q.coords;
+    let mut nearest = Vector4::new(0.0, 0.0, 0.0, 1.0);
+    let mut nearest_dot = -1.0;
+    let mut check = |candidate: Vector4<f32>| {
+        let dot = candidate.dot(&coords).abs();
+        if dot > nearest_dot {
+            nearest_dot = dot;
+            nearest = candidate;
+        }
+    };
+
+    // Quaternions (up to the sign) of the rotations have one component equal to 1, two components equal to
+    // 1/sqrt(2) by magnitude, or four components equal to 1/2 by magnitude.
+    let h = std::f32::consts::FRAC_1_SQRT_2;
+    for i in 0..4 {
+        let mut candidate = Vector4::zeros();
+        candidate[i] = 1.0;
+        check(candidate);
+
+        for j in (i + 1)..4 {
+            for sign in [-1.0, 1.0] {
+                let mut candidate = Vector4::zeros();
+                candidate[i] = h;
+                candidate[j] = sign * h;
+                check(candidate);
+            }
+        }
+    }
+    for signs in 0..8 {
+        let sign = |bit: u32| if signs & (1 << bit) != 0 { -0.5 } else { 0.5 };
+        check(Vector4::new(0.5, sign(0), sign(1), sign(2)));
+    }
+
+    if nearest.dot(&coords) < 0.0 {
+        nearest = -nearest;
+    }
+    UnitQuaternion::new_unchecked(Quaternion::from(nearest))
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{
+        container::ComponentInterpolation,
+        core::{
+            algebra::{Isometry3, UnitQuaternion, Vector2, Vector3, Vector4},
+            reflect::prelude::*,
+            variable::InheritableVariable,
+        },
+        value::{
+            nearest_axis_aligned_rotation, negate_unit_quaternion, nlerp, BindingError, BoundValue,
+            BoundValueCollection, BoundValueSmoother, BoundValueSpring, RotationBlendPath,
+            TrackValue, ValueBinding, ValueCoercion, ValueType,
+        },
+    };
+    use std::collections::HashMap;
+
+    fn position(x: f32) -> BoundValue {
+        BoundValue {
+            binding: ValueBinding::Position,
+            value: TrackValue::Vector3(Vector3::new(x, 0.0, 0.0)),
+        }
+    }
+
+    fn property(name: &str, value: f32) -> BoundValue {
+        BoundValue {
+            binding: ValueBinding::Property {
+                name: name.to_string(),
+                value_type: ValueType::F32,
+            },
+            value: TrackValue::Real(value),
+        }
+    }
+
+    #[test]
+    fn test_bound_value_collection_insert_rejects_duplicates() {
+        let mut collection = BoundValueCollection::default();
+
+        assert!(collection.insert(property("b", 1.0)));
+        assert!(collection.insert(position(1.0)));
+        assert!(collection.insert(property("a", 2.0)));
+        assert!(!collection.insert(position(2.0)));
+
+        assert_eq!(
+            collection.values,
+            vec![position(1.0), property("a", 2.0), property("b", 1.0)]
+        );
+    }
+
+    #[test]
+    fn test_bound_value_collection_blending_with_duplicates() {
+        let collection = |values: Vec<BoundValue>| {
+            let mut collection = BoundValueCollection::default();
+            for value in values {
+                collection.insert(value);
+            }
+            collection
+        };
+        let mut a = collection(vec![property("b", 0.0), position(0.0), position(10.0)]);
+        let mut a_reordered = collection(vec![position(0.0), property("b", 0.0), position(10.0)]);
+        let b = collection(vec![position(2.0), property("b", 4.0), position(100.0)]);
+
+        a.blend_with(&b, 0.5);
+        a_reordered.blend_with(&b, 0.5);
+
+        // Duplicates must be removed and the order must not depend on insertion order.
+        let expected = vec![position(1.0), property("b", 2.0)];
+        assert_eq!(a.values, expected);
+        assert_eq!(a_reordered.values, expected);
+    }
+
+    #[test]
+    fn test_track_value_non_finite_guard() {
+        let finite = TrackValue::Vector3(Vector3::new(1.0, 2.0, 3.0));
+        let non_finite = TrackValue::Vector3(Vector3::new(f32::NAN, 0.0, 0.0));
+
+        assert!(!non_finite.is_finite());
+        assert_eq!(finite.interpolate(&non_finite, 0.5), finite);
+        assert_eq!(non_finite.interpolate(&finite, 0.5), finite);
+
+        let mut value = non_finite.clone();
+        assert!(!value.blend_with(&finite, 0.25));
+        assert_eq!(value, finite);
+        assert!(value.blend_with(&finite, 0.25));
+    }
+
+    #[test]
+    fn test_bound_value_collection_bilinear_interpolate() {
+        fn corner(x: f32, y: f32, angle: f32) -> BoundValueCollection {
+            BoundValueCollection {
+                values: vec![
+                    BoundValue {
+                        binding: ValueBinding::Position,
+                        value: TrackValue::Vector3(Vector3::new(x, y, 0.0)),
+                    },
+                    BoundValue {
+                        binding: ValueBinding::Rotation,
+                        value: TrackValue::UnitQuaternion(UnitQuaternion::from_axis_angle(
+                            &Vector3::y_axis(),
+                            angle,
+                        )),
+                    },
+                ],
+            }
+        }
+
+        let bottom_left = corner(0.0, 0.0, 0.0);
+        let bottom_right = corner(1.0, 0.0, 0.5);
+        let top_left = corner(0.0, 1.0, 1.0);
+        let top_right = corner(1.0, 1.0, 1.5);
+
+        let result = BoundValueCollection::bilinear_interpolate(
+            &bottom_left,
+            &bottom_right,
+            &top_left,
+            &top_right,
+            Vector2::new(0.25, 0.5),
+        );
+
+        let rotation = |c: &BoundValueCollection| match c.values[1].value {
+            TrackValue::UnitQuaternion(q) => q,
+            _ => unreachable!(),
+        };
+        let expected_rotation = nlerp(
+            nlerp(rotation(&bottom_left), &rotation(&bottom_right), 0.25),
+            &nlerp(rotation(&top_left), &rotation(&top_right), 0.25),
+            0.5,
+        );
+
+        assert_eq!(
+            result.values[0].value,
+            TrackValue::Vector3(Vector3::new(0.25, 0.5, 0.0))
+        );
+        assert_eq!(
+            result.values[1].value,
+            TrackValue::UnitQuaternion(expected_rotation)
+        );
+    }
+
+    #[test]
+    fn test_bound_value_collection_blend_weighted() {
+        let collections = [
+            (
+                BoundValueCollection {
+                    values: vec![position(0.0), property("a", 0.0)],
+                },
+                2.0,
+            ),
+            (
+                BoundValueCollection {
+                    values: vec![position(4.0)],
+                },
+                1.0,
+            ),
+            (
+                BoundValueCollection {
+                    values: vec![position(8.0), property("a", 3.0)],
+                },
+                1.0,
+            ),
+        ];
+
+        let result = BoundValueCollection::blend_weighted(&collections);
+
+        // Position: (2.0 * 0.0 + 1.0 * 4.0 + 1.0 * 8.0) / 4.0; property: (2.0 * 0.0 + 1.0 * 3.0) / 3.0
+        assert_eq!(result.values, vec![position(3.0), property("a", 1.0)]);
+    }
+
+    #[test]
+    fn test_bound_value_smoother() {
+        let mut smoother = BoundValueSmoother::default();
+        smoother.set_time_constant(property("a", 0.0).binding, Some(1.0));
+
+        let target = |value: f32| BoundValueCollection {
+            values: vec![position(value), property("a", value)],
+        };
+
+        // Nothing to smooth from at first.
+        assert_eq!(smoother.smooth(&target(0.0), 0.1), target(0.0));
+
+        let result = smoother.smooth(&target(10.0), 1.0);
+        assert_eq!(result.values[0], position(10.0));
+        let expected = 10.0 * (1.0 - (-1.0f32).exp());
+        assert_eq!(result.values[1], property("a", expected));
+
+        smoother.reset();
+        assert_eq!(smoother.smooth(&target(5.0), 1.0), target(5.0));
+    }
+
+    #[test]
+    fn test_bound_value_spring() {
+        let mut spring = BoundValueSpring::default();
+
+        let target = |value: f32| BoundValueCollection {
+            values: vec![
+                position(value),
+                BoundValue {
+                    binding: ValueBinding::Rotation,
+                    value: TrackValue::UnitQuaternion(UnitQuaternion::from_euler_angles(
+                        0.0, value, 0.0,
+                    )),
+                },
+            ],
+        };
+
+        // Nothing to move from at first.
+        assert_eq!(spring.step(&target(0.0), 10.0, 0.1), target(0.0));
+
+        // A critically damped spring moves towards the target without overshooting.
+        let mut previous_x = 0.0;
+        for _ in 0..30 {
+            let result = spring.step(&target(1.0), 10.0, 0.1);
+            let TrackValue::Vector3(position) = result.values[0].value else {
+                unreachable!()
+            };
+            assert!(position.x >= previous_x && position.x <= 1.0);
+            previous_x = position.x;
+        }
+        assert!((previous_x - 1.0).abs() < 1.0e-3);
+        assert!(spring.velocity(&ValueBinding::Position).unwrap().norm() < 1.0e-2);
+
+        let result = spring.step(&target(1.0), 10.0, 0.1);
+        let TrackValue::UnitQuaternion(rotation) = result.values[1].value else {
+            unreachable!()
+        };
+        assert!(rotation.angle_to(&UnitQuaternion::from_euler_angles(0.0, 1.0, 0.0)) < 1.0e-3);
+
+        spring.reset();
+        assert_eq!(spring.step(&target(5.0), 10.0, 0.1), target(5.0));
+    }
+
+    #[test]
+    fn test_bound_value_validate_for_object() {
+        #[derive(Reflect, Debug, Default)]
+        struct Object {
+            real: f32,
+            inheritable: InheritableVariable<f32>,
+            vector: Vector3<f32>,
+        }
+
+        let object = Object::default();
+        let value = property("real", 1.0);
+
+        assert_eq!(
+            value.validate_for_object(&object, "real", ValueType::F32),
+            Ok(())
+        );
+        assert_eq!(
+            value.validate_for_object(&object, "inheritable", ValueType::F32),
+            Ok(())
+        );
+        assert!(matches!(
+            value.validate_for_object(&object, "vector", ValueType::F32),
+            Err(BindingError::TypeMismatch { .. })
+        ));
+        assert!(matches!(
+            value.validate_for_object(&object, "missing", ValueType::F32),
+            Err(BindingError::InvalidPath { .. })
+        ));
+    }
+
+    #[test]
+    fn test_rotation_blend_shortest_path() {
+        let rotation = |value: TrackValue| match value {
+            TrackValue::UnitQuaternion(q) => q,
+            _ => unreachable!(),
+        };
+
+        // A quaternion and its negation is the same rotation, blending must not rotate at all.
+        let q = UnitQuaternion::from_axis_angle(&Vector3::y_axis(), 1.0);
+        let a = TrackValue::UnitQuaternion(q);
+        let b = TrackValue::UnitQuaternion(negate_unit_quaternion(&q));
+        for t in [0.0, 0.25, 0.5, 0.75, 1.0] {
+            assert!(rotation(a.interpolate(&b, t)).angle_to(&q) < 1.0e-5);
+        }
+
+        // 90 degrees rotation given by negated quaternion, the shortest arc passes through 45 degrees,
+        // while the long one - through 135 degrees.
+        let a = TrackValue::UnitQuaternion(UnitQuaternion::identity());
+        let b = TrackValue::UnitQuaternion(negate_unit_quaternion(
+            &UnitQuaternion::from_axis_angle(&Vector3::z_axis(), std::f32::consts::FRAC_PI_2),
+        ));
+        let shortest = rotation(a.interpolate(&b, 0.5));
+        assert!((shortest.angle() - std::f32::consts::FRAC_PI_4).abs() < 1.0e-5);
+        let direct = rotation(a.interpolate_with_path(&b, 0.5, RotationBlendPath::Direct));
+        assert!((direct.angle() - 3.0 * std::f32::consts::FRAC_PI_4).abs() < 1.0e-5);
+    }
+
+    #[test]
+    fn test_bound_value_transformed() {
+        let offset = Isometry3::new(
+            Vector3::new(1.0, 2.0, 3.0),
+            Vector3::z() * std::f32::consts::FRAC_PI_2,
+        );
+
+        let position = BoundValue {
+            binding: ValueBinding::Position,
+            value: TrackValue::Vector3(Vector3::new(1.0, 0.0, 0.0)),
+        };
+        let TrackValue::Vector3(transformed) = position.transformed(&offset).value else {
+            unreachable!()
+        };
+        assert!((transformed - Vector3::new(1.0, 3.0, 3.0)).norm() < 1.0e-5);
+
+        let rotation = BoundValue {
+            binding: ValueBinding::Rotation,
+            value: TrackValue::UnitQuaternion(UnitQuaternion::identity()),
+        };
+        let TrackValue::UnitQuaternion(transformed) = rotation.transformed(&offset).value else {
+            unreachable!()
+        };
+        assert!(transformed.angle_to(&offset.rotation) < 1.0e-5);
+
+        for value in [
+            BoundValue {
+                binding: ValueBinding::Scale,
+                value: TrackValue::Vector3(Vector3::new(1.0, 0.0, 0.0)),
+            },
+            BoundValue {
+                binding: ValueBinding::Property {
+                    name: "foo".to_string(),
+                    value_type: ValueType::Vector3F32,
+                },
+                value: TrackValue::Vector3(Vector3::new(1.0, 0.0, 0.0)),
+            },
+        ] {
+            assert_eq!(value.transformed(&offset), value);
+        }
+    }
+
+    #[test]
+    fn test_apply_to_object_with_coercion() {
+        #[derive(Reflect, Debug, Default)]
+        struct Foo {
+            vec2: Vector2<f32>,
+            vec3: Vector3<f32>,
+        }
+
+        let mut foo = Foo::default();
+
+        // Vector3 value to Vector2 property, by dropping z.
+        let value = BoundValue {
+            binding: ValueBinding::Position,
+            value: TrackValue::Vector3(Vector3::new(1.0, 2.0, 3.0)),
+        };
+        value.apply_to_object(&mut foo, "vec2", ValueType::Vector3F32);
+        assert_eq!(foo.vec2, Vector2::default());
+        value.apply_to_object_with_coercion(
+            &mut foo,
+            "vec2",
+            ValueType::Vector3F32,
+            ValueCoercion::BestEffort,
+        );
+        assert_eq!(foo.vec2, Vector2::new(1.0, 2.0));
+
+        // Real value to Vector3 property, by broadcasting.
+        let value = BoundValue {
+            binding: ValueBinding::Position,
+            value: TrackValue::Real(5.0),
+        };
+        value.apply_to_object(&mut foo, "vec3", ValueType::Vector3F32);
+        assert_eq!(foo.vec3, Vector3::default());
+        value.apply_to_object_with_coercion(
+            &mut foo,
+            "vec3",
+            ValueType::Vector3F32,
+            ValueCoercion::BestEffort,
+        );
+        assert_eq!(foo.vec3, Vector3::repeat(5.0));
+
+        // Widening is not supported.
+        let value = BoundValue {
+            binding: ValueBinding::Position,
+            value: TrackValue::Vector2(Vector2::new(1.0, 2.0)),
+        };
+        value.apply_to_object_with_coercion(
+            &mut foo,
+            "vec3",
+            ValueType::Vector2F32,
+            ValueCoercion::BestEffort,
+        );
+        assert_eq!(foo.vec3, Vector3::repeat(5.0));
+    }
+
+    #[test]
+    fn test_apply_properties_to_object() {
+        #[derive(Reflect, Debug, Default)]
+        struct Foo {
+            a: f32,
+            b: f32,
+            vec3: Vector3<f32>,
+        }
+
+        let mut foo = Foo::default();
+        let mut values = BoundValueCollection::default();
+        values.insert(property("a", 1.0));
+        values.insert(property("b", 2.0));
+        values.insert(BoundValue {
+            binding: ValueBinding::Property {
+                name: "vec3".to_string(),
+                value_type: ValueType::Vector3F32,
+            },
+            value: TrackValue::Real(3.0),
+        });
+        // Non-property bindings are ignored.
+        values.insert(position(4.0));
+
+        values.apply_properties_to_object(&mut foo, ValueCoercion::Strict);
+        assert_eq!((foo.a, foo.b), (1.0, 2.0));
+        assert_eq!(foo.vec3, Vector3::default());
+
+        values.apply_properties_to_object(&mut foo, ValueCoercion::BestEffort);
+        assert_eq!(foo.vec3, Vector3::repeat(3.0));
+    }
+
+    #[test]
+    fn test_weighted_clone() {
+        let q = UnitQuaternion::from_axis_angle(&Vector3::x_axis(), 1.0);
+        for rotation in [q, negate_unit_quaternion(&q)] {
+            let value = TrackValue::UnitQuaternion(rotation);
+            let TrackValue::UnitQuaternion(zero) = value.weighted_clone(0.0) else {
+                unreachable!()
+            };
+            assert!(zero.angle() < 1.0e-5);
+            let TrackValue::UnitQuaternion(full) = value.weighted_clone(1.0) else {
+                unreachable!()
+            };
+            assert!(full.angle_to(&q) < 1.0e-5);
+            let TrackValue::UnitQuaternion(half) = value.weighted_clone(0.5) else {
+                unreachable!()
+            };
+            assert!((half.angle() - 0.5).abs() < 1.0e-5);
+        }
+
+        assert_eq!(
+            TrackValue::Vector3(Vector3::new(1.0, 2.0, 3.0)).weighted_clone(0.5),
+            TrackValue::Vector3(Vector3::new(0.5, 1.0, 1.5))
+        );
+        assert_eq!(
+            TrackValue::Real(2.0).weighted_clone(0.0),
+            TrackValue::Real(0.0)
+        );
+    }
+
+    #[test]
+    fn test_bound_value_collection_blend_with_weights() {
+        let collection = |position: f32, scale: f32| {
+            let mut collection = BoundValueCollection::default();
+            collection.insert(BoundValue {
+                binding: ValueBinding::Position,
+                value: TrackValue::Vector3(Vector3::repeat(position)),
+            });
+            collection.insert(BoundValue {
+                binding: ValueBinding::Scale,
+                value: TrackValue::Vector3(Vector3::repeat(scale)),
+            });
+            collection
+        };
+
+        let mut a = collection(0.0, 0.0);
+        a.blend_with_weights(&collection(1.0, 1.0), |binding| match binding {
+            ValueBinding::Position => 0.25,
+            _ => 1.0,
+        });
+        assert_eq!(a, collection(0.25, 1.0));
+
+        let mut uniform = collection(0.0, 0.0);
+        uniform.blend_with(&collection(1.0, 1.0), 0.5);
+        assert_eq!(uniform, collection(0.5, 0.5));
+    }
+
+    #[test]
+    fn test_remap_bindings() {
+        let property = |name: &str| ValueBinding::Property {
+            name: name.into(),
+            value_type: ValueType::F32,
+        };
+        let mut collection = BoundValueCollection::default();
+        collection.insert(BoundValue {
+            binding: property("old_name"),
+            value: TrackValue::Real(1.0),
+        });
+        collection.insert(BoundValue {
+            binding: ValueBinding::Position,
+            value: TrackValue::Vector3(Vector3::repeat(2.0)),
+        });
+
+        collection.remap_bindings(&Default::default());
+        assert_eq!(collection.values[1].binding, property("old_name"));
+
+        collection.remap_bindings(&HashMap::from([(
+            property("old_name"),
+            property("new_name"),
+        )]));
+        assert_eq!(collection.find(&property("old_name")), None);
+        assert_eq!(
+            collection.find(&property("new_name")).map(|v| &v.value),
+            Some(&TrackValue::Real(1.0))
+        );
+        assert_eq!(
+            collection.find(&ValueBinding::Position).map(|v| &v.value),
+            Some(&TrackValue::Vector3(Vector3::repeat(2.0)))
+        );
+    }
+
+    #[test]
+    fn test_nearest_axis_aligned_rotation() {
+        let quarter = std::f32::consts::FRAC_PI_2;
+        let mut rotations: Vec<UnitQuaternion<f32>> = Vec::new();
+        for x in 0..4 {
+            for y in 0..4 {
+                for z in 0..4 {
+                    let rotation = UnitQuaternion::from_euler_angles(
+                        x as f32 * quarter,
+                        y as f32 * quarter,
+                        z as f32 * quarter,
+                    );
+                    if rotations.iter().all(|r| r.angle_to(&rotation) > 1.0e-3) {
+                        rotations.push(rotation);
+                    }
+                }
+            }
+        }
+        assert_eq!(rotations.len(), 24);
+
+        let perturbation = UnitQuaternion::from_euler_angles(0.2, -0.1, 0.15);
+        for rotation in rotations.iter() {
+            let snapped = nearest_axis_aligned_rotation(&(perturbation * rotation));
+            assert!(snapped.angle_to(rotation) < 1.0e-3);
+            assert!(snapped.dot(&(perturbation * rotation)) > 0.0);
+        }
+
+        let snapped = TrackValue::UnitQuaternion(perturbation).snapped_to_axes();
+        assert_eq!(
+            snapped,
+            TrackValue::UnitQuaternion(UnitQuaternion::identity())
+        );
+        assert_eq!(
+            TrackValue::Real(0.3).snapped_to_axes(),
+            TrackValue::Real(0.3)
+        );
+    }
+
+    #[test]
+    fn test_interpolate_per_component() {
+        let a = TrackValue::Vector3(Vector3::new(0.0, 0.0, 0.0));
+        let b = TrackValue::Vector3(Vector3::new(1.0, 1.0, 1.0));
+        assert_eq!(
+            a.interpolate_per_component(
+                &b,
+                0.25,
+                &[ComponentInterpolation::Linear, ComponentInterpolation::Step]
+            ),
+            TrackValue::Vector3(Vector3::new(0.25, 0.0, 0.25))
+        );
+    }
+
+    #[test]
+    fn test_value_binding_kind() {
+        for binding in [
+            ValueBinding::Position,
+            ValueBinding::Scale,
+            ValueBinding::Rotation,
+        ] {
+            assert!(binding.is_transform_channel());
+            assert_eq!(binding.as_property_name(), None);
+        }
+
+        let binding = ValueBinding::Property {
+            name: "foo.bar".to_string(),
+            value_type: ValueType::F32,
+        };
+        assert!(!binding.is_transform_channel());
+        assert_eq!(binding.as_property_name(), Some("foo.bar"));
+    }
+
+    #[test]
+    fn test_difference() {
+        let pose = |x: f32, angle: f32, property_value: f32| BoundValueCollection {
+            values: vec![
+                position(x),
+                BoundValue {
+                    binding: ValueBinding::Rotation,
+                    value: TrackValue::UnitQuaternion(UnitQuaternion::from_axis_angle(
+                        &Vector3::y_axis(),
+                        angle,
+                    )),
+                },
+                property("foo", property_value),
+            ],
+        };
+        let base = pose(1.0, 0.5, 2.0);
+        let target = pose(3.0, 1.5, -1.0);
+
+        let difference = target.difference(&base);
+        assert_eq!(difference.values.len(), 3);
+        assert_eq!(
+            difference.find(&ValueBinding::Position).unwrap().value,
+            TrackValue::Vector3(Vector3::new(2.0, 0.0, 0.0))
+        );
+
+        let mut result = base.clone();
+        result.add_difference(&difference);
+        for (value, expected) in result.values.iter().zip(target.values.iter()) {
+            assert_eq!(value.binding, expected.binding);
+            match (&value.value, &expected.value) {
+                (TrackValue::UnitQuaternion(a), TrackValue::UnitQuaternion(b)) => {
+                    assert!(a.angle_to(b) < 1.0e-5)
+                }
+                (a, b) => assert_eq!(a, b),
+            }
+        }
+
+        // Values without a base are skipped.
+        assert!(target
+            .difference(&BoundValueCollection::default())
+            .values
+            .is_empty());
+    }
+
+    #[test]
+    fn test_snapshot_round_trip() {
+        let mut collection = BoundValueCollection::default();
+        collection.insert(BoundValue {
+            binding: ValueBinding::Property {
+                name: "material.color".to_owned(),
+                value_type: ValueType::Vector4F32,
+            },
+            value: TrackValue::Vector4(Vector4::new(0.1, 0.2, 0.3, 1.0)),
+        });
+        collection.insert(BoundValue {
+            binding: ValueBinding::Rotation,
+            value: TrackValue::UnitQuaternion(UnitQuaternion::from_euler_angles(0.1, 0.2, 0.3)),
+        });
+        collection.insert(BoundValue {
+            binding: ValueBinding::Position,
+            value: TrackValue::Vector3(Vector3::new(1.0, 2.0, 3.0)),
+        });
+        collection.insert(BoundValue {
+            binding: ValueBinding::Property {
+                name: "weight".to_owned(),
+                value_type: ValueType::F64,
+            },
+            value: TrackValue::Real(0.5),
+        });
+
+        let snapshot = collection.to_snapshot();
+        assert_eq!(
+            BoundValueCollection::from_snapshot(&snapshot),
+            Some(collection.clone())
+        );
+
+        // The order of insertion does not matter.
+        let mut reversed = BoundValueCollection::default();
+        for value in collection.values.iter().rev() {
+            reversed.values.push(value.clone());
+        }
+        assert_eq!(reversed.to_snapshot(), snapshot);
+
+        // Malformed snapshots.
+        assert_eq!(
+            BoundValueCollection::from_snapshot(&snapshot[..snapshot.len() - 1]),
+            None
+        );
+        let mut extra = snapshot.clone();
+        extra.push(0);
+        assert_eq!(BoundValueCollection::from_snapshot(&extra), None);
+        assert_eq!(
+            BoundValueCollection::from_snapshot(&BoundValueCollection::default().to_snapshot()),
+            Some(BoundValueCollection::default())
+        );
+    }
+}
@@ -155,3 +155,5 @@ impl_visit!(
         pub keys: Vec<CurveKey>,
     }
 );
+
+crate::uuid_provider!(Curve = "3c2c9e1e-5a4d-4b7a-8f0e-2d6b1f7c9a54");
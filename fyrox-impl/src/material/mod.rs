@@ -18,7 +18,9 @@ use crate::{
         visitor::{prelude::*, RegionGuard},
         TypeUuidProvider,
     },
-    material::shader::{PropertyKind, SamplerFallback, ShaderResource, ShaderResourceExtension},
+    material::shader::{
+        PropertyKind, SamplerFallback, Shader, ShaderResource, ShaderResourceExtension,
+    },
     resource::texture::{Texture, TextureResource},
 };
 use fxhash::FxHashMap;
@@ -28,6 +30,7 @@ use lazy_static::lazy_static;
 use std::error::Error;
 use std::{
     any::Any,
+    collections::VecDeque,
     fmt::{Display, Formatter},
     ops::Deref,
     path::Path,
@@ -43,7 +46,7 @@ pub mod shader;
 ///
 /// There is a limited set of possible types that can be passed to a shader, most of them are
 /// just simple data types.
-#[derive(Debug, Visit, Clone, Reflect)]
+#[derive(Debug, Visit, Clone, PartialEq, Reflect)]
 pub enum PropertyValue {
     /// Real number.
     Float(f32),
@@ -203,6 +206,85 @@ impl PropertyValue {
         }
     }
 
+    /// Creates a property value of the type that the given shader expects for a property with the
+    /// given name. The value is initialized with the default value from the shader definition. Use
+    /// this method to get correctly typed value, instead of guessing its type. Returns
+    /// [`MaterialError::NoSuchProperty`] if the shader does not have such property or the shader
+    /// is not loaded.
+    pub fn from_shader_property(
+        shader: &ShaderResource,
+        name: &str,
+        resource_manager: Option<&ResourceManager>,
+    ) -> Result<Self, MaterialError> {
+        let mut state = shader.state();
+        state
+            .data()
+            .and_then(|shader| {
+                shader
+                    .definition
+                    .properties
+                    .iter()
+                    .find(|property| property.name == name)
+            })
+            .map(|property| Self::from_property_kind(&property.kind, resource_manager))
+            .ok_or_else(|| MaterialError::NoSuchProperty {
+                property_name: name.to_string(),
+            })
+    }
+
+    /// Checks whether the value has the type that the given shader expects for a property with the
+    /// given name and returns the value back on success. Returns [`MaterialError::TypeMismatch`] if
+    /// the types do not match and [`MaterialError::NoSuchProperty`] if the shader does not have such
+    /// property.
+    pub fn checked_for_shader(
+        self,
+        shader: &ShaderResource,
+        name: &str,
+    ) -> Result<Self, MaterialError> {
+        let expected = Self::from_shader_property(shader, name, None)?;
+        if expected.is_same_type(&self) {
+            Ok(self)
+        } else {
+            Err(MaterialError::TypeMismatch {
+                property_name: name.to_string(),
+                expected,
+                given: self,
+            })
+        }
+    }
+
+    /// Returns `true` if both values have the same type (variant), `false` - otherwise. Actual
+    /// values are not compared.
+    pub fn is_same_type(&self, other: &Self) -> bool {
+        std::mem::discriminant(self) == std::mem::discriminant(other)
+    }
+
+    /// Tries to convert the value to the type of the given value. Numbers are converted between
+    /// each other (real numbers are rounded when converted to integers), colors are converted to
+    /// three- or four-dimensional vectors and vice versa, samplers keep their texture, but take the
+    /// fallback of the given value. Returns [`None`] if there's no meaningful conversion. Values
+    /// of the same type are returned as is.
+    pub fn converted_to_type_of(&self, other: &Self) -> Option<Self> {
+        match (self, other) {
+            (Self::Sampler { value, .. }, Self::Sampler { fallback, .. }) => Some(Self::Sampler {
+                value: value.clone(),
+                fallback: *fallback,
+            }),
+            (_, _) if self.is_same_type(other) => Some(self.clone()),
+            (Self::Float(v), Self::Int(_)) => Some(Self::Int(v.round() as i32)),
+            (Self::Float(v), Self::UInt(_)) => Some(Self::UInt(v.round().max(0.0) as u32)),
+            (Self::Int(v), Self::Float(_)) => Some(Self::Float(*v as f32)),
+            (Self::Int(v), Self::UInt(_)) => Some(Self::UInt((*v).max(0) as u32)),
+            (Self::UInt(v), Self::Float(_)) => Some(Self::Float(*v as f32)),
+            (Self::UInt(v), Self::Int(_)) => Some(Self::Int((*v).min(i32::MAX as u32) as i32)),
+            (Self::Color(v), Self::Vector3(_)) => Some(Self::Vector3(v.as_frgb())),
+            (Self::Color(v), Self::Vector4(_)) => Some(Self::Vector4(v.as_frgba())),
+            (Self::Vector3(v), Self::Color(_)) => Some(Self::Color(Color::from(*v))),
+            (Self::Vector4(v), Self::Color(_)) => Some(Self::Color(Color::from(*v))),
+            _ => None,
+        }
+    }
+
     define_as!(
         /// Tries to unwrap property value as float.
         as_float = Float -> f32
@@ -284,6 +366,85 @@ impl PropertyValue {
         as_matrix4_array = Matrix4Array -> [Matrix4<f32>]
     );
 
+    /// Returns `false` if the value contains at least one NaN or infinite number, `true` - otherwise.
+    /// Values without real numbers (integers, booleans, colors, samplers) are always finite.
+    pub fn is_finite(&self) -> bool {
+        fn all_finite<'a>(mut values: impl Iterator<Item = &'a f32>) -> bool {
+            values.all(|v| v.is_finite())
+        }
+
+        match self {
+            PropertyValue::Float(v) => v.is_finite(),
+            PropertyValue::FloatArray(v) => all_finite(v.iter()),
+            PropertyValue::Vector2(v) => all_finite(v.iter()),
+            PropertyValue::Vector2Array(v) => v.iter().all(|v| all_finite(v.iter())),
+            PropertyValue::Vector3(v) => all_finite(v.iter()),
+            PropertyValue::Vector3Array(v) => v.iter().all(|v| all_finite(v.iter())),
+            PropertyValue::Vector4(v) => all_finite(v.iter()),
+            PropertyValue::Vector4Array(v) => v.iter().all(|v| all_finite(v.iter())),
+            PropertyValue::Matrix2(v) => all_finite(v.iter()),
+            PropertyValue::Matrix2Array(v) => v.iter().all(|v| all_finite(v.iter())),
+            PropertyValue::Matrix3(v) => all_finite(v.iter()),
+            PropertyValue::Matrix3Array(v) => v.iter().all(|v| all_finite(v.iter())),
+            PropertyValue::Matrix4(v) => all_finite(v.iter()),
+            PropertyValue::Matrix4Array(v) => v.iter().all(|v| all_finite(v.iter())),
+            PropertyValue::Int(_)
+            | PropertyValue::IntArray(_)
+            | PropertyValue::UInt(_)
+            | PropertyValue::UIntArray(_)
+            | PropertyValue::Bool(_)
+            | PropertyValue::Color(_)
+            | PropertyValue::Sampler { .. } => true,
+        }
+    }
+
+    /// Compares the value with the other value, real numbers (including components of vectors and
+    /// matrices) are considered equal if they differ by no more than `epsilon`. Other values
+    /// (integers, booleans, colors, samplers) are compared exactly. Values of different types are
+    /// never equal.
+    pub fn approx_eq(&self, other: &Self, epsilon: f32) -> bool {
+        fn close(a: &[f32], b: &[f32], epsilon: f32) -> bool {
+            a.len() == b.len() && a.iter().zip(b).all(|(a, b)| (a - b).abs() <= epsilon)
+        }
+
+        fn close_arrays<T>(a: &[T], b: &[T], epsilon: f32, as_slice: fn(&T) -> &[f32]) -> bool {
+            a.len() == b.len()
+                && a.iter()
+                    .zip(b)
+                    .all(|(a, b)| close(as_slice(a), as_slice(b), epsilon))
+        }
+
+        match (self, other) {
+            (Self::Float(a), Self::Float(b)) => (a - b).abs() <= epsilon,
+            (Self::FloatArray(a), Self::FloatArray(b)) => close(a, b, epsilon),
+            (Self::Vector2(a), Self::Vector2(b)) => close(a.as_slice(), b.as_slice(), epsilon),
+            (Self::Vector2Array(a), Self::Vector2Array(b)) => {
+                close_arrays(a, b, epsilon, |v| v.as_slice())
+            }
+            (Self::Vector3(a), Self::Vector3(b)) => close(a.as_slice(), b.as_slice(), epsilon),
+            (Self::Vector3Array(a), Self::Vector3Array(b)) => {
+                close_arrays(a, b, epsilon, |v| v.as_slice())
+            }
+            (Self::Vector4(a), Self::Vector4(b)) => close(a.as_slice(), b.as_slice(), epsilon),
+            (Self::Vector4Array(a), Self::Vector4Array(b)) => {
+                close_arrays(a, b, epsilon, |v| v.as_slice())
+            }
+            (Self::Matrix2(a), Self::Matrix2(b)) => close(a.as_slice(), b.as_slice(), epsilon),
+            (Self::Matrix2Array(a), Self::Matrix2Array(b)) => {
+                close_arrays(a, b, epsilon, |v| v.as_slice())
+            }
+            (Self::Matrix3(a), Self::Matrix3(b)) => close(a.as_slice(), b.as_slice(), epsilon),
+            (Self::Matrix3Array(a), Self::Matrix3Array(b)) => {
+                close_arrays(a, b, epsilon, |v| v.as_slice())
+            }
+            (Self::Matrix4(a), Self::Matrix4(b)) => close(a.as_slice(), b.as_slice(), epsilon),
+            (Self::Matrix4Array(a), Self::Matrix4Array(b)) => {
+                close_arrays(a, b, epsilon, |v| v.as_slice())
+            }
+            _ => self == other,
+        }
+    }
+
     /// Tries to unwrap property value as texture.
     pub fn as_sampler(&self) -> Option<TextureResource> {
         if let PropertyValue::Sampler { value, .. } = self {
@@ -400,6 +561,92 @@ impl Default for PropertyValue {
 pub struct Material {
     shader: ShaderResource,
     properties: FxHashMap<ImmutableString, PropertyValue>,
+    render_queue: i32,
+    #[reflect(hidden)]
+    property_order: Vec<ImmutableString>,
+    #[reflect(hidden)]
+    is_modified: bool,
+    #[reflect(hidden)]
+    property_history: Option<PropertyHistory>,
+}
+
+/// Describes what happens with the properties of a material, when its shader is replaced with an
+/// other one. See [`Material::plan_shader_migration`] for more info. Every list is sorted by names
+/// of the properties.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct ShaderMigrationPlan {
+    /// Properties, that exist in both shaders and have the same type. Their values are kept as is.
+    pub kept: Vec<ImmutableString>,
+    /// Properties, that exist in both shaders, but have different types (or different sampler
+    /// fallbacks) that can be converted. Each property is paired with its converted value.
+    pub migrated: Vec<(ImmutableString, PropertyValue)>,
+    /// Properties, that do not exist in the new shader or cannot be converted to the new type.
+    /// Their values are lost.
+    pub dropped: Vec<ImmutableString>,
+    /// Properties of the new shader, that the material does not have (or have dropped). They will
+    /// have default values from the new shader.
+    pub added: Vec<ImmutableString>,
+}
+
+/// A bounded list of values that properties of a material have held, in chronological order. It is
+/// a diagnostic tool, that is mostly used by the editor to show how a property was tweaked. See
+/// [`Material::set_property_history_capacity`] for more info.
+#[derive(Debug, Clone, Default)]
+pub struct PropertyHistory {
+    capacity: usize,
+    entries: VecDeque<(ImmutableString, PropertyValue)>,
+}
+
+impl PropertyHistory {
+    /// Creates new history, that holds at most `capacity` entries (at least one).
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            capacity: capacity.max(1),
+            entries: Default::default(),
+        }
+    }
+
+    /// Returns maximum amount of entries in the history.
+    pub fn capacity(&self) -> usize {
+        self.capacity
+    }
+
+    /// Adds a new entry to the history, the oldest entry is removed if the history is full.
+    pub fn push(&mut self, name: ImmutableString, value: PropertyValue) {
+        while self.entries.len() >= self.capacity {
+            self.entries.pop_front();
+        }
+        self.entries.push_back((name, value));
+    }
+
+    /// Returns an iterator over all entries of the history, starting from the oldest one.
+    pub fn entries(&self) -> impl Iterator<Item = (&ImmutableString, &PropertyValue)> {
+        self.entries.iter().map(|(name, value)| (name, value))
+    }
+
+    /// Returns an iterator over the values of a property with the given name, starting from the
+    /// oldest one.
+    pub fn values_of<'a>(&'a self, name: &'a str) -> impl Iterator<Item = &'a PropertyValue> {
+        self.entries
+            .iter()
+            .filter(move |(entry_name, _)| entry_name.as_str() == name)
+            .map(|(_, value)| value)
+    }
+
+    /// Returns amount of entries in the history.
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    /// Returns `true` if the history is empty, `false` - otherwise.
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    /// Removes all entries from the history.
+    pub fn clear(&mut self) {
+        self.entries.clear();
+    }
 }
 
 impl Visit for Material {
@@ -418,6 +665,8 @@ impl Visit for Material {
         shader.visit("Shader", &mut region)?;
         self.shader = shader;
         self.properties.visit("Properties", &mut region)?;
+        let _ = self.render_queue.visit("RenderQueue", &mut region); // Backward compatibility.
+        let _ = self.property_order.visit("PropertyOrder", &mut region); // Backward compatibility.
 
         Ok(())
     }
@@ -452,6 +701,7 @@ impl ResourceData for Material {
         let mut visitor = Visitor::new();
         self.visit("Material", &mut visitor)?;
         visitor.save_binary(path)?;
+        self.is_modified = false;
         Ok(())
     }
 
@@ -679,6 +929,10 @@ impl Material {
         Self {
             shader,
             properties: property_values,
+            render_queue: 0,
+            property_order: Default::default(),
+            is_modified: false,
+            property_history: None,
         }
     }
 
@@ -695,13 +949,223 @@ impl Material {
         let mut material = Material {
             shader: Default::default(),
             properties: Default::default(),
+            render_queue: 0,
+            property_order: Default::default(),
+            is_modified: false,
+            property_history: None,
         };
-        let mut visitor = Visitor::load_from_memory(&content)?;
-        visitor.blackboard.register(Arc::new(resource_manager));
-        material.visit("Material", &mut visitor)?;
+        // The visitor is not `Send`, so it must not live across the await point below.
+        {
+            let mut visitor = Visitor::load_from_memory(&content)?;
+            visitor
+                .blackboard
+                .register(Arc::new(resource_manager.clone()));
+            material.visit("Material", &mut visitor)?;
+        }
+        // Default values are taken from the shader, so it must be loaded first. A shader that
+        // failed to load is reported elsewhere, the repair is skipped in this case.
+        if material.shader.clone().await.is_ok() {
+            material.repair_non_finite_properties(Some(&resource_manager));
+        }
         Ok(material)
     }
 
+    /// Replaces every property value, that contains NaN or infinite numbers (see
+    /// [`PropertyValue::is_finite`]), with the default value of the property from the shader. Such
+    /// values could appear in a material after a bad edit and they produce broken rendering. Every
+    /// replaced property is written to the log, a property is left as is (with an error message) if
+    /// the shader does not have a default value for it. Returns the names of the replaced
+    /// properties. This method is called automatically when a material is loaded from a file.
+    pub fn repair_non_finite_properties(
+        &mut self,
+        resource_manager: Option<&ResourceManager>,
+    ) -> Vec<ImmutableString> {
+        let mut repaired = Vec::new();
+        for (name, value) in self.properties.iter_mut() {
+            if value.is_finite() {
+                continue;
+            }
+
+            match PropertyValue::from_shader_property(&self.shader, name, resource_manager) {
+                Ok(default) => {
+                    Log::warn(format!(
+                        "Property {} of the material has non-finite value {:?}, it was replaced \
+                        with the default value {:?} from the shader.",
+                        name, value, default
+                    ));
+                    *value = default;
+                    repaired.push(name.clone());
+                }
+                Err(err) => Log::err(format!(
+                    "Property {} of the material has non-finite value {:?}, but it cannot be \
+                    repaired. Reason: {}",
+                    name, value, err
+                )),
+            }
+        }
+        repaired
+    }
+
+    /// Creates a deep copy of the material, where every external resource (the shader and the
+    /// textures) is re-requested from the given resource manager. It is useful to copy materials
+    /// between different resource manager contexts, for example between scenes of a tool. Embedded
+    /// textures are shared between the materials. A texture that does not exist is written to the
+    /// log and the default value of the property from the shader is used instead. The shader is
+    /// re-requested only if it exists, built-in shaders are shared as is.
+    pub async fn clone_with_resource_manager(
+        &self,
+        resource_manager: &ResourceManager,
+    ) -> Material {
+        let io = resource_manager.resource_io();
+        let mut material = self.clone();
+
+        if let Some(path) = material.shader.kind().into_path() {
+            if io.exists(&path).await {
+                material.shader = resource_manager.request::<Shader>(&path);
+            }
+        }
+
+        for (name, value) in material.properties.iter_mut() {
+            let path = match value {
+                PropertyValue::Sampler {
+                    value: Some(texture),
+                    ..
+                } => texture.kind().into_path(),
+                _ => None,
+            };
+            let Some(path) = path else {
+                continue;
+            };
+
+            if io.exists(&path).await {
+                if let PropertyValue::Sampler {
+                    value: Some(texture),
+                    ..
+                } = value
+                {
+                    *texture = resource_manager.request::<Texture>(&path);
+                }
+            } else {
+                Log::err(format!(
+                    "Texture {} of property {} of the material does not exist, the default value \
+                    is used instead.",
+                    path.display(),
+                    name
+                ));
+                match PropertyValue::from_shader_property(
+                    &material.shader,
+                    name,
+                    Some(resource_manager),
+                ) {
+                    Ok(default) => *value = default,
+                    Err(_) => {
+                        if let PropertyValue::Sampler { value, .. } = value {
+                            *value = None;
+                        }
+                    }
+                }
+            }
+        }
+
+        material
+    }
+
+    /// Creates a new material with the same shader, that has the values of the given properties
+    /// copied from this material. Every other property of the new material has the default value
+    /// from the shader (see [`Self::from_shader`] for the meaning of the resource manager). Names
+    /// of properties, that this material does not have, are ignored.
+    pub fn extract_properties(
+        &self,
+        names: &[ImmutableString],
+        resource_manager: Option<ResourceManager>,
+    ) -> Material {
+        let mut material = Material::from_shader(self.shader.clone(), resource_manager);
+        for name in names {
+            if let Some(value) = self.properties.get(name) {
+                material.properties.insert(name.clone(), value.clone());
+            }
+        }
+        material
+    }
+
+    /// Calculates how the properties of the material would be kept, migrated (converted to a new
+    /// type, see [`PropertyValue::converted_to_type_of`]) or dropped, if the shader of the material
+    /// is replaced with the given one. The material is not modified, so the plan could be used for
+    /// a preview before the actual change. The plan could be applied using
+    /// [`Self::with_shader_migration`]. Returns [`None`] if the shader is not loaded.
+    pub fn plan_shader_migration(&self, shader: &ShaderResource) -> Option<ShaderMigrationPlan> {
+        let mut state = shader.state();
+        let shader = state.data()?;
+
+        let mut plan = ShaderMigrationPlan::default();
+        for property_definition in shader.definition.properties.iter() {
+            let name = ImmutableString::new(&property_definition.name);
+            let Some(value) = self.properties.get(&name) else {
+                plan.added.push(name);
+                continue;
+            };
+
+            let default = PropertyValue::from_property_kind(&property_definition.kind, None);
+            match value.converted_to_type_of(&default) {
+                Some(converted) if converted == *value => plan.kept.push(name),
+                Some(converted) => plan.migrated.push((name, converted)),
+                None => {
+                    plan.dropped.push(name.clone());
+                    plan.added.push(name);
+                }
+            }
+        }
+
+        for name in self.properties.keys() {
+            if !shader
+                .definition
+                .properties
+                .iter()
+                .any(|property| property.name == name.as_str())
+            {
+                plan.dropped.push(name.clone());
+            }
+        }
+
+        plan.kept.sort_by(|a, b| a.as_str().cmp(b.as_str()));
+        plan.migrated.sort_by(|a, b| a.0.as_str().cmp(b.0.as_str()));
+        plan.dropped.sort_by(|a, b| a.as_str().cmp(b.as_str()));
+        plan.added.sort_by(|a, b| a.as_str().cmp(b.as_str()));
+
+        Some(plan)
+    }
+
+    /// Creates a new material with the given shader, that has the values of the properties of this
+    /// material according to the given migration plan (see [`Self::plan_shader_migration`]). Every
+    /// other property has the default value from the shader (see [`Self::from_shader`] for the
+    /// meaning of the resource manager). The render queue of this material is preserved, as well as
+    /// the display order of the properties, that exist in the new material (see
+    /// [`Self::set_property_order`]).
+    pub fn with_shader_migration(
+        &self,
+        shader: ShaderResource,
+        plan: &ShaderMigrationPlan,
+        resource_manager: Option<ResourceManager>,
+    ) -> Material {
+        let mut material = Material::from_shader(shader, resource_manager);
+        for name in plan.kept.iter() {
+            if let Some(value) = self.properties.get(name) {
+                material.properties.insert(name.clone(), value.clone());
+            }
+        }
+        for (name, value) in plan.migrated.iter() {
+            material.properties.insert(name.clone(), value.clone());
+        }
+        material.render_queue = self.render_queue;
+        material.property_order = self
+            .property_order
+            .iter()
+            .filter(|name| material.properties.contains_key(*name))
+            .cloned()
+            .collect();
+        material
+    }
+
     /// Searches for a property with given name.
     ///
     /// # Complexity
@@ -910,6 +1374,154 @@ impl Material {
         &self.shader
     }
 
+    /// Returns every property of the shader of the material, in the order of their definition in
+    /// the shader, as a tuple of its name, its current value and its default value (from the shader
+    /// definition). If a property is defined in the shader, but it is not set in the material, the
+    /// default value is returned as the current value. Properties of the material, that are not
+    /// defined in the shader, are not included. Returns an empty vector if the shader is not loaded.
+    /// The resource manager is used to load default textures of samplers.
+    pub fn properties_with_defaults(
+        &self,
+        resource_manager: Option<&ResourceManager>,
+    ) -> Vec<(ImmutableString, PropertyValue, PropertyValue)> {
+        let mut state = self.shader.state();
+        let Some(shader) = state.data() else {
+            return Vec::new();
+        };
+        shader
+            .definition
+            .properties
+            .iter()
+            .map(|definition| {
+                let name = ImmutableString::new(&definition.name);
+                let default = PropertyValue::from_property_kind(&definition.kind, resource_manager);
+                let current = self
+                    .properties
+                    .get(&name)
+                    .cloned()
+                    .unwrap_or_else(|| default.clone());
+                (name, current, default)
+            })
+            .collect()
+    }
+
+    /// Checks whether the material is effectively identical to the other material: both materials
+    /// use the same shader resource, have the same render queue and their properties are equal
+    /// (see [`PropertyValue::approx_eq`]). A property, that is set in one material, but missing in
+    /// the other, is compared with the default value from the shader definition (samplers are
+    /// compared with their default fallback without a texture). The display order of properties
+    /// (see [`Self::set_property_order`]) is not compared, because it does not affect rendering.
+    pub fn approx_eq(&self, other: &Self, epsilon: f32) -> bool {
+        if self.shader != other.shader || self.render_queue != other.render_queue {
+            return false;
+        }
+
+        let value_of =
+            |material: &Material, name: &ImmutableString| {
+                material.properties.get(name).cloned().or_else(|| {
+                    PropertyValue::from_shader_property(&material.shader, name, None).ok()
+                })
+            };
+
+        self.properties
+            .keys()
+            .chain(other.properties.keys())
+            .all(|name| match (value_of(self, name), value_of(other, name)) {
+                (Some(a), Some(b)) => a.approx_eq(&b, epsilon),
+                (a, b) => a.is_none() && b.is_none(),
+            })
+    }
+
+    /// Sets new render queue of the material and returns the previous one. Draw calls are sorted by
+    /// the render queue of their materials first (lower values are rendered first) and then by
+    /// the automatic ordering of the renderer (for example, by distance to the camera for
+    /// transparent objects). It allows you to render some transparent materials strictly before
+    /// or after the others. All materials have `0` render queue by default, which keeps the
+    /// automatic ordering.
+    pub fn set_render_queue(&mut self, render_queue: i32) -> i32 {
+        std::mem::replace(&mut self.render_queue, render_queue)
+    }
+
+    /// Returns current render queue of the material. See [`Self::set_render_queue`] for more info.
+    pub fn render_queue(&self) -> i32 {
+        self.render_queue
+    }
+
+    /// Sets new display order of the properties of the material and returns the previous one. It is
+    /// pure metadata for editors, that allows to keep the most important properties at the top of
+    /// the property list. It does not affect rendering. The order may contain names of properties,
+    /// that do not exist in the material, they are ignored. See [`Self::ordered_property_names`]
+    /// for more info.
+    pub fn set_property_order(&mut self, order: Vec<ImmutableString>) -> Vec<ImmutableString> {
+        std::mem::replace(&mut self.property_order, order)
+    }
+
+    /// Returns current display order of the properties of the material. See
+    /// [`Self::set_property_order`] for more info.
+    pub fn property_order(&self) -> &[ImmutableString] {
+        &self.property_order
+    }
+
+    /// Returns the names of every property of the material in display order: the properties from
+    /// the display order of the material (see [`Self::set_property_order`]) go first, the rest of
+    /// the properties follow them sorted by their names.
+    pub fn ordered_property_names(&self) -> Vec<ImmutableString> {
+        let mut names = Vec::with_capacity(self.properties.len());
+        for name in self.property_order.iter() {
+            if self.properties.contains_key(name) && !names.contains(name) {
+                names.push(name.clone());
+            }
+        }
+        let mut rest = self
+            .properties
+            .keys()
+            .filter(|name| !names.contains(name))
+            .cloned()
+            .collect::<Vec<_>>();
+        rest.sort_by(|a, b| a.as_str().cmp(b.as_str()));
+        names.extend(rest);
+        names
+    }
+
+    /// Marks the material as modified, which means that its in-memory state differs from the one
+    /// that was saved last time. The flag is reset on successful save of the material.
+    pub fn mark_modified(&mut self) {
+        self.is_modified = true;
+    }
+
+    /// Returns `true` if the material was modified since the last save (see [`Self::mark_modified`]).
+    pub fn is_modified(&self) -> bool {
+        self.is_modified
+    }
+
+    /// Enables (with the given capacity) or disables (with [`None`]) recording of property values
+    /// history. Changing the capacity clears the history. The history is not serialized and it is
+    /// disabled by default. Values are recorded with [`Self::record_property_value`], the editor
+    /// does this for every property change made by its commands.
+    pub fn set_property_history_capacity(&mut self, capacity: Option<usize>) {
+        self.property_history = capacity.map(PropertyHistory::new);
+    }
+
+    /// Returns a reference to the history of property values, if it is enabled.
+    pub fn property_history(&self) -> Option<&PropertyHistory> {
+        self.property_history.as_ref()
+    }
+
+    /// Returns a reference to the history of property values, if it is enabled.
+    pub fn property_history_mut(&mut self) -> Option<&mut PropertyHistory> {
+        self.property_history.as_mut()
+    }
+
+    /// Adds the current value of a property with the given name to the history of property values.
+    /// Does nothing if the history is disabled or there's no such property.
+    pub fn record_property_value(&mut self, name: &ImmutableString) {
+        if let Some(history) = self.property_history.as_mut() {
+            if let Some(value) = self.properties.get(name) {
+                history.push(name.clone(), value.clone());
+            }
+        }
+    }
+
     /// Returns immutable reference to internal property storage.
     pub fn properties(&self) -> &FxHashMap<ImmutableString, PropertyValue> {
         &self.properties
@@ -1009,3 +1621,398 @@ where
     }
     None
 }
+
+#[cfg(test)]
+mod test {
+    use crate::{
+        asset::{manager::ResourceManager, untyped::ResourceKind, ResourceData},
+        core::{
+            algebra::Vector3,
+            color::Color,
+            futures::executor::block_on,
+            sstorage::ImmutableString,
+            visitor::{Visit, Visitor},
+        },
+        material::{
+            shader::{ShaderResource, ShaderResourceExtension},
+            Material, MaterialError, PropertyValue,
+        },
+        resource::texture::TextureResource,
+    };
+    use std::sync::Arc;
+
+    #[test]
+    fn test_property_value_from_shader_property() {
+        let shader = ShaderResource::standard();
+
+        assert!(matches!(
+            PropertyValue::from_shader_property(&shader, "diffuseTexture", None),
+            Ok(PropertyValue::Sampler { .. })
+        ));
+        assert!(matches!(
+            PropertyValue::from_shader_property(&shader, "doesNotExist", None),
+            Err(MaterialError::NoSuchProperty { .. })
+        ));
+    }
+
+    #[test]
+    fn test_property_value_checked_for_shader() {
+        let shader = ShaderResource::standard();
+
+        assert!(PropertyValue::Sampler {
+            value: None,
+            fallback: Default::default(),
+        }
+        .checked_for_shader(&shader, "diffuseTexture")
+        .is_ok());
+        assert!(matches!(
+            PropertyValue::Float(1.0).checked_for_shader(&shader, "diffuseTexture"),
+            Err(MaterialError::TypeMismatch { .. })
+        ));
+    }
+
+    #[test]
+    fn test_material_modified_flag() {
+        let mut material = Material::standard();
+        assert!(!material.is_modified());
+        material.mark_modified();
+        assert!(material.is_modified());
+
+        let path = std::env::temp_dir().join("fyrox_test_material_modified_flag.material");
+        material.save(&path).unwrap();
+        assert!(!material.is_modified());
+        let _ = std::fs::remove_file(path);
+    }
+
+    #[test]
+    fn test_render_queue() {
+        let mut material = Material::standard();
+        assert_eq!(material.render_queue(), 0);
+        assert_eq!(material.set_render_queue(5), 0);
+        assert_eq!(material.render_queue(), 5);
+
+        let mut visitor = Visitor::new();
+        material.visit("Material", &mut visitor).unwrap();
+        let data = visitor.save_binary_to_vec().unwrap();
+
+        let mut loaded = Material::standard();
+        let mut visitor = Visitor::load_from_memory(&data).unwrap();
+        visitor
+            .blackboard
+            .register(Arc::new(ResourceManager::new(Arc::new(Default::default()))));
+        loaded.visit("Material", &mut visitor).unwrap();
+        assert_eq!(loaded.render_queue(), 5);
+    }
+
+    #[test]
+    fn test_material_approx_eq() {
+        let name = ImmutableString::new("diffuseColor");
+        let a = Material::standard();
+        let mut b = Material::standard();
+        assert!(a.approx_eq(&b, 1.0e-5));
+
+        // A property, that is set to its default value, is equal to the missing property.
+        b.properties.remove(&name);
+        assert!(a.approx_eq(&b, 1.0e-5));
+        assert!(b.approx_eq(&a, 1.0e-5));
+
+        b.properties
+            .insert(name.clone(), PropertyValue::Color(Color::RED));
+        assert!(!a.approx_eq(&b, 1.0e-5));
+
+        let mut a = Material::standard();
+        let mut b = Material::standard();
+        let name = ImmutableString::new("parallaxScale");
+        a.set_property(&name, PropertyValue::Float(0.5)).unwrap();
+        b.set_property(&name, PropertyValue::Float(0.5001)).unwrap();
+        assert!(a.approx_eq(&b, 1.0e-3));
+        assert!(!a.approx_eq(&b, 1.0e-5));
+
+        b.set_render_queue(1);
+        assert!(!a.approx_eq(&b, 1.0e-3));
+
+        assert!(!Material::standard().approx_eq(&Material::standard_2d(), 1.0e-3));
+
+        assert!(
+            PropertyValue::Vector3Array(vec![Vector3::new(1.0, 2.0, 3.0)]).approx_eq(
+                &PropertyValue::Vector3Array(vec![Vector3::new(1.0, 2.0, 3.0001)]),
+                1.0e-3
+            )
+        );
+        assert!(!PropertyValue::FloatArray(vec![1.0])
+            .approx_eq(&PropertyValue::FloatArray(vec![1.0, 2.0]), 1.0e-3));
+        assert!(!PropertyValue::Float(1.0).approx_eq(&PropertyValue::Int(1), 1.0e-3));
+    }
+
+    #[test]
+    fn test_property_order() {
+        let mut material = Material::standard();
+        let mut names = material.ordered_property_names();
+        assert!(names
+            .windows(2)
+            .all(|pair| pair[0].as_str() < pair[1].as_str()));
+
+        let last = names.pop().unwrap();
+        let unknown = ImmutableString::new("doesNotExist");
+        material.set_property_order(vec![last.clone(), unknown.clone()]);
+        assert_eq!(material.property_order(), &[last.clone(), unknown]);
+
+        let ordered = material.ordered_property_names();
+        assert_eq!(ordered[0], last);
+        assert_eq!(&ordered[1..], names.as_slice());
+    }
+
+    #[test]
+    fn test_property_history() {
+        let name = ImmutableString::new("foo");
+
+        let mut material = Material::standard();
+        material.set_property_history_capacity(Some(2));
+        for i in 0..3 {
+            material
+                .properties
+                .insert(name.clone(), PropertyValue::Float(i as f32));
+            material.record_property_value(&name);
+        }
+        material.record_property_value(&ImmutableString::new("doesNotExist"));
+
+        let history = material.property_history().unwrap();
+        assert_eq!(history.len(), 2);
+        assert_eq!(
+            history.values_of("foo").collect::<Vec<_>>(),
+            vec![&PropertyValue::Float(1.0), &PropertyValue::Float(2.0)]
+        );
+
+        material.property_history_mut().unwrap().clear();
+        assert!(material.property_history().unwrap().is_empty());
+
+        material.set_property_history_capacity(None);
+        material.record_property_value(&name);
+        assert!(material.property_history().is_none());
+    }
+
+    #[test]
+    fn test_repair_non_finite_properties() {
+        let mut material = Material::standard();
+        let emission_strength = ImmutableString::new("emissionStrength");
+        let default = material.property_ref(&emission_strength).cloned().unwrap();
+        material.properties.insert(
+            emission_strength.clone(),
+            PropertyValue::Vector3(Vector3::new(1.0, f32::NAN, 0.0)),
+        );
+        let does_not_exist = ImmutableString::new("doesNotExist");
+        material
+            .properties
+            .insert(does_not_exist.clone(), PropertyValue::Float(f32::INFINITY));
+
+        assert!(!material
+            .property_ref(&emission_strength)
+            .unwrap()
+            .is_finite());
+        assert_eq!(
+            material.repair_non_finite_properties(None),
+            vec![emission_strength.clone()]
+        );
+        assert_eq!(material.property_ref(&emission_strength), Some(&default));
+        assert!(!material.property_ref(&does_not_exist).unwrap().is_finite());
+    }
+
+    #[test]
+    fn test_extract_properties() {
+        let mut material = Material::standard();
+        let diffuse_color = ImmutableString::new("diffuseColor");
+        material
+            .set_property(&diffuse_color, PropertyValue::Color(Color::RED))
+            .unwrap();
+        let emission_strength = ImmutableString::new("emissionStrength");
+        material
+            .set_property(
+                &emission_strength,
+                PropertyValue::Vector3(Vector3::new(1.0, 2.0, 3.0)),
+            )
+            .unwrap();
+
+        let extracted = material.extract_properties(
+            &[diffuse_color.clone(), ImmutableString::new("doesNotExist")],
+            None,
+        );
+        assert_eq!(extracted.shader(), material.shader());
+        assert_eq!(
+            extracted.property_ref(&diffuse_color),
+            Some(&PropertyValue::Color(Color::RED))
+        );
+        assert_eq!(
+            extracted.property_ref(&emission_strength),
+            Material::standard().property_ref(&emission_strength)
+        );
+        assert!(extracted
+            .property_ref(&ImmutableString::new("doesNotExist"))
+            .is_none());
+    }
+
+    #[test]
+    fn test_shader_migration() {
+        let shader = ShaderResource::from_str(
+            r#"
+            (
+                name: "TestShader",
+                properties: [
+                    (
+                        name: "diffuseColor",
+                        kind: Color(r: 255, g: 255, b: 255, a: 255),
+                    ),
+                    (
+                        name: "diffuseTexture",
+                        kind: Sampler(default: None, fallback: Black),
+                    ),
+                    (
+                        name: "parallaxCenter",
+                        kind: Int(0),
+                    ),
+                    (
+                        name: "emissionStrength",
+                        kind: Matrix2Array([]),
+                    ),
+                    (
+                        name: "newProperty",
+                        kind: Bool(true),
+                    ),
+                ],
+                passes: [],
+            )
+            "#,
+            ResourceKind::Embedded,
+        )
+        .unwrap();
+
+        let mut material = Material::standard();
+        let parallax_center = ImmutableString::new("parallaxCenter");
+        material
+            .set_property(&parallax_center, PropertyValue::Float(2.6))
+            .unwrap();
+        material.set_render_queue(3);
+        material.set_property_order(vec![
+            ImmutableString::new("normalTexture"),
+            parallax_center.clone(),
+            ImmutableString::new("diffuseColor"),
+        ]);
+
+        let plan = material.plan_shader_migration(&shader).unwrap();
+        assert_eq!(plan.kept, vec![ImmutableString::new("diffuseColor")]);
+        assert_eq!(plan.migrated.len(), 2);
+        assert_eq!(plan.migrated[0].0, ImmutableString::new("diffuseTexture"));
+        assert_eq!(
+            plan.migrated[1],
+            (parallax_center.clone(), PropertyValue::Int(3))
+        );
+        assert!(plan
+            .dropped
+            .contains(&ImmutableString::new("emissionStrength")));
+        assert!(plan
+            .dropped
+            .contains(&ImmutableString::new("normalTexture")));
+        assert_eq!(
+            plan.added,
+            vec![
+                ImmutableString::new("emissionStrength"),
+                ImmutableString::new("newProperty")
+            ]
+        );
+
+        // The dry run does not modify the material.
+        assert_eq!(material.shader(), &ShaderResource::standard());
+
+        let migrated = material.with_shader_migration(shader.clone(), &plan, None);
+        assert_eq!(migrated.shader(), &shader);
+        assert_eq!(
+            migrated.property_ref(&parallax_center),
+            Some(&PropertyValue::Int(3))
+        );
+        assert_eq!(
+            migrated.property_ref(&ImmutableString::new("newProperty")),
+            Some(&PropertyValue::Bool(true))
+        );
+        assert_eq!(migrated.properties().len(), 5);
+        assert_eq!(migrated.render_queue(), 3);
+        // The order of the dropped properties is discarded.
+        assert_eq!(
+            migrated.property_order(),
+            &[parallax_center, ImmutableString::new("diffuseColor")]
+        );
+    }
+
+    #[test]
+    fn test_clone_with_resource_manager() {
+        let resource_manager = ResourceManager::new(Arc::new(Default::default()));
+
+        let mut material = Material::standard();
+        let diffuse_texture = ImmutableString::new("diffuseTexture");
+        material
+            .set_property(
+                &diffuse_texture,
+                PropertyValue::Sampler {
+                    value: Some(TextureResource::new_pending(ResourceKind::External(
+                        "does_not_exist.png".into(),
+                    ))),
+                    fallback: Default::default(),
+                },
+            )
+            .unwrap();
+        let normal_texture = ImmutableString::new("normalTexture");
+        let embedded = TextureResource::new_pending(ResourceKind::Embedded);
+        material
+            .set_property(
+                &normal_texture,
+                PropertyValue::Sampler {
+                    value: Some(embedded.clone()),
+                    fallback: Default::default(),
+                },
+            )
+            .unwrap();
+
+        let clone = block_on(material.clone_with_resource_manager(&resource_manager));
+        assert_eq!(clone.shader(), material.shader());
+        assert_eq!(
+            clone.property_ref(&diffuse_texture),
+            Material::standard().property_ref(&diffuse_texture)
+        );
+        assert_eq!(
+            clone.property_ref(&normal_texture).unwrap().as_sampler(),
+            Some(embedded)
+        );
+    }
+
+    #[test]
+    fn test_properties_with_defaults() {
+        let mut material = Material::standard();
+        let diffuse_color = ImmutableString::new("diffuseColor");
+        material
+            .set_property(&diffuse_color, PropertyValue::Color(Color::RED))
+            .unwrap();
+        let emission_strength = ImmutableString::new("emissionStrength");
+        material.properties.remove(&emission_strength);
+
+        let properties = material.properties_with_defaults(None);
+        assert_eq!(
+            properties.len(),
+            ShaderResource::standard()
+                .data_ref()
+                .definition
+                .properties
+                .len()
+        );
+
+        let (_, current, default) = properties
+            .iter()
+            .find(|(name, _, _)| *name == diffuse_color)
+            .unwrap();
+        assert_eq!(current, &PropertyValue::Color(Color::RED));
+        assert_ne!(current, default);
+
+        let (_, current, default) = properties
+            .iter()
+            .find(|(name, _, _)| *name == emission_strength)
+            .unwrap();
+        assert_eq!(current, default);
+    }
+}
@@ -52,14 +52,21 @@
                layout(location = 2) in float particleSize;
                layout(location = 3) in float particleRotation;
                layout(location = 4) in vec4 vertexColor;
+               layout(location = 5) in vec3 particleAxis;
+               layout(location = 6) in float particleStretch;
+               layout(location = 7) in float particleCustom;
+               layout(location = 8) in vec4 particleFrame;
+               layout(location = 9) in float particleMinScreenSize;
 
                uniform mat4 fyrox_viewProjectionMatrix;
                uniform mat4 fyrox_worldMatrix;
                uniform vec3 fyrox_cameraUpVector;
                uniform vec3 fyrox_cameraSideVector;
+               uniform sampler2D fyrox_sceneDepth;
 
                out vec2 texCoord;
                out vec4 color;
+               out float customData;
 
                vec2 rotateVec2(vec2 v, float angle)
                {
@@ -72,10 +79,39 @@
                void main()
                {
                    color = S_SRGBToLinear(vertexColor);
-                   texCoord = vertexTexCoord;
+                   texCoord = particleFrame.xy + vertexTexCoord * particleFrame.zw;
+                   customData = particleCustom;
                    vec2 vertexOffset = rotateVec2(vertexTexCoord * 2.0 - 1.0, particleRotation);
                    vec4 worldPosition = fyrox_worldMatrix * vec4(vertexPosition, 1.0);
-                   vec3 offset = (vertexOffset.x * fyrox_cameraSideVector + vertexOffset.y * fyrox_cameraUpVector) * particleSize;
+                   vec3 sideVector = fyrox_cameraSideVector;
+                   vec3 upVector = fyrox_cameraUpVector;
+                   // Aligned particles are rotated around their axis to face the camera as much as possible.
+                   float axisLength = length(particleAxis);
+                   if (axisLength > 0.0) {
+                       vec3 axis = particleAxis / axisLength;
+                       vec3 alignedSide = cross(axis, cross(fyrox_cameraUpVector, fyrox_cameraSideVector));
+                       float alignedSideLength = length(alignedSide);
+                       if (alignedSideLength > 0.0001) {
+                           sideVector = alignedSide / alignedSideLength;
+                           upVector = axis;
+                       }
+                   }
+                   // Tiny particles are enlarged to cover at least the given amount of pixels on screen.
+                   float size = particleSize;
+                   if (particleMinScreenSize > 0.0) {
+                       vec2 viewportSize = vec2(textureSize(fyrox_sceneDepth, 0));
+                       vec4 clipCenter = fyrox_viewProjectionMatrix * worldPosition;
+                       vec4 clipEdge = fyrox_viewProjectionMatrix * (worldPosition + vec4(sideVector, 0.0));
+                       if (clipCenter.w > 0.0 && clipEdge.w > 0.0) {
+                           // Size (in pixels) of a quad of a particle with unit size.
+                           float pixelsPerUnit = length((clipEdge.xy / clipEdge.w - clipCenter.xy / clipCenter.w) * viewportSize);
+                           if (pixelsPerUnit > 0.0) {
+                               size = max(size, particleMinScreenSize / pixelsPerUnit);
+                           }
+                       }
+                   }
+                   // Stretched particles are elongated along their axis (the up vector of the quad).
+                   vec3 offset = vertexOffset.x * sideVector * size + vertexOffset.y * upVector * (size + 0.5 * particleStretch);
                    gl_Position = fyrox_viewProjectionMatrix * (worldPosition + vec4(offset.x, offset.y, offset.z, 0.0));
                }
                "#,
@@ -92,6 +128,7 @@
                out vec4 FragColor;
                in vec2 texCoord;
                in vec4 color;
+               in float customData;
 
                float toProjSpace(float z)
                {
@@ -145,6 +145,7 @@ pub struct RenderDataBundle {
     /// A decal layer index of the bundle.
     pub decal_layer_index: u8,
     sort_index: u64,
+    render_queue: i32,
 }
 
 impl Debug for RenderDataBundle {
@@ -287,12 +288,20 @@ impl RenderDataBundleStorage {
         storage
     }
 
-    /// Sorts the bundles by their respective sort index.
+    /// Sorts the bundles by the render queue of their materials (see [`crate::material::Material::set_render_queue`])
+    /// and then by their respective sort index.
     pub fn sort(&mut self) {
-        self.bundles.sort_unstable_by_key(|b| b.sort_index);
+        self.bundles
+            .sort_unstable_by_key(|b| (b.render_queue, b.sort_index));
     }
 }
 
+/// Returns the render queue of the material, or the default one if the material is not loaded.
+fn material_render_queue(material: &MaterialResource) -> i32 {
+    let mut state = material.state();
+    state.data().map_or(0, |material| material.render_queue())
+}
+
 impl RenderDataBundleStorageTrait for RenderDataBundleStorage {
     /// Adds a new mesh to the bundle storage using the given set of vertices and triangles. This
     /// method automatically creates a render bundle according to a hash of the following parameters:
@@ -360,6 +369,7 @@ impl RenderDataBundleStorageTrait for RenderDataBundleStorage {
             self.bundles.push(RenderDataBundle {
                 data,
                 sort_index,
+                render_queue: material_render_queue(material),
                 instances: vec![
                     // Each bundle must have at least one instance to be rendered.
                     SurfaceInstanceData {
@@ -420,6 +430,7 @@ impl RenderDataBundleStorageTrait for RenderDataBundleStorage {
             self.bundles.push(RenderDataBundle {
                 data: data.clone(),
                 sort_index,
+                render_queue: material_render_queue(material),
                 instances: Default::default(),
                 material: material.clone(),
                 is_skinned,
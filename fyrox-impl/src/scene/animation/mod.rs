@@ -12,14 +12,16 @@ use crate::{
         visitor::prelude::*,
         TypeUuidProvider,
     },
-    generic_animation::value::{BoundValueCollection, TrackValue, ValueBinding},
+    generic_animation::value::{
+        BindingError, BoundValueCollection, TrackValue, ValueBinding, ValueCoercion,
+    },
     scene::{
         base::{Base, BaseBuilder},
         graph::{Graph, NodePool},
         node::{Node, NodeTrait, UpdateContext},
     },
 };
-use fyrox_graph::BaseSceneGraph;
+use fyrox_graph::{BaseSceneGraph, NodeHandleMap};
 use std::ops::{Deref, DerefMut};
 
 pub mod absm;
@@ -46,7 +48,10 @@ pub mod prelude {
     pub use crate::generic_animation::{
         container::{TrackDataContainer, TrackValueKind},
         signal::AnimationSignal,
-        value::{BoundValueCollection, TrackValue, ValueBinding, ValueType},
+        value::{
+            BindingError, BoundValueCollection, BoundValueSmoother, BoundValueSpring,
+            RotationBlendPath, TrackValue, ValueBinding, ValueCoercion, ValueType,
+        },
         AnimationEvent,
     };
 }
@@ -82,6 +87,14 @@ pub trait AnimationPoseExt {
     fn apply_with<C>(&self, graph: &mut Graph, callback: C)
     where
         C: FnMut(&mut Node, Handle<Node>, &NodePose);
+
+    /// Same as [`Self::apply`], but the pose of each animated node is applied to the node that the
+    /// given map maps it to. It allows you to play one animation (for example, a skeletal animation
+    /// of a character) on many copies of the animated hierarchy: instantiate the hierarchy, take
+    /// its `original -> copy` handle map (see [`crate::scene::graph::Graph::copy_node`] for example)
+    /// and apply the same pose to every copy in one call. Poses of the nodes, that have no mapping,
+    /// are ignored.
+    fn apply_mapped(&self, graph: &mut Graph, map: &NodeHandleMap<Node>);
 }
 
 impl AnimationPoseExt for AnimationPose {
@@ -117,16 +130,41 @@ impl AnimationPoseExt for AnimationPose {
             }
         }
     }
+
+    fn apply_mapped(&self, graph: &mut Graph, map: &NodeHandleMap<Node>) {
+        for (node, local_pose) in self.poses() {
+            if let Some(node_ref) = map
+                .inner()
+                .get(node)
+                .and_then(|mapped| graph.try_get_mut(*mapped))
+            {
+                local_pose.values.apply(node_ref);
+            }
+        }
+    }
 }
 
 /// Extension trait for [`BoundValueCollection`].
 pub trait BoundValueCollectionExt {
     /// Tries to set each value from the collection to the respective property (by binding) of the given scene node.
     fn apply(&self, node_ref: &mut Node);
+
+    /// Same as [`Self::apply`], but allows you to specify what happens if a value of a property binding cannot be
+    /// applied as is. See
+    /// [`crate::generic_animation::value::BoundValue::apply_to_object_with_coercion`] for more info.
+    fn apply_with_coercion(&self, node_ref: &mut Node, coercion: ValueCoercion);
+
+    /// Checks whether every value from the collection can be applied to the given scene node, without applying
+    /// anything. Returns a list of problems, that would otherwise be logged by [`Self::apply`].
+    fn validate(&self, node_ref: &Node) -> Vec<BindingError>;
 }
 
 impl BoundValueCollectionExt for BoundValueCollection {
     fn apply(&self, node_ref: &mut Node) {
+        self.apply_with_coercion(node_ref, ValueCoercion::Strict)
+    }
+
+    fn apply_with_coercion(&self, node_ref: &mut Node, coercion: ValueCoercion) {
         for bound_value in self.values.iter() {
             match bound_value.binding {
                 ValueBinding::Position => {
@@ -152,12 +190,48 @@ impl BoundValueCollectionExt for BoundValueCollection {
                         Log::err("Unable to apply rotation, because underlying type is not UnitQuaternion!")
                     }
                 }
+                // Properties are applied all at once below.
+                ValueBinding::Property { .. } => (),
+            }
+        }
+
+        self.apply_properties_to_object(node_ref, coercion);
+    }
+
+    fn validate(&self, node_ref: &Node) -> Vec<BindingError> {
+        let mut errors = Vec::new();
+        for bound_value in self.values.iter() {
+            let result = match bound_value.binding {
+                ValueBinding::Position | ValueBinding::Scale => {
+                    if let TrackValue::Vector3(_) = bound_value.value {
+                        Ok(())
+                    } else {
+                        Err(BindingError::ValueMismatch {
+                            binding: bound_value.binding.clone(),
+                            expected: "Vector3",
+                        })
+                    }
+                }
+                ValueBinding::Rotation => {
+                    if let TrackValue::UnitQuaternion(_) = bound_value.value {
+                        Ok(())
+                    } else {
+                        Err(BindingError::ValueMismatch {
+                            binding: bound_value.binding.clone(),
+                            expected: "UnitQuaternion",
+                        })
+                    }
+                }
                 ValueBinding::Property {
                     name: ref property_name,
                     value_type,
-                } => bound_value.apply_to_object(node_ref, property_name, value_type),
+                } => bound_value.validate_for_object(node_ref, property_name, value_type),
+            };
+            if let Err(error) = result {
+                errors.push(error);
             }
         }
+        errors
     }
 }
 
@@ -363,3 +437,43 @@ impl AnimationPlayerBuilder {
         graph.add_node(self.build_node())
     }
 }
+
+#[cfg(test)]
+mod test {
+    use crate::{
+        core::{algebra::Vector3, math::curve::CurveKeyKind, pool::Handle},
+        scene::{
+            animation::prelude::*, base::BaseBuilder, graph::Graph, node::Node, pivot::PivotBuilder,
+        },
+    };
+    use fyrox_graph::NodeHandleMap;
+
+    #[test]
+    fn test_apply_mapped() {
+        let mut graph = Graph::new();
+        let original = PivotBuilder::new(BaseBuilder::new()).build(&mut graph);
+        let copy = PivotBuilder::new(BaseBuilder::new()).build(&mut graph);
+        let unmapped = PivotBuilder::new(BaseBuilder::new()).build(&mut graph);
+
+        let mut animation = Animation::default();
+        for (target, position) in [
+            (original, Vector3::new(1.0, 2.0, 3.0)),
+            (unmapped, Vector3::new(4.0, 5.0, 6.0)),
+        ] {
+            let mut frames = TrackDataContainer::new(TrackValueKind::Vector3);
+            frames.insert_keyframe(0.0, &TrackValue::Vector3(position), CurveKeyKind::Linear);
+            animation.add_track(Track::new(frames, ValueBinding::Position).with_target(target));
+        }
+
+        let mut map = NodeHandleMap::default();
+        map.insert(original, copy);
+        animation.sample(0.0).apply_mapped(&mut graph, &map);
+
+        let position = |node: Handle<Node>| **graph[node].local_transform().position();
+        assert_eq!(position(copy), Vector3::new(1.0, 2.0, 3.0));
+        // The pose of the original node is redirected to the copy.
+        assert_eq!(position(original), Vector3::default());
+        // Poses of the nodes without mapping are ignored.
+        assert_eq!(position(unmapped), Vector3::default());
+    }
+}
@@ -26,7 +26,7 @@ use crate::{
     },
 };
 use fyrox_core::uuid_provider;
-use fyrox_graph::BaseSceneGraph;
+use fyrox_graph::{BaseSceneGraph, SceneGraph};
 use rapier2d::geometry::ColliderHandle;
 use std::{
     cell::Cell,
@@ -117,6 +117,42 @@ pub struct TriangleShape {
     pub c: Vector2<f32>,
 }
 
+impl TriangleShape {
+    /// Flips winding (orientation) of the triangle by swapping its second and third points.
+    pub fn flip_winding(&mut self) {
+        std::mem::swap(&mut self.b, &mut self.c);
+    }
+
+    /// Returns the area of the triangle.
+    pub fn area(&self) -> f32 {
+        0.5 * (self.b - self.a).perp(&(self.c - self.a)).abs()
+    }
+
+    /// Checks whether the triangle is degenerate - it has zero area, or it is so thin, that its
+    /// smallest height is less than `thinness` fraction of its longest edge, or some of its points
+    /// are not finite. Such triangles behave badly in physics: contacts with them are unstable and
+    /// bodies could pass through them.
+    pub fn is_degenerate(&self, thinness: f32) -> bool {
+        let points = [self.a, self.b, self.c];
+        if points
+            .iter()
+            .any(|point| !point.x.is_finite() || !point.y.is_finite())
+        {
+            return true;
+        }
+
+        let longest_edge = (0..3)
+            .map(|i| (points[(i + 1) % 3] - points[i]).norm())
+            .fold(0.0, f32::max);
+        if longest_edge <= 0.0 {
+            return true;
+        }
+
+        let smallest_height = 2.0 * self.area() / longest_edge;
+        smallest_height <= thinness * longest_edge
+    }
+}
+
 impl Default for TriangleShape {
     fn default() -> Self {
         Self {
@@ -228,6 +264,28 @@ impl ColliderShape {
     pub fn heightfield(geometry_source: GeometrySource) -> Self {
         Self::Heightfield(HeightfieldShape { geometry_source })
     }
+
+    /// Flips orientation of the shape, if it has one. Triangles get their winding flipped, segments
+    /// get their ends swapped (which flips their normal). Returns `true` if the shape was flipped,
+    /// `false` if the shape has no orientation. Flipping the same shape twice restores its initial
+    /// state.
+    pub fn flip_winding(&mut self) -> bool {
+        match self {
+            ColliderShape::Triangle(triangle) => {
+                triangle.flip_winding();
+                true
+            }
+            ColliderShape::Segment(segment) => {
+                std::mem::swap(&mut segment.begin, &mut segment.end);
+                true
+            }
+            ColliderShape::Ball(_)
+            | ColliderShape::Cuboid(_)
+            | ColliderShape::Capsule(_)
+            | ColliderShape::Trimesh(_)
+            | ColliderShape::Heightfield(_) => false,
+        }
+    }
 }
 
 /// Collider is a geometric entity that can be attached to a rigid body to allow participate it
@@ -705,6 +763,19 @@ impl ColliderBuilder {
     }
 }
 
+/// Returns handles of every 2D collider in the graph, that has a degenerate triangle shape. See
+/// [`TriangleShape::is_degenerate`] for more info about `thinness` parameter. It is intended to be
+/// used to catch authoring mistakes, for example before saving a scene.
+pub fn find_degenerate_triangle_colliders(graph: &Graph, thinness: f32) -> Vec<Handle<Node>> {
+    graph
+        .pair_iter()
+        .filter_map(|(handle, node)| match node.cast::<Collider>()?.shape() {
+            ColliderShape::Triangle(triangle) if triangle.is_degenerate(thinness) => Some(handle),
+            _ => None,
+        })
+        .collect()
+}
+
 #[cfg(test)]
 mod test {
 
@@ -712,7 +783,9 @@ mod test {
     use crate::scene::{
         base::BaseBuilder,
         dim2::{
-            collider::{ColliderBuilder, ColliderShape},
+            collider::{
+                find_degenerate_triangle_colliders, ColliderBuilder, ColliderShape, TriangleShape,
+            },
             rigidbody::RigidBodyBuilder,
         },
         graph::Graph,
@@ -776,4 +849,68 @@ mod test {
                 .count()
         );
     }
+
+    #[test]
+    fn test_degenerate_triangles() {
+        let triangle =
+            |a: Vector2<f32>, b: Vector2<f32>, c: Vector2<f32>| TriangleShape { a, b, c };
+
+        let good = triangle(
+            Vector2::new(0.0, 0.0),
+            Vector2::new(1.0, 0.0),
+            Vector2::new(0.0, 1.0),
+        );
+        assert_eq!(good.area(), 0.5);
+        assert!(!good.is_degenerate(1.0e-3));
+
+        let flat = triangle(
+            Vector2::new(0.0, 0.0),
+            Vector2::new(1.0, 0.0),
+            Vector2::new(2.0, 0.0),
+        );
+        assert_eq!(flat.area(), 0.0);
+        assert!(flat.is_degenerate(1.0e-3));
+
+        let thin = triangle(
+            Vector2::new(0.0, 0.0),
+            Vector2::new(100.0, 0.0),
+            Vector2::new(50.0, 0.01),
+        );
+        assert!(thin.is_degenerate(1.0e-3));
+        assert!(!thin.is_degenerate(1.0e-5));
+
+        let point = triangle(Vector2::default(), Vector2::default(), Vector2::default());
+        assert!(point.is_degenerate(0.0));
+
+        let mut graph = Graph::new();
+        let good_collider = ColliderBuilder::new(BaseBuilder::new())
+            .with_shape(ColliderShape::Triangle(good))
+            .build(&mut graph);
+        let flat_collider = ColliderBuilder::new(BaseBuilder::new())
+            .with_shape(ColliderShape::Triangle(flat))
+            .build(&mut graph);
+        let degenerate = find_degenerate_triangle_colliders(&graph, 1.0e-3);
+        assert!(degenerate.contains(&flat_collider));
+        assert!(!degenerate.contains(&good_collider));
+    }
+
+    #[test]
+    fn test_flip_winding() {
+        let a = Vector2::new(0.0, 0.0);
+        let b = Vector2::new(1.0, 0.0);
+        let c = Vector2::new(0.0, 1.0);
+        let mut shape = ColliderShape::triangle(a, b, c);
+        assert!(shape.flip_winding());
+        assert_eq!(shape, ColliderShape::triangle(a, c, b));
+        assert!(shape.flip_winding());
+        assert_eq!(shape, ColliderShape::triangle(a, b, c));
+
+        let mut shape = ColliderShape::segment(a, b);
+        assert!(shape.flip_winding());
+        assert_eq!(shape, ColliderShape::segment(b, a));
+
+        let mut shape = ColliderShape::ball(1.0);
+        assert!(!shape.flip_winding());
+        assert_eq!(shape, ColliderShape::ball(1.0));
+    }
 }
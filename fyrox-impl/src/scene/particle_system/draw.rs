@@ -1,6 +1,6 @@
 use crate::{
     core::{
-        algebra::{Vector2, Vector3},
+        algebra::{Vector2, Vector3, Vector4},
         color::Color,
     },
     scene::mesh::buffer::{
@@ -17,6 +17,19 @@ pub struct Vertex {
     pub size: f32,
     pub rotation: f32,
     pub color: Color,
+    /// An axis along which the particle quad is aligned, zero means that the quad is aligned to
+    /// the screen (billboard).
+    pub axis: Vector3<f32>,
+    /// An amount by which the particle quad is elongated along its axis, used to stretch particles
+    /// by their velocity.
+    pub stretch: f32,
+    /// Custom value of the particle.
+    pub custom: f32,
+    /// Rectangle of the current sprite sheet frame in texture coordinates, `xy` - offset, `zw` -
+    /// size.
+    pub frame: Vector4<f32>,
+    /// Minimum size of the particle quad on screen in pixels, zero means no limit.
+    pub min_screen_size: f32,
 }
 
 impl VertexTrait for Vertex {
@@ -62,6 +75,46 @@ impl VertexTrait for Vertex {
                 shader_location: 4,
                 normalized: true,
             },
+            VertexAttributeDescriptor {
+                usage: VertexAttributeUsage::Custom2,
+                data_type: VertexAttributeDataType::F32,
+                size: 3,
+                divisor: 0,
+                shader_location: 5,
+                normalized: false,
+            },
+            VertexAttributeDescriptor {
+                usage: VertexAttributeUsage::Custom3,
+                data_type: VertexAttributeDataType::F32,
+                size: 1,
+                divisor: 0,
+                shader_location: 6,
+                normalized: false,
+            },
+            VertexAttributeDescriptor {
+                usage: VertexAttributeUsage::Custom4,
+                data_type: VertexAttributeDataType::F32,
+                size: 1,
+                divisor: 0,
+                shader_location: 7,
+                normalized: false,
+            },
+            VertexAttributeDescriptor {
+                usage: VertexAttributeUsage::Custom5,
+                data_type: VertexAttributeDataType::F32,
+                size: 4,
+                divisor: 0,
+                shader_location: 8,
+                normalized: false,
+            },
+            VertexAttributeDescriptor {
+                usage: VertexAttributeUsage::Custom6,
+                data_type: VertexAttributeDataType::F32,
+                size: 1,
+                divisor: 0,
+                shader_location: 9,
+                normalized: false,
+            },
         ]
     }
 }
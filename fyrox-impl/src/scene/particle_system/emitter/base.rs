@@ -2,12 +2,115 @@
 
 use crate::{
     core::{
-        algebra::Vector3, color::Color, numeric_range::RangeExt, reflect::prelude::*,
+        algebra::{Matrix4, UnitQuaternion, Vector3},
+        color::Color,
+        math::aabb::AxisAlignedBoundingBox,
+        numeric_range::RangeExt,
+        reflect::prelude::*,
+        type_traits::prelude::*,
         visitor::prelude::*,
     },
-    scene::particle_system::{Particle, ParticleSystemRng},
+    rand::Rng,
+    scene::particle_system::{emitter::repair_non_negative, Particle, ParticleSystemRng},
 };
 use std::ops::Range;
+use strum_macros::{AsRefStr, EnumString, VariantNames};
+
+/// Defines a volume which is used by an emitter to pick initial positions of particles.
+#[derive(
+    Default,
+    Copy,
+    Clone,
+    PartialOrd,
+    PartialEq,
+    Eq,
+    Ord,
+    Hash,
+    Debug,
+    Visit,
+    Reflect,
+    AsRefStr,
+    EnumString,
+    VariantNames,
+    TypeUuidProvider,
+)]
+#[type_uuid(id = "6a3c1e2d-5b0f-4f7e-9c47-1d8e2b7a9f31")]
+#[repr(u32)]
+pub enum EmissionVolume {
+    /// Particles are spawned inside the shape of an emitter (cuboid, sphere, etc.).
+    #[default]
+    Shape = 0,
+    /// Particles are spawned inside the bounding box of a mesh, that is the parent of the
+    /// particle system. The shape of the emitter is used if there's no such mesh or the mesh has
+    /// no geometry.
+    NodeBounds = 1,
+    /// Particles are spawned on the surface of the bounding box of a mesh, that is the parent of
+    /// the particle system. The shape of the emitter is used if there's no such mesh or the mesh
+    /// has no geometry.
+    NodeBoundsSurface = 2,
+}
+
+/// Defines what drives the amount of particles spawned by an emitter.
+#[derive(
+    Default,
+    Copy,
+    Clone,
+    PartialOrd,
+    PartialEq,
+    Eq,
+    Ord,
+    Hash,
+    Debug,
+    Visit,
+    Reflect,
+    AsRefStr,
+    EnumString,
+    VariantNames,
+    TypeUuidProvider,
+)]
+#[type_uuid(id = "b2f7c9e4-3d1a-4c8b-a6e5-8f0d2c4b7e19")]
+#[repr(u32)]
+pub enum EmissionMode {
+    /// Particles are spawned with the spawn rate of the emitter (particles per second).
+    #[default]
+    Time = 0,
+    /// Particles are spawned proportionally to the distance (in world units) travelled by the
+    /// particle system since the previous frame, using the amount of particles per meter of the
+    /// emitter. Stationary particle system does not emit anything in this mode. It is useful for
+    /// trails, that should look the same regardless of the speed of their owner.
+    Distance = 1,
+}
+
+/// Defines how an emitter fills the custom value of emitted particles (see
+/// [`Particle::custom`]).
+#[derive(
+    Default,
+    Copy,
+    Clone,
+    PartialOrd,
+    PartialEq,
+    Eq,
+    Ord,
+    Hash,
+    Debug,
+    Visit,
+    Reflect,
+    AsRefStr,
+    EnumString,
+    VariantNames,
+    TypeUuidProvider,
+)]
+#[type_uuid(id = "9c4e1a7b-2f6d-4b38-8e05-6a1d3c9f7b24")]
+#[repr(u32)]
+pub enum ParticleCustomData {
+    /// The custom value is left untouched (zero, unless the emitter sets it on its own).
+    #[default]
+    Keep = 0,
+    /// Random value in `[0; 1)` range.
+    Random = 1,
+    /// Sequential index of the particle among all the particles emitted by the emitter.
+    Index = 2,
+}
 
 /// See module docs.
 #[derive(Debug, Visit, PartialEq, Reflect)]
@@ -15,10 +118,14 @@ pub struct BaseEmitter {
     /// Offset from center of particle system.
     position: Vector3<f32>,
     /// Particle spawn rate in unit-per-second. If < 0, spawns `max_particles`,
-    /// spawns nothing if `max_particles` < 0
+    /// spawns nothing if `max_particles` < 0. It could be animated by a property track of
+    /// `TrackValue::Real` values, bound as `ValueType::U32` to the
+    /// `emitters[<index>].<Kind>@0.emitter.particle_spawn_rate` path of a particle system. The
+    /// values are truncated towards zero, negative values give zero.
     #[visit(rename = "SpawnRate")]
     particle_spawn_rate: u32,
-    /// Maximum amount of particles emitter can emit. Unlimited if < 0
+    /// Maximum amount of alive particles of the emitter, unlimited if [`None`]. If particle
+    /// resurrection is disabled, it is the total amount of particles the emitter can emit.
     #[visit(optional)] // Backward compatibility
     max_particles: Option<u32>,
     /// Range of initial lifetime of a particle
@@ -40,15 +147,81 @@ pub struct BaseEmitter {
     rotation: Range<f32>,
     #[reflect(hidden)]
     pub(crate) alive_particles: u32,
+    /// Fractional amount of particles that were not spawned yet. It is carried over to the next
+    /// frames, so the emission rate stays correct even if less than one particle is emitted per
+    /// frame.
     #[visit(skip)]
     #[reflect(hidden)]
-    time: f32,
+    spawn_accumulator: f32,
     #[visit(skip)]
     #[reflect(hidden)]
     pub(crate) particles_to_spawn: u32,
     resurrect_particles: bool,
     #[reflect(hidden)]
     pub(crate) spawned_particles: u64,
+    #[visit(optional)] // Backward compatibility
+    emission_volume: EmissionVolume,
+    /// A delay (in seconds) from the start of the particle system after which the emitter starts
+    /// to emit particles.
+    #[visit(optional)] // Backward compatibility
+    #[reflect(min_value = 0.0, step = 0.1)]
+    start_delay: f32,
+    /// Orientation of the emitter relative to the particle system. It rotates emitted particles
+    /// (their offset from the emitter position and their velocity).
+    #[visit(optional)] // Backward compatibility
+    orientation: UnitQuaternion<f32>,
+    /// Disabled emitter does not emit new particles, but its existing particles continue to live.
+    #[visit(optional)] // Backward compatibility
+    enabled: bool,
+    /// Paused emitter does not emit new particles, but unlike disabled one, it keeps its fractional
+    /// amount of not yet spawned particles, so it continues exactly where it was paused.
+    #[visit(optional)] // Backward compatibility
+    paused: bool,
+    /// An arbitrary tag of the emitter, that allows you to control multiple emitters of a particle
+    /// system at once. See [`crate::scene::particle_system::ParticleSystem::set_group_enabled`] for more info.
+    #[visit(optional)] // Backward compatibility
+    group: String,
+    /// If set, the spawn rate is treated as the amount of particles per second per unit of volume
+    /// of the emitter, so the density of particles remains the same when the emitter is resized.
+    #[visit(optional)] // Backward compatibility
+    normalize_density: bool,
+    /// Defines what drives the amount of spawned particles, see [`EmissionMode`] docs.
+    #[visit(optional)] // Backward compatibility
+    emission_mode: EmissionMode,
+    /// Amount of particles spawned per meter travelled by the particle system. It is used only
+    /// in [`EmissionMode::Distance`] mode.
+    #[visit(optional)] // Backward compatibility
+    #[reflect(min_value = 0.0, step = 0.1)]
+    particles_per_meter: f32,
+    /// Distance (in world units) travelled by the particle system since the previous update. It
+    /// is refreshed by the particle system on every update and consumed by the next tick.
+    #[visit(skip)]
+    #[reflect(hidden)]
+    pub(crate) travelled_distance: f32,
+    /// Defines how the emitter fills the custom value of emitted particles.
+    #[visit(optional)] // Backward compatibility
+    custom_data: ParticleCustomData,
+    /// Index of the next particle, that is used for [`ParticleCustomData::Index`].
+    #[visit(skip)]
+    #[reflect(hidden)]
+    custom_index: u32,
+    /// Bounding box of the parent mesh in local coordinates of the particle system. It is
+    /// refreshed by the particle system on every update.
+    #[visit(skip)]
+    #[reflect(hidden)]
+    pub(crate) node_bounds: Option<AxisAlignedBoundingBox>,
+    /// Age of the particle system normalized to `[0; 1]` range using lifetime of the particle
+    /// system node. It is [`None`] if the node has unlimited lifetime.
+    #[visit(skip)]
+    #[reflect(hidden)]
+    pub(crate) normalized_system_age: Option<f32>,
+    #[visit(skip)]
+    #[reflect(hidden)]
+    pub(crate) system_age: f32,
+    /// Volume of the emitter. It is refreshed by the particle system on every update.
+    #[visit(skip)]
+    #[reflect(hidden)]
+    pub(crate) volume: f32,
 }
 
 /// Emitter builder allows you to construct emitter in declarative manner.
@@ -66,6 +239,16 @@ pub struct BaseEmitterBuilder {
     rotation_speed: Range<f32>,
     rotation: Range<f32>,
     resurrect_particles: bool,
+    emission_volume: EmissionVolume,
+    start_delay: f32,
+    orientation: UnitQuaternion<f32>,
+    enabled: bool,
+    paused: bool,
+    group: String,
+    normalize_density: bool,
+    emission_mode: EmissionMode,
+    particles_per_meter: f32,
+    custom_data: ParticleCustomData,
 }
 
 impl Default for BaseEmitterBuilder {
@@ -90,6 +273,16 @@ impl BaseEmitterBuilder {
             rotation_speed: -0.02..0.02,
             rotation: -std::f32::consts::PI..std::f32::consts::PI,
             resurrect_particles: true,
+            emission_volume: Default::default(),
+            start_delay: 0.0,
+            orientation: UnitQuaternion::identity(),
+            enabled: true,
+            paused: false,
+            group: Default::default(),
+            normalize_density: false,
+            emission_mode: Default::default(),
+            particles_per_meter: 10.0,
+            custom_data: Default::default(),
         }
     }
 
@@ -105,7 +298,8 @@ impl BaseEmitterBuilder {
         self
     }
 
-    /// Sets desired max amount of particles.
+    /// Sets desired max amount of alive particles of the emitter. See
+    /// [`BaseEmitter::set_max_particles`] for more info.
     pub fn with_max_particles(mut self, value: u32) -> Self {
         self.max_particles = Some(value);
         self
@@ -165,6 +359,69 @@ impl BaseEmitterBuilder {
         self
     }
 
+    /// Sets desired emission volume.
+    pub fn with_emission_volume(mut self, emission_volume: EmissionVolume) -> Self {
+        self.emission_volume = emission_volume;
+        self
+    }
+
+    /// Sets desired start delay in seconds.
+    pub fn with_start_delay(mut self, start_delay: f32) -> Self {
+        self.start_delay = start_delay;
+        self
+    }
+
+    /// Sets desired orientation of the emitter.
+    pub fn with_orientation(mut self, orientation: UnitQuaternion<f32>) -> Self {
+        self.orientation = orientation;
+        self
+    }
+
+    /// Sets whether the emitter is enabled or not.
+    pub fn with_enabled(mut self, enabled: bool) -> Self {
+        self.enabled = enabled;
+        self
+    }
+
+    /// Sets whether the emitter is paused or not. See [`BaseEmitter::pause`] for more info.
+    pub fn with_paused(mut self, paused: bool) -> Self {
+        self.paused = paused;
+        self
+    }
+
+    /// Sets desired group of the emitter.
+    pub fn with_group<S: AsRef<str>>(mut self, group: S) -> Self {
+        self.group = group.as_ref().to_owned();
+        self
+    }
+
+    /// Sets whether the spawn rate should be scaled by the volume of the emitter or not. See
+    /// [`BaseEmitter::set_density_normalization`] for more info.
+    pub fn with_density_normalization(mut self, normalize_density: bool) -> Self {
+        self.normalize_density = normalize_density;
+        self
+    }
+
+    /// Sets desired emission mode of the emitter. See [`EmissionMode`] docs for more info.
+    pub fn with_emission_mode(mut self, emission_mode: EmissionMode) -> Self {
+        self.emission_mode = emission_mode;
+        self
+    }
+
+    /// Sets desired amount of particles spawned per meter travelled by the particle system. See
+    /// [`BaseEmitter::set_particles_per_meter`] for more info.
+    pub fn with_particles_per_meter(mut self, particles_per_meter: f32) -> Self {
+        self.particles_per_meter = particles_per_meter;
+        self
+    }
+
+    /// Sets how the emitter fills the custom value of emitted particles. See
+    /// [`ParticleCustomData`] docs for more info.
+    pub fn with_custom_data(mut self, custom_data: ParticleCustomData) -> Self {
+        self.custom_data = custom_data;
+        self
+    }
+
     /// Creates new instance of emitter.
     pub fn build(self) -> BaseEmitter {
         BaseEmitter {
@@ -180,29 +437,134 @@ impl BaseEmitterBuilder {
             rotation_speed: self.rotation_speed,
             rotation: self.rotation,
             alive_particles: 0,
-            time: 0.0,
+            spawn_accumulator: 0.0,
             particles_to_spawn: 0,
             resurrect_particles: self.resurrect_particles,
             spawned_particles: 0,
+            emission_volume: self.emission_volume,
+            start_delay: self.start_delay.max(0.0),
+            orientation: self.orientation,
+            enabled: self.enabled,
+            paused: self.paused,
+            group: self.group,
+            normalize_density: self.normalize_density,
+            emission_mode: self.emission_mode,
+            particles_per_meter: self.particles_per_meter.max(0.0),
+            travelled_distance: 0.0,
+            custom_data: self.custom_data,
+            custom_index: 0,
+            node_bounds: None,
+            normalized_system_age: None,
+            system_age: 0.0,
+            volume: 0.0,
         }
     }
 }
 
 impl BaseEmitter {
+    /// Replaces invalid values (non-finite numbers, negative delay) of the parameters of the
+    /// emitter with the default ones and adds the names of the replaced parameters to the given
+    /// list. See [`super::Emitter::repair_invalid_parameters`] for more info.
+    pub(crate) fn repair_parameters(&mut self, repaired: &mut Vec<&'static str>) {
+        let default = BaseEmitter::default();
+
+        if !self.position.iter().all(|c| c.is_finite()) {
+            self.position = default.position;
+            repaired.push("position");
+        }
+
+        for (name, range, default_range) in [
+            ("lifetime", &mut self.lifetime, default.lifetime),
+            ("size", &mut self.size, default.size),
+            (
+                "size_modifier",
+                &mut self.size_modifier,
+                default.size_modifier,
+            ),
+            ("x_velocity", &mut self.x_velocity, default.x_velocity),
+            ("y_velocity", &mut self.y_velocity, default.y_velocity),
+            ("z_velocity", &mut self.z_velocity, default.z_velocity),
+            (
+                "rotation_speed",
+                &mut self.rotation_speed,
+                default.rotation_speed,
+            ),
+            ("rotation", &mut self.rotation, default.rotation),
+        ] {
+            if !(range.start.is_finite() && range.end.is_finite()) {
+                *range = default_range;
+                repaired.push(name);
+            }
+        }
+
+        repair_non_negative("start_delay", &mut self.start_delay, 0.0, repaired);
+        repair_non_negative(
+            "particles_per_meter",
+            &mut self.particles_per_meter,
+            default.particles_per_meter,
+            repaired,
+        );
+
+        if !self.orientation.coords.iter().all(|c| c.is_finite()) {
+            self.orientation = default.orientation;
+            repaired.push("orientation");
+        }
+    }
+
     /// Updates emitter and emits required amount of particles each call. There is no
     /// need to call it manually, it will be automatically called by scene update call.
     pub fn tick(&mut self, dt: f32) {
-        self.time += dt;
-        let time_amount_per_particle = 1.0 / self.particle_spawn_rate as f32;
-        self.particles_to_spawn = (self.time / time_amount_per_particle) as u32;
-        self.time -= time_amount_per_particle * self.particles_to_spawn as f32;
-        if let Some(max_particles) = self.max_particles {
-            let alive_particles = self.alive_particles;
-            if alive_particles < max_particles
-                && alive_particles + self.particles_to_spawn > max_particles
-            {
-                self.particles_to_spawn = max_particles.saturating_sub(alive_particles);
+        // The distance is consumed by every tick, so it won't be counted twice when the particle
+        // system is rewound or the emitter is disabled.
+        let travelled_distance = std::mem::take(&mut self.travelled_distance);
+
+        if !self.enabled {
+            self.spawn_accumulator = 0.0;
+            self.particles_to_spawn = 0;
+            return;
+        }
+
+        if self.paused || self.system_age < self.start_delay {
+            self.particles_to_spawn = 0;
+            return;
+        }
+
+        let spawn_amount = match self.emission_mode {
+            EmissionMode::Time => {
+                let spawn_rate = if self.normalize_density {
+                    self.particle_spawn_rate as f32 * self.volume
+                } else {
+                    self.particle_spawn_rate as f32
+                };
+
+                if !(spawn_rate > 0.0 && spawn_rate.is_finite()) {
+                    // Nothing to spawn, also prevents accumulating NaN (inf * 0) when the rate is
+                    // animated from zero.
+                    self.spawn_accumulator = 0.0;
+                    self.particles_to_spawn = 0;
+                    return;
+                }
+
+                spawn_rate * dt
             }
+            EmissionMode::Distance => self.particles_per_meter * travelled_distance,
+        };
+
+        if !spawn_amount.is_finite() {
+            self.spawn_accumulator = 0.0;
+            self.particles_to_spawn = 0;
+            return;
+        }
+
+        self.spawn_accumulator += spawn_amount.max(0.0);
+        self.particles_to_spawn = self.spawn_accumulator as u32;
+        self.spawn_accumulator -= self.particles_to_spawn as f32;
+        if let Some(max_particles) = self.max_particles {
+            // The limit is per emitter, so a greedy emitter cannot take the whole capacity of the
+            // particle system from the others.
+            self.particles_to_spawn = self
+                .particles_to_spawn
+                .min(max_particles.saturating_sub(self.alive_particles));
             if !self.resurrect_particles && self.spawned_particles >= u64::from(max_particles) {
                 self.particles_to_spawn = 0;
             }
@@ -227,6 +589,117 @@ impl BaseEmitter {
         particle.rotation_speed = self.rotation_speed.random(rng);
     }
 
+    /// Picks a random position of a particle using current emission volume. Returns [`None`] if
+    /// the emitter should use its own shape, which happens either if the emission volume is set
+    /// to [`EmissionVolume::Shape`] or there's no parent mesh with valid bounds to take them from.
+    pub fn sample_node_bounds(&self, rng: &mut ParticleSystemRng) -> Option<Vector3<f32>> {
+        if self.emission_volume == EmissionVolume::Shape {
+            return None;
+        }
+        let bounds = self.node_bounds?;
+        let min = bounds.min;
+        let max = bounds.max;
+        let mut point = Vector3::new(
+            (min.x..max.x).random(rng),
+            (min.y..max.y).random(rng),
+            (min.z..max.z).random(rng),
+        );
+        if self.emission_volume == EmissionVolume::NodeBoundsSurface {
+            // Pick a face with the probability proportional to its area, so the particles
+            // are distributed uniformly over the surface and then snap the point to it.
+            let size = bounds.max - bounds.min;
+            let areas = [size.y * size.z, size.x * size.z, size.x * size.y];
+            let total_area = 2.0 * (areas[0] + areas[1] + areas[2]);
+            if total_area > 0.0 {
+                let mut value = rng.gen_range(0.0..total_area);
+                for (axis, area) in areas.iter().enumerate() {
+                    if value < 2.0 * area || axis == 2 {
+                        point[axis] = if value < *area { min[axis] } else { max[axis] };
+                        break;
+                    }
+                    value -= 2.0 * area;
+                }
+            }
+        }
+        Some(self.position + point)
+    }
+
+    /// Turns the bounds of the shape of the emitter into the bounds of the space in which the
+    /// emitter actually spawns particles, by taking the bounds of the parent mesh (see
+    /// [`EmissionVolume`]) and the orientation of the emitter into account.
+    pub(crate) fn oriented_bounds(
+        &self,
+        shape_bounds: AxisAlignedBoundingBox,
+    ) -> AxisAlignedBoundingBox {
+        if self.emission_volume != EmissionVolume::Shape {
+            if let Some(mut node_bounds) = self.node_bounds {
+                node_bounds.offset(self.position);
+                return node_bounds;
+            }
+        }
+        if self.orientation == UnitQuaternion::identity() {
+            return shape_bounds;
+        }
+        // The orientation rotates the shape around the position of the emitter.
+        let transform = Matrix4::new_translation(&self.position)
+            * self.orientation.to_homogeneous()
+            * Matrix4::new_translation(&-self.position);
+        shape_bounds.transform(&transform)
+    }
+
+    /// Returns the custom value for the next emitted particle, or [`None`] if the custom value
+    /// should be left untouched. See [`ParticleCustomData`] docs for more info.
+    pub(crate) fn next_custom_data(&mut self, rng: &mut ParticleSystemRng) -> Option<f32> {
+        match self.custom_data {
+            ParticleCustomData::Keep => None,
+            ParticleCustomData::Random => Some(rng.gen_range(0.0..1.0)),
+            ParticleCustomData::Index => {
+                let index = self.custom_index;
+                self.custom_index = self.custom_index.wrapping_add(1);
+                Some(index as f32)
+            }
+        }
+    }
+
+    /// Sets how the emitter fills the custom value of emitted particles. See
+    /// [`ParticleCustomData`] docs for more info.
+    pub fn set_custom_data(&mut self, custom_data: ParticleCustomData) -> &mut Self {
+        self.custom_data = custom_data;
+        self
+    }
+
+    /// Returns how the emitter fills the custom value of emitted particles.
+    pub fn custom_data(&self) -> ParticleCustomData {
+        self.custom_data
+    }
+
+    /// Returns volume of the bounding box of the parent mesh, if the emitter uses it to spawn
+    /// particles. See [`EmissionVolume`] docs for more info.
+    pub fn node_bounds_volume(&self) -> Option<f32> {
+        if self.emission_volume == EmissionVolume::Shape {
+            return None;
+        }
+        self.node_bounds.map(|bounds| bounds.volume())
+    }
+
+    /// Returns age of the particle system normalized to `[0; 1]` range, where `1.0` means the end
+    /// of the lifetime of the particle system node. Returns [`None`] if the node has unlimited
+    /// lifetime.
+    pub fn normalized_system_age(&self) -> Option<f32> {
+        self.normalized_system_age
+    }
+
+    /// Sets new emission volume of the emitter.
+    pub fn set_emission_volume(&mut self, emission_volume: EmissionVolume) -> &mut Self {
+        self.emission_volume = emission_volume;
+        self
+    }
+
+    /// Returns current emission volume of the emitter.
+    pub fn emission_volume(&self) -> EmissionVolume {
+        self.emission_volume
+    }
+
     /// Sets new position of emitter in local coordinates.
     pub fn set_position(&mut self, position: Vector3<f32>) -> &mut Self {
         self.position = position;
@@ -249,13 +722,58 @@ impl BaseEmitter {
         self.particle_spawn_rate
     }
 
-    /// Sets maximum amount of particles.
+    /// Enables or disables density normalization. When enabled, the spawn rate is treated as the
+    /// amount of particles per second per unit of volume of the emitter, so the density of
+    /// particles remains the same when the emitter is resized. For example, an emitter with the
+    /// spawn rate of 10 and the volume of 2.5 cubic units will emit 25 particles per second. When
+    /// disabled (default), the emitter spawns a fixed amount of particles per second regardless
+    /// of its size.
+    pub fn set_density_normalization(&mut self, normalize_density: bool) -> &mut Self {
+        self.normalize_density = normalize_density;
+        self
+    }
+
+    /// Returns `true` if the spawn rate is scaled by the volume of the emitter, `false` - otherwise.
+    pub fn is_density_normalized(&self) -> bool {
+        self.normalize_density
+    }
+
+    /// Sets new emission mode of the emitter. See [`EmissionMode`] docs for more info.
+    pub fn set_emission_mode(&mut self, emission_mode: EmissionMode) -> &mut Self {
+        self.emission_mode = emission_mode;
+        self
+    }
+
+    /// Returns current emission mode of the emitter.
+    pub fn emission_mode(&self) -> EmissionMode {
+        self.emission_mode
+    }
+
+    /// Sets new amount of particles spawned per meter travelled by the particle system in world
+    /// space. It is used only in [`EmissionMode::Distance`] mode, for example a trail with 20
+    /// particles per meter spawns 20 particles when its owner moves for 1 meter, regardless of how
+    /// fast it moves. Negative values are clamped to zero.
+    pub fn set_particles_per_meter(&mut self, particles_per_meter: f32) -> &mut Self {
+        self.particles_per_meter = particles_per_meter.max(0.0);
+        self
+    }
+
+    /// Returns current amount of particles spawned per meter travelled by the particle system.
+    pub fn particles_per_meter(&self) -> f32 {
+        self.particles_per_meter
+    }
+
+    /// Sets maximum amount of alive particles of the emitter. The limit is applied to each emitter
+    /// separately, which gives predictable balance between emitters of the same particle system.
+    /// If it is [`None`], the emitter is limited only by the capacity of the particle system (see
+    /// [`crate::scene::particle_system::ParticleSystem::set_particle_capacity`]). If particle
+    /// resurrection is disabled, the limit is the total amount of particles the emitter can emit.
     pub fn set_max_particles(&mut self, max: Option<u32>) -> &mut Self {
         self.max_particles = max;
         self
     }
 
-    /// Returns maximum amount of particles.
+    /// Returns maximum amount of alive particles of the emitter.
     pub fn max_particles(&self) -> Option<u32> {
         self.max_particles
     }
@@ -373,6 +891,104 @@ impl BaseEmitter {
         self.resurrect_particles
     }
 
+    /// Sets new start delay (in seconds) of the emitter. The emitter won't emit any particles
+    /// until the age of the particle system exceeds the delay. Negative values are clamped to zero.
+    pub fn set_start_delay(&mut self, start_delay: f32) -> &mut Self {
+        self.start_delay = start_delay.max(0.0);
+        self
+    }
+
+    /// Returns current start delay (in seconds) of the emitter.
+    pub fn start_delay(&self) -> f32 {
+        self.start_delay
+    }
+
+    /// Sets new orientation of the emitter relative to the particle system. It allows to rotate the
+    /// shape of the emitter without rotating the particle system node and to have multiple
+    /// differently oriented emitters in the same particle system. Particles emitted using the
+    /// bounds of the parent mesh (see [`EmissionVolume`]) keep their position, only their velocity
+    /// is rotated.
+    pub fn set_orientation(&mut self, orientation: UnitQuaternion<f32>) -> &mut Self {
+        self.orientation = orientation;
+        self
+    }
+
+    /// Returns current orientation of the emitter relative to the particle system.
+    pub fn orientation(&self) -> UnitQuaternion<f32> {
+        self.orientation
+    }
+
+    /// Enables or disables the emitter. Disabled emitter does not emit new particles, but its
+    /// existing particles continue to live until the end of their lifetime. Disabling discards
+    /// the fractional amount of not yet spawned particles, use [`Self::pause`] if the emitter
+    /// must continue exactly where it was stopped.
+    pub fn set_enabled(&mut self, enabled: bool) -> &mut Self {
+        self.enabled = enabled;
+        self
+    }
+
+    /// Returns `true` if the emitter is enabled, `false` - otherwise.
+    pub fn is_enabled(&self) -> bool {
+        self.enabled
+    }
+
+    /// Pauses the emitter. Paused emitter does not emit new particles, but keeps the fractional
+    /// amount of not yet spawned particles, so [`Self::resume`] continues the emission exactly
+    /// where it was paused. Existing particles continue to live until the end of their lifetime.
+    pub fn pause(&mut self) -> &mut Self {
+        self.paused = true;
+        self
+    }
+
+    /// Resumes the emitter paused by [`Self::pause`].
+    pub fn resume(&mut self) -> &mut Self {
+        self.paused = false;
+        self
+    }
+
+    /// Returns `true` if the emitter is paused, `false` - otherwise.
+    pub fn is_paused(&self) -> bool {
+        self.paused
+    }
+
+    /// Sets new group of the emitter.
+    pub fn set_group<S: AsRef<str>>(&mut self, group: S) -> &mut Self {
+        self.group = group.as_ref().to_owned();
+        self
+    }
+
+    /// Returns group of the emitter.
+    pub fn group(&self) -> &str {
+        &self.group
+    }
+
+    /// Rotates the emitted particle using the orientation of the emitter. There is no need to call
+    /// it manually, the particle system calls it for every emitted particle.
+    pub(crate) fn apply_orientation(&self, particle: &mut Particle) {
+        if self.orientation == UnitQuaternion::identity() {
+            return;
+        }
+        let uses_node_bounds =
+            self.emission_volume != EmissionVolume::Shape && self.node_bounds.is_some();
+        if !uses_node_bounds {
+            particle.position = self.position
+                + self
+                    .orientation
+                    .transform_vector(&(particle.position - self.position));
+        }
+        particle.velocity = self.orientation.transform_vector(&particle.velocity);
+    }
+
+    /// Returns amount of particles of the emitter, that are alive at the moment.
+    pub fn alive_particles(&self) -> u32 {
+        self.alive_particles
+    }
+
+    /// Returns amount of particles, that were emitted during the last update of the particle system.
+    pub fn particles_emitted_last_tick(&self) -> u32 {
+        self.particles_to_spawn
+    }
+
     /// Returns amount of spawned particles from moment of creation of particle system.
     pub fn spawned_particles(&self) -> u64 {
         self.spawned_particles
@@ -394,10 +1010,26 @@ impl Clone for BaseEmitter {
             rotation_speed: self.rotation_speed.clone(),
             rotation: self.rotation.clone(),
             alive_particles: self.alive_particles,
-            time: self.time,
+            spawn_accumulator: self.spawn_accumulator,
             particles_to_spawn: 0,
             resurrect_particles: self.resurrect_particles,
             spawned_particles: self.spawned_particles,
+            emission_volume: self.emission_volume,
+            node_bounds: self.node_bounds,
+            start_delay: self.start_delay,
+            orientation: self.orientation,
+            enabled: self.enabled,
+            paused: self.paused,
+            group: self.group.clone(),
+            normalize_density: self.normalize_density,
+            emission_mode: self.emission_mode,
+            particles_per_meter: self.particles_per_meter,
+            travelled_distance: 0.0,
+            custom_data: self.custom_data,
+            custom_index: self.custom_index,
+            normalized_system_age: self.normalized_system_age,
+            system_age: self.system_age,
+            volume: self.volume,
         }
     }
 }
@@ -417,10 +1049,26 @@ impl Default for BaseEmitter {
             rotation_speed: -0.02..0.02,
             rotation: -std::f32::consts::PI..std::f32::consts::PI,
             alive_particles: 0,
-            time: 0.0,
+            spawn_accumulator: 0.0,
             particles_to_spawn: 0,
             resurrect_particles: true,
             spawned_particles: 0,
+            emission_volume: Default::default(),
+            start_delay: 0.0,
+            orientation: UnitQuaternion::identity(),
+            enabled: true,
+            paused: false,
+            group: Default::default(),
+            normalize_density: false,
+            emission_mode: Default::default(),
+            particles_per_meter: 10.0,
+            travelled_distance: 0.0,
+            custom_data: Default::default(),
+            custom_index: 0,
+            node_bounds: None,
+            normalized_system_age: None,
+            system_age: 0.0,
+            volume: 0.0,
         }
     }
 }
@@ -4,11 +4,14 @@
 use crate::core::numeric_range::RangeExt;
 use crate::scene::particle_system::ParticleSystemRng;
 use crate::{
-    core::{algebra::Vector3, reflect::prelude::*, visitor::prelude::*},
+    core::{
+        algebra::Vector3, math::aabb::AxisAlignedBoundingBox, reflect::prelude::*,
+        visitor::prelude::*,
+    },
     scene::particle_system::{
         emitter::{
             base::{BaseEmitter, BaseEmitterBuilder},
-            Emit, Emitter,
+            repair_non_negative, Emit, Emitter,
         },
         particle::Particle,
     },
@@ -42,6 +45,16 @@ impl DerefMut for CuboidEmitter {
 }
 
 impl CuboidEmitter {
+    /// Replaces invalid values of the parameters of the emitter with the default ones and adds
+    /// the names of the replaced parameters to the given list. See
+    /// [`Emitter::repair_invalid_parameters`] for more info.
+    pub(crate) fn repair_parameters(&mut self, repaired: &mut Vec<&'static str>) {
+        self.emitter.repair_parameters(repaired);
+        repair_non_negative("half_width", &mut self.half_width, 0.5, repaired);
+        repair_non_negative("half_height", &mut self.half_height, 0.5, repaired);
+        repair_non_negative("half_depth", &mut self.half_depth, 0.5, repaired);
+    }
+
     /// Creates new box emitter of given width, height and depth.
     pub fn new(emitter: BaseEmitter, width: f32, height: f32, depth: f32) -> Self {
         Self {
@@ -81,6 +94,19 @@ impl CuboidEmitter {
     pub fn set_half_depth(&mut self, half_depth: f32) {
         self.half_depth = half_depth.max(0.0);
     }
+
+    /// Returns volume of the box emitter.
+    pub fn volume(&self) -> f32 {
+        8.0 * self.half_width * self.half_height * self.half_depth
+    }
+
+    /// Returns bounds of the box emitter in local coordinates of the particle system. It includes
+    /// the position of the emitter, but not its orientation, see [`Emitter::emission_bounds`].
+    pub fn shape_bounds(&self) -> AxisAlignedBoundingBox {
+        let half_extents = Vector3::new(self.half_width, self.half_height, self.half_depth);
+        let position = self.position();
+        AxisAlignedBoundingBox::from_min_max(position - half_extents, position + half_extents)
+    }
 }
 
 impl Default for CuboidEmitter {
@@ -97,6 +123,10 @@ impl Default for CuboidEmitter {
 impl Emit for CuboidEmitter {
     fn emit(&self, particle: &mut Particle, rng: &mut ParticleSystemRng) {
         self.emitter.emit(particle, rng);
+        if let Some(position) = self.emitter.sample_node_bounds(rng) {
+            particle.position = position;
+            return;
+        }
         let position = self.position();
         particle.position = Vector3::new(
             position.x + (-self.half_width..self.half_width).random(rng),
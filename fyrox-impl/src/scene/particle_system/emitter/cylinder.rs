@@ -1,17 +1,57 @@
 //! Vertical cylinder emitter.
 
 use crate::{
-    core::{algebra::Vector3, numeric_range::RangeExt, reflect::prelude::*, visitor::prelude::*},
+    core::{
+        algebra::Vector3,
+        math::{aabb::AxisAlignedBoundingBox, curve::Curve},
+        numeric_range::RangeExt,
+        reflect::prelude::*,
+        type_traits::prelude::*,
+        visitor::prelude::*,
+    },
     scene::particle_system::{
         emitter::{
             base::{BaseEmitter, BaseEmitterBuilder},
-            Emit, Emitter,
+            repair_finite, repair_non_negative, Emit, Emitter,
         },
         particle::Particle,
         ParticleSystemRng,
     },
 };
 use std::ops::{Deref, DerefMut};
+use strum_macros::{AsRefStr, EnumString, VariantNames};
+
+/// Defines an axis along which the height of a cylinder emitter goes. Its disk lies in the plane,
+/// that is perpendicular to the axis.
+#[derive(
+    Default,
+    Copy,
+    Clone,
+    PartialOrd,
+    PartialEq,
+    Eq,
+    Ord,
+    Hash,
+    Debug,
+    Visit,
+    Reflect,
+    AsRefStr,
+    EnumString,
+    VariantNames,
+    TypeUuidProvider,
+)]
+#[type_uuid(id = "9d1f4c7b-2e8a-4a3d-b5c6-7e0f1a2b3c4d")]
+#[repr(u32)]
+pub enum CylinderAxis {
+    /// The disk lies in the YZ plane, the height goes along X axis.
+    X = 0,
+    /// The disk lies in the XZ plane, the height goes along Y axis. It is the most natural choice
+    /// for projects with Y-up convention.
+    Y = 1,
+    /// The disk lies in the XY plane, the height goes along Z axis.
+    #[default]
+    Z = 2,
+}
 
 /// See module docs.
 #[derive(Clone, Debug, Visit, PartialEq, Reflect)]
@@ -21,6 +61,22 @@ pub struct CylinderEmitter {
     height: f32,
     #[reflect(min_value = 0.0, step = 0.1)]
     radius: f32,
+    /// Optional curve that defines the radius of the emitter over normalized age of the particle
+    /// system. It overrides static radius, if the particle system node has limited lifetime.
+    #[visit(optional)] // Backward compatibility
+    radius_curve: Option<Curve>,
+    #[visit(optional)] // Backward compatibility
+    axis: CylinderAxis,
+    /// Start angle (in radians) of the arc of the disk of the emitter, in which particles are
+    /// emitted. The angle is measured from the first axis of the disk plane.
+    #[visit(optional)] // Backward compatibility
+    #[reflect(step = 0.1)]
+    start_angle: f32,
+    /// End angle (in radians) of the arc of the disk of the emitter, in which particles are
+    /// emitted. `start_angle = 0` and `end_angle = 2π` (default) gives the full circle.
+    #[visit(optional)] // Backward compatibility
+    #[reflect(step = 0.1)]
+    end_angle: f32,
 }
 
 impl Default for CylinderEmitter {
@@ -29,6 +85,10 @@ impl Default for CylinderEmitter {
             emitter: Default::default(),
             height: 1.0,
             radius: 0.5,
+            radius_curve: None,
+            axis: Default::default(),
+            start_angle: 0.0,
+            end_angle: 2.0 * std::f32::consts::PI,
         }
     }
 }
@@ -49,18 +109,43 @@ impl DerefMut for CylinderEmitter {
 
 impl Emit for CylinderEmitter {
     fn emit(&self, particle: &mut Particle, rng: &mut ParticleSystemRng) {
+        if let Some(position) = self.emitter.sample_node_bounds(rng) {
+            particle.position = position;
+            return;
+        }
         // Disk point picking extended in 3D - http://mathworld.wolfram.com/DiskPointPicking.html
         let scale: f32 = (0.0..1.0).random(rng);
-        let theta = (0.0..2.0 * std::f32::consts::PI).random(rng);
-        let z = (0.0..self.height).random(rng);
-        let radius = scale.sqrt() * self.radius;
-        let x = radius * theta.cos();
-        let y = radius * theta.sin();
-        particle.position = self.position() + Vector3::new(x, y, z);
+        let theta = (self.start_angle..self.end_angle).random(rng);
+        let height = (0.0..self.height).random(rng);
+        let radius = scale.sqrt() * self.current_radius();
+        let a = radius * theta.cos();
+        let b = radius * theta.sin();
+        let offset = match self.axis {
+            CylinderAxis::X => Vector3::new(height, a, b),
+            CylinderAxis::Y => Vector3::new(a, height, b),
+            CylinderAxis::Z => Vector3::new(a, b, height),
+        };
+        particle.position = self.position() + offset;
     }
 }
 
 impl CylinderEmitter {
+    /// Replaces invalid values of the parameters of the emitter with the default ones and adds
+    /// the names of the replaced parameters to the given list. See
+    /// [`Emitter::repair_invalid_parameters`] for more info.
+    pub(crate) fn repair_parameters(&mut self, repaired: &mut Vec<&'static str>) {
+        self.emitter.repair_parameters(repaired);
+        repair_non_negative("height", &mut self.height, 1.0, repaired);
+        repair_non_negative("radius", &mut self.radius, 0.5, repaired);
+        repair_finite("start_angle", &mut self.start_angle, 0.0, repaired);
+        repair_finite(
+            "end_angle",
+            &mut self.end_angle,
+            2.0 * std::f32::consts::PI,
+            repaired,
+        );
+    }
+
     /// Returns radius of the cylinder emitter.
     pub fn radius(&self) -> f32 {
         self.radius
@@ -71,6 +156,83 @@ impl CylinderEmitter {
         self.radius = radius.max(0.0);
     }
 
+    /// Sets new radius curve of the cylinder emitter. See [`Self::current_radius`] for more info.
+    pub fn set_radius_curve(&mut self, radius_curve: Option<Curve>) {
+        self.radius_curve = radius_curve;
+    }
+
+    /// Returns current radius curve of the cylinder emitter.
+    pub fn radius_curve(&self) -> Option<&Curve> {
+        self.radius_curve.as_ref()
+    }
+
+    /// Returns the radius that is used to emit particles at the moment. If there's a radius
+    /// curve, it is sampled at normalized age of the particle system, otherwise the static radius
+    /// is used. The static radius is also used if the particle system has unlimited lifetime.
+    pub fn current_radius(&self) -> f32 {
+        match (
+            self.radius_curve.as_ref(),
+            self.emitter.normalized_system_age(),
+        ) {
+            (Some(radius_curve), Some(age)) => radius_curve.value_at(age).max(0.0),
+            _ => self.radius,
+        }
+    }
+
+    /// Returns volume of the cylinder emitter, it uses current radius (see [`Self::current_radius`])
+    /// and takes the arc of the emitter into account (see [`Self::set_arc`]).
+    pub fn volume(&self) -> f32 {
+        std::f32::consts::PI * self.current_radius().powi(2) * self.height * self.arc_fraction()
+    }
+
+    /// Returns bounds of the cylinder in local coordinates of the particle system, it uses current
+    /// radius (see [`Self::current_radius`]). It includes the position of the emitter, but not its
+    /// orientation, see [`Emitter::emission_bounds`]. The arc of the emitter is not taken into
+    /// account, the bounds always enclose the entire cylinder.
+    pub fn shape_bounds(&self) -> AxisAlignedBoundingBox {
+        let radius = self.current_radius();
+        let (min, max) = match self.axis {
+            CylinderAxis::X => (
+                Vector3::new(0.0, -radius, -radius),
+                Vector3::new(self.height, radius, radius),
+            ),
+            CylinderAxis::Y => (
+                Vector3::new(-radius, 0.0, -radius),
+                Vector3::new(radius, self.height, radius),
+            ),
+            CylinderAxis::Z => (
+                Vector3::new(-radius, -radius, 0.0),
+                Vector3::new(radius, radius, self.height),
+            ),
+        };
+        let position = self.position();
+        AxisAlignedBoundingBox::from_min_max(position + min, position + max)
+    }
+
+    /// Returns a fraction of the full circle, that is covered by the arc of the emitter.
+    fn arc_fraction(&self) -> f32 {
+        ((self.end_angle - self.start_angle).abs() / (2.0 * std::f32::consts::PI)).min(1.0)
+    }
+
+    /// Sets the arc (in radians) of the disk of the emitter, in which particles are emitted. It
+    /// allows you to create fan-like emission shapes. `0..2π` gives the full circle. Non-finite
+    /// angles are replaced with the angles of the full circle.
+    pub fn set_arc(&mut self, start_angle: f32, end_angle: f32) {
+        (self.start_angle, self.end_angle) = sanitize_arc(start_angle, end_angle);
+    }
+
+    /// Returns start angle (in radians) of the arc of the emitter. See [`Self::set_arc`] for more
+    /// info.
+    pub fn start_angle(&self) -> f32 {
+        self.start_angle
+    }
+
+    /// Returns end angle (in radians) of the arc of the emitter. See [`Self::set_arc`] for more
+    /// info.
+    pub fn end_angle(&self) -> f32 {
+        self.end_angle
+    }
+
     /// Returns height of the cylinder emitter.
     pub fn height(&self) -> f32 {
         self.height
@@ -80,6 +242,24 @@ impl CylinderEmitter {
     pub fn set_height(&mut self, height: f32) {
         self.height = height.max(0.0);
     }
+
+    /// Returns the axis along which the height of the cylinder emitter goes.
+    pub fn axis(&self) -> CylinderAxis {
+        self.axis
+    }
+
+    /// Sets the axis along which the height of the cylinder emitter goes.
+    pub fn set_axis(&mut self, axis: CylinderAxis) {
+        self.axis = axis;
+    }
+}
+
+fn sanitize_arc(start_angle: f32, end_angle: f32) -> (f32, f32) {
+    let finite_or = |angle: f32, default: f32| if angle.is_finite() { angle } else { default };
+    (
+        finite_or(start_angle, 0.0),
+        finite_or(end_angle, 2.0 * std::f32::consts::PI),
+    )
 }
 
 /// Box emitter builder allows you to construct cylinder emitter in declarative manner.
@@ -88,6 +268,10 @@ pub struct CylinderEmitterBuilder {
     base: BaseEmitterBuilder,
     height: f32,
     radius: f32,
+    radius_curve: Option<Curve>,
+    axis: CylinderAxis,
+    start_angle: f32,
+    end_angle: f32,
 }
 
 impl CylinderEmitterBuilder {
@@ -97,6 +281,10 @@ impl CylinderEmitterBuilder {
             base,
             height: 1.0,
             radius: 0.5,
+            radius_curve: None,
+            axis: Default::default(),
+            start_angle: 0.0,
+            end_angle: 2.0 * std::f32::consts::PI,
         }
     }
 
@@ -112,12 +300,35 @@ impl CylinderEmitterBuilder {
         self
     }
 
+    /// Sets desired radius curve of the emitter.
+    pub fn with_radius_curve(mut self, radius_curve: Curve) -> Self {
+        self.radius_curve = Some(radius_curve);
+        self
+    }
+
+    /// Sets desired axis of the emitter.
+    pub fn with_axis(mut self, axis: CylinderAxis) -> Self {
+        self.axis = axis;
+        self
+    }
+
+    /// Sets desired arc (in radians) of the emitter. See [`CylinderEmitter::set_arc`] for more
+    /// info.
+    pub fn with_arc(mut self, start_angle: f32, end_angle: f32) -> Self {
+        (self.start_angle, self.end_angle) = sanitize_arc(start_angle, end_angle);
+        self
+    }
+
     /// Creates new cylinder emitter with given parameters.
     pub fn build(self) -> Emitter {
         Emitter::Cylinder(CylinderEmitter {
             emitter: self.base.build(),
             height: self.height,
             radius: self.radius,
+            radius_curve: self.radius_curve,
+            axis: self.axis,
+            start_angle: self.start_angle,
+            end_angle: self.end_angle,
         })
     }
 }
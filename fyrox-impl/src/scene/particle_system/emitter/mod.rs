@@ -2,11 +2,14 @@
 //! use BaseEmitter which contains base functionality.
 
 use crate::{
-    core::{reflect::prelude::*, visitor::prelude::*},
+    core::{
+        algebra::Vector3, math::aabb::AxisAlignedBoundingBox, reflect::prelude::*,
+        visitor::prelude::*,
+    },
     scene::particle_system::{
         emitter::{
             base::BaseEmitter, cuboid::CuboidEmitter, cylinder::CylinderEmitter,
-            sphere::SphereEmitter,
+            sphere::SphereEmitter, torus::TorusEmitter,
         },
         Particle, ParticleSystemRng,
     },
@@ -19,6 +22,7 @@ pub mod base;
 pub mod cuboid;
 pub mod cylinder;
 pub mod sphere;
+pub mod torus;
 
 /// Emit trait must be implemented for any particle system emitter.
 pub trait Emit {
@@ -35,10 +39,37 @@ pub enum Emitter {
     Sphere(SphereEmitter),
     /// Cylinder emitter.
     Cylinder(CylinderEmitter),
+    /// See TorusEmitter docs.
+    Torus(TorusEmitter),
 }
 
 uuid_provider!(Emitter = "4cad87ed-6b2c-411d-8c05-86dc26e463b2");
 
+/// Kind of an emitter, it is a data-less discriminant of [`Emitter`] variants. It could be used to
+/// show the shape of an emitter in a user interface without matching on the emitter itself.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash, AsRefStr)]
+pub enum EmitterKind {
+    /// [`Emitter::Cuboid`]
+    Cuboid,
+    /// [`Emitter::Sphere`]
+    Sphere,
+    /// [`Emitter::Cylinder`]
+    Cylinder,
+    /// [`Emitter::Torus`]
+    Torus,
+}
+
+macro_rules! static_dispatch {
+    ($self:ident, $func:ident, $($args:expr),*) => {
+        match $self {
+            Emitter::Cuboid(v) => v.$func($($args),*),
+            Emitter::Sphere(v) => v.$func($($args),*),
+            Emitter::Cylinder(v) => v.$func($($args),*),
+            Emitter::Torus(v) => v.$func($($args),*),
+        }
+    };
+}
+
 impl Emitter {
     /// Creates new emitter from given id.
     pub fn new(id: i32) -> Result<Self, String> {
@@ -46,28 +77,105 @@ impl Emitter {
             1 => Ok(Self::Cuboid(Default::default())),
             2 => Ok(Self::Sphere(Default::default())),
             3 => Ok(Self::Cylinder(Default::default())),
+            4 => Ok(Self::Torus(Default::default())),
             _ => Err(format!("Invalid emitter id {}!", id)),
         }
     }
 
+    /// Returns volume of the space in which the emitter spawns particles. It is either the volume
+    /// of the shape of the emitter or the volume of the bounds of the parent mesh, depending on the
+    /// emission volume (see [`BaseEmitter::node_bounds_volume`]).
+    pub fn volume(&self) -> f32 {
+        self.node_bounds_volume()
+            .unwrap_or_else(|| static_dispatch!(self, volume,))
+    }
+
+    /// Returns bounds of the space in which the emitter spawns particles, in local coordinates of
+    /// the particle system. It includes the position and the orientation of the emitter. If the
+    /// emitter spawns particles in the bounds of the parent mesh (see [`BaseEmitter::node_bounds_volume`]),
+    /// then these bounds are returned. The bounds do not include spread of the particles after
+    /// they were emitted.
+    pub fn emission_bounds(&self) -> AxisAlignedBoundingBox {
+        let shape_bounds = static_dispatch!(self, shape_bounds,);
+        self.oriented_bounds(shape_bounds)
+    }
+
+    /// Emits `count` particles using a random number generator with the given seed and returns
+    /// their positions in local coordinates of the particle system, including the orientation of
+    /// the emitter. It is a debugging tool, that helps to find mistakes in the shape, axes or
+    /// position of the emitter without rendering anything. The same seed always gives the same
+    /// positions. The state of the emitter is not changed.
+    pub fn sample_positions(&self, count: usize, seed: u64) -> Vec<Vector3<f32>> {
+        let mut rng = ParticleSystemRng::new(seed);
+        (0..count)
+            .map(|_| {
+                let mut particle = Particle::default();
+                self.emit(&mut particle, &mut rng);
+                self.apply_orientation(&mut particle);
+                particle.position
+            })
+            .collect()
+    }
+
+    /// Replaces invalid values of the numeric parameters of the emitter (non-finite numbers,
+    /// negative sizes of the shape, etc.) with the default ones and returns the names of the
+    /// replaced parameters. Such values could come from a bad edit or corrupted data and they
+    /// produce invisible or exploding effects. The setters of the emitters already clamp the
+    /// values, this method is for the data that bypasses them. It is called automatically when a
+    /// particle system is loaded.
+    pub fn repair_invalid_parameters(&mut self) -> Vec<&'static str> {
+        let mut repaired = Vec::new();
+        static_dispatch!(self, repair_parameters, &mut repaired);
+        repaired
+    }
+
+    /// Returns the kind of the emitter.
+    pub fn kind(&self) -> EmitterKind {
+        match self {
+            Self::Cuboid(_) => EmitterKind::Cuboid,
+            Self::Sphere(_) => EmitterKind::Sphere,
+            Self::Cylinder(_) => EmitterKind::Cylinder,
+            Self::Torus(_) => EmitterKind::Torus,
+        }
+    }
+
     /// Returns id of current emitter kind.
     pub fn id(&self) -> i32 {
         match self {
             Self::Cuboid(_) => 1,
             Self::Sphere(_) => 2,
             Self::Cylinder(_) => 3,
+            Self::Torus(_) => 4,
         }
     }
 }
 
-macro_rules! static_dispatch {
-    ($self:ident, $func:ident, $($args:expr),*) => {
-        match $self {
-            Emitter::Cuboid(v) => v.$func($($args),*),
-            Emitter::Sphere(v) => v.$func($($args),*),
-            Emitter::Cylinder(v) => v.$func($($args),*),
-        }
-    };
+/// Replaces the value with the default one, if it is non-finite or negative, and adds the name of
+/// the parameter to the list of repaired parameters.
+pub(crate) fn repair_non_negative(
+    name: &'static str,
+    value: &mut f32,
+    default: f32,
+    repaired: &mut Vec<&'static str>,
+) {
+    if !(value.is_finite() && *value >= 0.0) {
+        *value = default;
+        repaired.push(name);
+    }
+}
+
+/// Replaces the value with the default one, if it is non-finite, and adds the name of the parameter
+/// to the list of repaired parameters.
+pub(crate) fn repair_finite(
+    name: &'static str,
+    value: &mut f32,
+    default: f32,
+    repaired: &mut Vec<&'static str>,
+) {
+    if !value.is_finite() {
+        *value = default;
+        repaired.push(name);
+    }
 }
 
 impl Visit for Emitter {
@@ -94,6 +202,7 @@ impl Clone for Emitter {
             Self::Cuboid(box_emitter) => Self::Cuboid(box_emitter.clone()),
             Self::Sphere(sphere_emitter) => Self::Sphere(sphere_emitter.clone()),
             Self::Cylinder(cylinder) => Self::Cylinder(cylinder.clone()),
+            Self::Torus(torus) => Self::Torus(torus.clone()),
         }
     }
 }
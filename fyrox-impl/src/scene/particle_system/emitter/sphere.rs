@@ -2,11 +2,14 @@
 //! radius = 0, then it represents point emitter.
 
 use crate::{
-    core::{algebra::Vector3, numeric_range::RangeExt, reflect::prelude::*, visitor::prelude::*},
+    core::{
+        algebra::Vector3, math::aabb::AxisAlignedBoundingBox, numeric_range::RangeExt,
+        reflect::prelude::*, visitor::prelude::*,
+    },
     scene::particle_system::{
         emitter::{
             base::{BaseEmitter, BaseEmitterBuilder},
-            Emit, Emitter,
+            repair_non_negative, Emit, Emitter,
         },
         particle::Particle,
         ParticleSystemRng,
@@ -46,6 +49,14 @@ impl Default for SphereEmitter {
 }
 
 impl SphereEmitter {
+    /// Replaces invalid values of the parameters of the emitter with the default ones and adds
+    /// the names of the replaced parameters to the given list. See
+    /// [`Emitter::repair_invalid_parameters`] for more info.
+    pub(crate) fn repair_parameters(&mut self, repaired: &mut Vec<&'static str>) {
+        self.emitter.repair_parameters(repaired);
+        repair_non_negative("radius", &mut self.radius, 0.5, repaired);
+    }
+
     /// Creates new sphere emitter with given radius.
     pub fn new(emitter: BaseEmitter, radius: f32) -> Self {
         Self { emitter, radius }
@@ -60,11 +71,28 @@ impl SphereEmitter {
     pub fn set_radius(&mut self, radius: f32) {
         self.radius = radius.max(0.0);
     }
+
+    /// Returns volume of the sphere.
+    pub fn volume(&self) -> f32 {
+        4.0 / 3.0 * std::f32::consts::PI * self.radius.powi(3)
+    }
+
+    /// Returns bounds of the sphere in local coordinates of the particle system. It includes the
+    /// position of the emitter, but not its orientation, see [`Emitter::emission_bounds`].
+    pub fn shape_bounds(&self) -> AxisAlignedBoundingBox {
+        let mut bounds = AxisAlignedBoundingBox::from_radius(self.radius);
+        bounds.offset(self.position());
+        bounds
+    }
 }
 
 impl Emit for SphereEmitter {
     fn emit(&self, particle: &mut Particle, rng: &mut ParticleSystemRng) {
         self.emitter.emit(particle, rng);
+        if let Some(position) = self.emitter.sample_node_bounds(rng) {
+            particle.position = position;
+            return;
+        }
         let phi = (0.0..std::f32::consts::PI).random(rng);
         let theta = (0.0..2.0 * std::f32::consts::PI).random(rng);
         let radius = (0.0..self.radius).random(rng);
@@ -0,0 +1,224 @@
+//! Torus emitter uniformly places particles in a torus volume or on its surface. The torus lies in
+//! XZ plane, its symmetry axis is Y. Could be used for halos and ring bursts.
+
+use crate::{
+    core::{
+        algebra::Vector3, math::aabb::AxisAlignedBoundingBox, numeric_range::RangeExt,
+        reflect::prelude::*, visitor::prelude::*,
+    },
+    scene::particle_system::{
+        emitter::{
+            base::{BaseEmitter, BaseEmitterBuilder},
+            repair_non_negative, Emit, Emitter,
+        },
+        particle::Particle,
+        ParticleSystemRng,
+    },
+};
+use std::ops::{Deref, DerefMut};
+
+/// Maximum amount of rejection sampling attempts per particle. Acceptance probability is always at least
+/// `(major - minor) / (major + minor)`, so this limit is reached only for degenerate tori.
+const MAX_SAMPLING_ATTEMPTS: usize = 32;
+
+/// See module docs.
+#[derive(Debug, Clone, Visit, PartialEq, Reflect)]
+pub struct TorusEmitter {
+    emitter: BaseEmitter,
+    #[reflect(min_value = 0.0, step = 0.1)]
+    major_radius: f32,
+    #[reflect(min_value = 0.0, step = 0.1)]
+    minor_radius: f32,
+    surface_only: bool,
+}
+
+impl Deref for TorusEmitter {
+    type Target = BaseEmitter;
+
+    fn deref(&self) -> &Self::Target {
+        &self.emitter
+    }
+}
+
+impl DerefMut for TorusEmitter {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        &mut self.emitter
+    }
+}
+
+impl Default for TorusEmitter {
+    fn default() -> Self {
+        Self {
+            emitter: Default::default(),
+            major_radius: 1.0,
+            minor_radius: 0.25,
+            surface_only: false,
+        }
+    }
+}
+
+impl TorusEmitter {
+    /// Replaces invalid values of the parameters of the emitter with the default ones and adds
+    /// the names of the replaced parameters to the given list. See
+    /// [`Emitter::repair_invalid_parameters`] for more info.
+    pub(crate) fn repair_parameters(&mut self, repaired: &mut Vec<&'static str>) {
+        self.emitter.repair_parameters(repaired);
+        repair_non_negative("major_radius", &mut self.major_radius, 1.0, repaired);
+        repair_non_negative("minor_radius", &mut self.minor_radius, 0.25, repaired);
+    }
+
+    /// Creates new torus emitter with given radii.
+    pub fn new(emitter: BaseEmitter, major_radius: f32, minor_radius: f32) -> Self {
+        Self {
+            emitter,
+            major_radius: major_radius.max(0.0),
+            minor_radius: minor_radius.max(0.0),
+            surface_only: false,
+        }
+    }
+
+    /// Returns distance from the center of the torus to the center of its tube.
+    pub fn major_radius(&self) -> f32 {
+        self.major_radius
+    }
+
+    /// Sets distance from the center of the torus to the center of its tube.
+    pub fn set_major_radius(&mut self, major_radius: f32) {
+        self.major_radius = major_radius.max(0.0);
+    }
+
+    /// Returns radius of the tube of the torus.
+    pub fn minor_radius(&self) -> f32 {
+        self.minor_radius
+    }
+
+    /// Sets radius of the tube of the torus. It is clamped to major radius when emitting, to prevent
+    /// self-intersecting tori.
+    pub fn set_minor_radius(&mut self, minor_radius: f32) {
+        self.minor_radius = minor_radius.max(0.0);
+    }
+
+    /// Returns volume of the torus. Minor radius is clamped to major radius, the same way as it is
+    /// done when emitting.
+    pub fn volume(&self) -> f32 {
+        let minor = self.minor_radius.min(self.major_radius);
+        2.0 * std::f32::consts::PI.powi(2) * self.major_radius * minor * minor
+    }
+
+    /// Returns bounds of the torus in local coordinates of the particle system. It includes the
+    /// position of the emitter, but not its orientation, see [`Emitter::emission_bounds`].
+    pub fn shape_bounds(&self) -> AxisAlignedBoundingBox {
+        let minor = self.minor_radius.min(self.major_radius);
+        let extent = self.major_radius + minor;
+        let half_extents = Vector3::new(extent, minor, extent);
+        let position = self.position();
+        AxisAlignedBoundingBox::from_min_max(position - half_extents, position + half_extents)
+    }
+
+    /// Returns true if particles are placed only on the surface of the torus.
+    pub fn is_surface_only(&self) -> bool {
+        self.surface_only
+    }
+
+    /// Defines whether particles should be placed only on the surface of the torus or in its volume.
+    pub fn set_surface_only(&mut self, surface_only: bool) {
+        self.surface_only = surface_only;
+    }
+
+    /// Samples a point in the cross-section of the tube, returns `(distance from tube center, angle)` pair.
+    /// Outer parts of the torus are longer than inner parts (circumference at the distance `major + d * cos(angle)`
+    /// from the symmetry axis is proportional to that distance), so the sample is accepted with the probability
+    /// proportional to the distance from the axis.
+    fn sample_cross_section(&self, rng: &mut ParticleSystemRng) -> (f32, f32) {
+        let major = self.major_radius;
+        let minor = self.minor_radius.min(major);
+        let max_distance = major + minor;
+        let mut sample = (0.0, 0.0);
+        for _ in 0..MAX_SAMPLING_ATTEMPTS {
+            let distance = if self.surface_only {
+                minor
+            } else {
+                // Uniform sampling of a disk.
+                minor * (0.0f32..1.0).random(rng).sqrt()
+            };
+            let angle = (0.0..2.0 * std::f32::consts::PI).random(rng);
+            sample = (distance, angle);
+            if (0.0..max_distance).random(rng) <= major + distance * angle.cos() {
+                break;
+            }
+        }
+        sample
+    }
+}
+
+impl Emit for TorusEmitter {
+    fn emit(&self, particle: &mut Particle, rng: &mut ParticleSystemRng) {
+        self.emitter.emit(particle, rng);
+        if let Some(position) = self.emitter.sample_node_bounds(rng) {
+            particle.position = position;
+            return;
+        }
+        if self.major_radius <= 0.0 {
+            particle.position = self.position();
+            return;
+        }
+        let (distance, tube_angle) = self.sample_cross_section(rng);
+        let ring_angle = (0.0..2.0 * std::f32::consts::PI).random(rng);
+        let axis_distance = self.major_radius + distance * tube_angle.cos();
+        particle.position = self.position()
+            + Vector3::new(
+                axis_distance * ring_angle.cos(),
+                distance * tube_angle.sin(),
+                axis_distance * ring_angle.sin(),
+            );
+    }
+}
+
+/// Torus emitter builder allows you to construct torus emitter in declarative manner.
+/// This is typical implementation of Builder pattern.
+pub struct TorusEmitterBuilder {
+    base: BaseEmitterBuilder,
+    major_radius: f32,
+    minor_radius: f32,
+    surface_only: bool,
+}
+
+impl TorusEmitterBuilder {
+    /// Creates new torus emitter builder with 1.0 major radius and 0.25 minor radius.
+    pub fn new(base: BaseEmitterBuilder) -> Self {
+        Self {
+            base,
+            major_radius: 1.0,
+            minor_radius: 0.25,
+            surface_only: false,
+        }
+    }
+
+    /// Sets desired distance from the center of the torus to the center of its tube.
+    pub fn with_major_radius(mut self, major_radius: f32) -> Self {
+        self.major_radius = major_radius;
+        self
+    }
+
+    /// Sets desired radius of the tube of the torus.
+    pub fn with_minor_radius(mut self, minor_radius: f32) -> Self {
+        self.minor_radius = minor_radius;
+        self
+    }
+
+    /// Defines whether particles should be placed only on the surface of the torus.
+    pub fn with_surface_only(mut self, surface_only: bool) -> Self {
+        self.surface_only = surface_only;
+        self
+    }
+
+    /// Creates new torus emitter.
+    pub fn build(self) -> Emitter {
+        Emitter::Torus(TorusEmitter {
+            emitter: self.base.build(),
+            major_radius: self.major_radius.max(0.0),
+            minor_radius: self.minor_radius.max(0.0),
+            surface_only: self.surface_only,
+        })
+    }
+}
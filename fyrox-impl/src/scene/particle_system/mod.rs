@@ -5,10 +5,10 @@ use crate::scene::mesh::buffer::VertexTrait;
 use crate::scene::node::RdcControlFlow;
 use crate::{
     core::{
-        algebra::{Point3, Vector2, Vector3},
+        algebra::{Matrix4, Point3, Vector2, Vector3, Vector4},
         color_gradient::ColorGradient,
         log::Log,
-        math::{aabb::AxisAlignedBoundingBox, TriangleDefinition},
+        math::{aabb::AxisAlignedBoundingBox, Rect, TriangleDefinition},
         pool::Handle,
         reflect::prelude::*,
         sstorage::ImmutableString,
@@ -18,17 +18,18 @@ use crate::{
         TypeUuidProvider,
     },
     material::{self, Material, MaterialResource, PropertyValue},
-    rand::{prelude::StdRng, Error, RngCore, SeedableRng},
+    rand::{prelude::StdRng, Error, Rng, RngCore, SeedableRng},
     renderer::{self, bundle::RenderContext},
     scene::{
         base::{Base, BaseBuilder},
         graph::Graph,
-        mesh::RenderPath,
+        mesh::{Mesh, RenderPath},
         node::{Node, NodeTrait, UpdateContext},
         particle_system::{
             draw::Vertex,
-            emitter::{Emit, Emitter},
+            emitter::{base::EmissionVolume, Emit, Emitter, EmitterKind},
             particle::Particle,
+            turbulence::ParticleTurbulence,
         },
     },
 };
@@ -39,18 +40,58 @@ use std::{
     fmt::Debug,
     ops::{Deref, DerefMut},
 };
+use strum_macros::{AsRefStr, EnumString, VariantNames};
 
 pub(crate) mod draw;
 pub mod emitter;
 pub mod particle;
+pub mod turbulence;
+
+/// A seedable source of pseudo-random numbers, that could be plugged into [`ParticleSystemRng`]
+/// to use a different algorithm or to control the randomness of a particle system precisely (for
+/// example in tests or networked simulations). It is implemented for every seedable generator
+/// from the `rand` crate, so [`ParticleSystemRng::with_source`] could be used with
+/// `SmallRng`, `StdRng`, etc. as is.
+pub trait ParticleRngSource: RngCore + Debug + Send + 'static {
+    /// Re-initializes the state of the generator using the given seed. The same seed must always
+    /// produce the same sequence of numbers.
+    fn reseed(&mut self, seed: u64);
+
+    /// Creates a boxed copy of the generator with its current state.
+    fn clone_box(&self) -> Box<dyn ParticleRngSource>;
+}
 
-/// Pseudo-random numbers generator for particle systems.
-#[derive(Debug, Clone, Reflect)]
+impl<T> ParticleRngSource for T
+where
+    T: RngCore + SeedableRng + Clone + Debug + Send + 'static,
+{
+    fn reseed(&mut self, seed: u64) {
+        *self = T::seed_from_u64(seed);
+    }
+
+    fn clone_box(&self) -> Box<dyn ParticleRngSource> {
+        Box::new(self.clone())
+    }
+}
+
+/// Pseudo-random numbers generator for particle systems. It uses [`StdRng`] by default, other
+/// algorithms could be plugged in using [`ParticleRngSource`] trait. Only the seed is serialized,
+/// a loaded generator always uses the default algorithm.
+#[derive(Debug, Reflect)]
 pub struct ParticleSystemRng {
     rng_seed: u64,
 
     #[reflect(hidden)]
-    rng: StdRng,
+    rng: Box<dyn ParticleRngSource>,
+}
+
+impl Clone for ParticleSystemRng {
+    fn clone(&self) -> Self {
+        Self {
+            rng_seed: self.rng_seed,
+            rng: self.rng.clone_box(),
+        }
+    }
 }
 
 impl Default for ParticleSystemRng {
@@ -63,16 +104,35 @@ impl ParticleSystemRng {
     /// Creates new PRNG with a given seed. Fixed seed guarantees that particle system's behaviour will be
     /// deterministic.
     pub fn new(seed: u64) -> Self {
-        Self {
+        Self::with_source(seed, StdRng::seed_from_u64(seed))
+    }
+
+    /// Creates new PRNG with a given seed, that uses the given source of random numbers. The source
+    /// is re-seeded with the seed, so its initial state does not matter.
+    pub fn with_source<R: ParticleRngSource>(seed: u64, source: R) -> Self {
+        let mut rng = Self {
             rng_seed: seed,
-            rng: StdRng::seed_from_u64(seed),
-        }
+            rng: Box::new(source),
+        };
+        rng.reset();
+        rng
+    }
+
+    /// Replaces the source of random numbers and re-seeds it with the current seed.
+    pub fn set_source<R: ParticleRngSource>(&mut self, source: R) {
+        self.rng = Box::new(source);
+        self.reset();
+    }
+
+    /// Returns the seed of PRNG.
+    pub fn seed(&self) -> u64 {
+        self.rng_seed
     }
 
     /// Resets the state of PRNG.
     #[inline]
     pub fn reset(&mut self) {
-        self.rng = StdRng::seed_from_u64(self.rng_seed);
+        self.rng.reseed(self.rng_seed);
     }
 }
 
@@ -106,13 +166,571 @@ impl Visit for ParticleSystemRng {
 
         // Re-initialize the RNG to keep determinism.
         if guard.is_reading() {
-            self.rng = StdRng::seed_from_u64(self.rng_seed);
+            self.reset();
         }
 
         Ok(())
     }
 }
 
+/// Statistics of the particle pool of a particle system. See
+/// [`ParticleSystem::set_particle_capacity`] for more info.
+#[derive(Default, Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ParticlePoolStats {
+    /// The maximum amount of particles that were alive at the same time.
+    pub high_water_mark: usize,
+    /// Amount of particles that were emitted into slots of dead particles.
+    pub recycled: u64,
+    /// Amount of particles that were not emitted, because the pool was full.
+    pub dropped: u64,
+}
+
+/// Defines how particles are oriented when rendered.
+#[derive(
+    Default,
+    Copy,
+    Clone,
+    PartialOrd,
+    PartialEq,
+    Eq,
+    Ord,
+    Hash,
+    Debug,
+    Visit,
+    Reflect,
+    AsRefStr,
+    EnumString,
+    VariantNames,
+    TypeUuidProvider,
+)]
+#[type_uuid(id = "b4e2a7c9-1d3f-4e6a-9b8c-5f0d2e7a1c36")]
+#[repr(u32)]
+pub enum ParticleOrientationMode {
+    /// Particles are screen-aligned quads (billboards), that always face the camera.
+    #[default]
+    Billboard = 0,
+    /// Particles are aligned along their velocity and rotated around it to face the camera as much
+    /// as possible. It is useful for streaks, sparks, rain, etc. Particles without velocity are
+    /// rendered as billboards.
+    VelocityAligned = 1,
+    /// Particles are aligned along the local Y axis of the particle system and rotated around it
+    /// to face the camera as much as possible. It is useful for grass, flames, etc.
+    AxisAligned = 2,
+}
+
+/// Defines a coordinate space in which particles are simulated.
+#[derive(
+    Default,
+    Copy,
+    Clone,
+    PartialOrd,
+    PartialEq,
+    Eq,
+    Ord,
+    Hash,
+    Debug,
+    Visit,
+    Reflect,
+    AsRefStr,
+    EnumString,
+    VariantNames,
+    TypeUuidProvider,
+)]
+#[type_uuid(id = "0f5d8e3a-7c21-4b9e-a6d4-3e8b5c1f2a97")]
+#[repr(u32)]
+pub enum SimulationSpace {
+    /// Particles are simulated in local coordinates of the particle system, which means that they
+    /// will follow the particle system when it moves.
+    #[default]
+    Local = 0,
+    /// Particles are transformed to world coordinates when emitted, which means that they won't
+    /// follow the particle system when it moves. It is useful for trails, smoke, etc.
+    World = 1,
+}
+
+/// Defines the order in which particles are rendered. Translucent particles are blended correctly
+/// only if they are rendered in back-to-front order.
+#[derive(
+    Default,
+    Copy,
+    Clone,
+    PartialOrd,
+    PartialEq,
+    Eq,
+    Ord,
+    Hash,
+    Debug,
+    Visit,
+    Reflect,
+    AsRefStr,
+    EnumString,
+    VariantNames,
+    TypeUuidProvider,
+)]
+#[type_uuid(id = "d83f2b6e-4a1c-4e97-b5d0-2c7e9a3f6b41")]
+#[repr(u32)]
+pub enum ParticleSortMode {
+    /// Particles are rendered in the order of their slots in the particle pool. It is the fastest
+    /// mode, that could be used for opaque or additive particles, that do not depend on the order.
+    None = 0,
+    /// Particles are sorted by the distance to the camera every frame, the farthest particles are
+    /// rendered first. It gives correct blending of overlapping translucent particles.
+    #[default]
+    BackToFront = 1,
+    /// Particles are sorted by their age, the oldest particles are rendered first, so new particles
+    /// are always on top of old ones. It is stable when the camera moves, which is useful for
+    /// smoke trails.
+    OldestFirst = 2,
+}
+
+/// Defines what happens with a particle, that crosses a collision plane of a particle system.
+#[derive(
+    Default,
+    Copy,
+    Clone,
+    PartialOrd,
+    PartialEq,
+    Eq,
+    Ord,
+    Hash,
+    Debug,
+    Visit,
+    Reflect,
+    AsRefStr,
+    EnumString,
+    VariantNames,
+    TypeUuidProvider,
+)]
+#[type_uuid(id = "5e9a3c71-8b2d-4f06-a1e4-7c3d9b5f2e18")]
+#[repr(u32)]
+pub enum ParticleCollisionResponse {
+    /// The particle is removed.
+    #[default]
+    Kill = 0,
+    /// The particle is pushed back and bounces off the plane, see
+    /// [`ParticleCollisionPlane::restitution`] and [`ParticleCollisionPlane::friction`].
+    Bounce = 1,
+}
+
+/// An infinite plane, that particles of a particle system cannot cross. It is a lightweight
+/// alternative to physics for the common case of flat ground. See
+/// [`ParticleSystem::set_collision_plane`] for more info.
+#[derive(Debug, Clone, PartialEq, Visit, Reflect, TypeUuidProvider)]
+#[type_uuid(id = "c5c1020c-d774-44ac-9bbc-8b01ae62bf27")]
+pub struct ParticleCollisionPlane {
+    /// A point on the plane in world coordinates.
+    pub point: Vector3<f32>,
+    /// Normal of the plane in world coordinates. Particles can live only on the side of the plane,
+    /// that the normal points to.
+    pub normal: Vector3<f32>,
+    /// Defines what happens with a particle, that crosses the plane.
+    pub response: ParticleCollisionResponse,
+    /// Fraction of the velocity of a particle along the normal of the plane, that is kept after a
+    /// bounce. `0.0` - the particle slides over the plane, `1.0` - perfectly elastic bounce. Used
+    /// only with [`ParticleCollisionResponse::Bounce`] response.
+    #[reflect(min_value = 0.0, max_value = 1.0, step = 0.05)]
+    pub restitution: f32,
+    /// Fraction of the velocity of a particle along the plane, that is lost on each bounce. `0.0` -
+    /// the particle keeps sliding without any friction, `1.0` - the particle stops sliding after
+    /// the first bounce. Used only with [`ParticleCollisionResponse::Bounce`] response.
+    #[visit(optional)] // Backward compatibility
+    #[reflect(min_value = 0.0, max_value = 1.0, step = 0.05)]
+    pub friction: f32,
+}
+
+impl Default for ParticleCollisionPlane {
+    fn default() -> Self {
+        Self {
+            point: Vector3::default(),
+            normal: Vector3::y(),
+            response: Default::default(),
+            restitution: 0.5,
+            friction: 0.0,
+        }
+    }
+}
+
+impl ParticleCollisionPlane {
+    /// Transforms the plane by the inverse of the given transform. Returns [`None`] if the transform
+    /// is degenerate or the normal is zero.
+    fn inverse_transform(&self, transform: &Matrix4<f32>) -> Option<Self> {
+        let inv_transform = transform.try_inverse()?;
+        // Normals are transformed by the inverse transpose matrix, which is the transpose of the
+        // initial one in this case.
+        let normal = (transform.fixed_view::<3, 3>(0, 0).transpose() * self.normal)
+            .try_normalize(f32::EPSILON)?;
+        Some(Self {
+            point: inv_transform
+                .transform_point(&Point3::from(self.point))
+                .coords,
+            normal,
+            ..self.clone()
+        })
+    }
+
+    /// Returns the point on the plane, where the particle crossed it, or [`None`] if the particle is
+    /// on the positive side of the plane.
+    fn contact_point(&self, particle: &Particle) -> Option<Vector3<f32>> {
+        let distance = (particle.position - self.point).dot(&self.normal);
+        (distance < 0.0).then(|| particle.position - self.normal.scale(distance))
+    }
+
+    /// Checks whether the particle crossed the plane and bounces it off the plane if needed.
+    /// Returns `true` if the particle must be killed.
+    fn collide(&self, particle: &mut Particle) -> bool {
+        let distance = (particle.position - self.point).dot(&self.normal);
+        if distance >= 0.0 {
+            return false;
+        }
+
+        match self.response {
+            ParticleCollisionResponse::Kill => true,
+            ParticleCollisionResponse::Bounce => {
+                let restitution = self.restitution.clamp(0.0, 1.0);
+                particle.position -= self.normal.scale((1.0 + restitution) * distance);
+                let normal_velocity = particle.velocity.dot(&self.normal);
+                if normal_velocity < 0.0 {
+                    let friction = self.friction.clamp(0.0, 1.0);
+                    let tangential_velocity =
+                        particle.velocity - self.normal.scale(normal_velocity);
+                    particle.velocity -= self.normal.scale((1.0 + restitution) * normal_velocity)
+                        + tangential_velocity.scale(friction);
+                }
+                false
+            }
+        }
+    }
+}
+
+/// Defines what happens with a particle, that leaves the confinement box of a particle system.
+#[derive(
+    Default,
+    Copy,
+    Clone,
+    PartialOrd,
+    PartialEq,
+    Eq,
+    Ord,
+    Hash,
+    Debug,
+    Visit,
+    Reflect,
+    AsRefStr,
+    EnumString,
+    VariantNames,
+    TypeUuidProvider,
+)]
+#[type_uuid(id = "2076fdfd-a1b3-4e6b-9e10-9cfe75f360cf")]
+#[repr(u32)]
+pub enum ParticleConfinementResponse {
+    /// The particle is moved back to the nearest point of the box and its velocity towards the
+    /// outside of the box is removed, so the particle slides along the walls of the box.
+    #[default]
+    Clamp = 0,
+    /// The particle is mirrored back into the box and its velocity towards the outside of the box
+    /// is reversed, so the particle bounces off the walls of the box.
+    Reflect = 1,
+    /// The particle is removed.
+    Kill = 2,
+}
+
+/// An axis-aligned box, that particles of a particle system cannot leave. It could be used to keep
+/// contained effects (for example, fog in a room) from leaking through walls. See
+/// [`ParticleSystem::set_confinement`] for more info.
+#[derive(Debug, Clone, PartialEq, Visit, Reflect, TypeUuidProvider)]
+#[type_uuid(id = "5e9bad4d-7f0d-4baf-bbe6-aa8d4602f915")]
+pub struct ParticleConfinement {
+    /// Minimum corner of the box in local coordinates of the particle system.
+    pub min: Vector3<f32>,
+    /// Maximum corner of the box in local coordinates of the particle system.
+    pub max: Vector3<f32>,
+    /// Defines what happens with a particle, that leaves the box.
+    pub response: ParticleConfinementResponse,
+}
+
+impl Default for ParticleConfinement {
+    fn default() -> Self {
+        Self {
+            min: Vector3::repeat(-1.0),
+            max: Vector3::repeat(1.0),
+            response: Default::default(),
+        }
+    }
+}
+
+impl ParticleConfinement {
+    /// Checks whether the particle left the box and moves it back if needed. `to_local` transforms
+    /// the particle from the simulation space to the local space of the particle system,
+    /// `to_simulation` does the opposite. Returns `true` if the particle must be killed.
+    fn confine(
+        &self,
+        particle: &mut Particle,
+        to_local: &Matrix4<f32>,
+        to_simulation: &Matrix4<f32>,
+    ) -> bool {
+        let min = self.min.inf(&self.max);
+        let max = self.min.sup(&self.max);
+        let mut position = to_local
+            .transform_point(&Point3::from(particle.position))
+            .coords;
+        if (0..3).all(|i| position[i] >= min[i] && position[i] <= max[i]) {
+            return false;
+        }
+
+        let mut velocity = to_local.transform_vector(&particle.velocity);
+        for i in 0..3 {
+            let (bound, outward) = if position[i] < min[i] {
+                (min[i], -1.0)
+            } else if position[i] > max[i] {
+                (max[i], 1.0)
+            } else {
+                continue;
+            };
+
+            match self.response {
+                ParticleConfinementResponse::Kill => return true,
+                ParticleConfinementResponse::Clamp => {
+                    position[i] = bound;
+                    if velocity[i] * outward > 0.0 {
+                        velocity[i] = 0.0;
+                    }
+                }
+                ParticleConfinementResponse::Reflect => {
+                    position[i] = (2.0 * bound - position[i]).clamp(min[i], max[i]);
+                    if velocity[i] * outward > 0.0 {
+                        velocity[i] = -velocity[i];
+                    }
+                }
+            }
+        }
+
+        particle.position = to_simulation
+            .transform_point(&Point3::from(position))
+            .coords;
+        particle.velocity = to_simulation.transform_vector(&velocity);
+        false
+    }
+}
+
+/// Defines how the force of a [`ParticleAttractor`] depends on the distance between a particle and
+/// the attractor.
+#[derive(
+    Default,
+    Copy,
+    Clone,
+    PartialOrd,
+    PartialEq,
+    Eq,
+    Ord,
+    Hash,
+    Debug,
+    Visit,
+    Reflect,
+    AsRefStr,
+    EnumString,
+    VariantNames,
+    TypeUuidProvider,
+)]
+#[type_uuid(id = "c4a81e56-3f2b-4d97-b0e6-5a9d17f3c28b")]
+#[repr(u32)]
+pub enum ParticleAttractorFalloff {
+    /// The force is the same at any distance within the radius of the attractor.
+    Constant = 0,
+    /// The force linearly decreases from full strength at the center of the attractor to zero at
+    /// its radius.
+    #[default]
+    Linear = 1,
+    /// The force is inversely proportional to the squared distance, like gravity. The distance is
+    /// limited by [`ParticleAttractor::MIN_DISTANCE`] from below to avoid infinite forces.
+    InverseSquare = 2,
+}
+
+/// A point, that pulls particles of a particle system towards itself or pushes them away. Forces of
+/// all attractors of a particle system are summed. Attractors could be used to create swirling,
+/// vortex or black hole effects. See [`ParticleSystem::set_attractors`] for more info.
+#[derive(Debug, Clone, PartialEq, Visit, Reflect, TypeUuidProvider)]
+#[type_uuid(id = "0e7d3b9a-62c5-4f18-9a4e-d85b21c6f703")]
+pub struct ParticleAttractor {
+    /// Position of the attractor in local coordinates of the particle system.
+    pub position: Vector3<f32>,
+    /// Strength (acceleration in units per second squared) of the attractor. Positive values pull
+    /// particles towards the attractor, negative values push particles away from it.
+    pub strength: f32,
+    /// Maximum distance at which the attractor affects particles.
+    #[reflect(min_value = 0.0, step = 0.1)]
+    pub radius: f32,
+    /// Defines how the force depends on the distance to the attractor.
+    pub falloff: ParticleAttractorFalloff,
+}
+
+impl Default for ParticleAttractor {
+    fn default() -> Self {
+        Self {
+            position: Vector3::default(),
+            strength: 1.0,
+            radius: 5.0,
+            falloff: Default::default(),
+        }
+    }
+}
+
+impl ParticleAttractor {
+    /// Minimum distance used to calculate [`ParticleAttractorFalloff::InverseSquare`] force.
+    pub const MIN_DISTANCE: f32 = 0.1;
+
+    /// Returns the acceleration, that the attractor applies to a particle at the given point.
+    pub fn acceleration_at(&self, point: Vector3<f32>) -> Vector3<f32> {
+        let offset = self.position - point;
+        let distance = offset.norm();
+        let radius = self.radius.max(0.0);
+        if distance <= f32::EPSILON || distance > radius {
+            return Vector3::default();
+        }
+
+        let magnitude = match self.falloff {
+            ParticleAttractorFalloff::Constant => self.strength,
+            ParticleAttractorFalloff::Linear => self.strength * (1.0 - distance / radius),
+            ParticleAttractorFalloff::InverseSquare => {
+                self.strength / distance.max(Self::MIN_DISTANCE).powi(2)
+            }
+        };
+        offset.scale(magnitude / distance)
+    }
+}
+
+/// A volume, that is used to find particles of a particle system. See
+/// [`ParticleSystem::particles_in_volume`] for more info.
+#[derive(Debug, Clone, PartialEq)]
+pub enum ParticleQueryVolume {
+    /// A sphere with the given center and radius.
+    Sphere {
+        /// Center of the sphere.
+        center: Vector3<f32>,
+        /// Radius of the sphere.
+        radius: f32,
+    },
+    /// An axis-aligned box.
+    Box(AxisAlignedBoundingBox),
+}
+
+impl ParticleQueryVolume {
+    /// Returns `true` if the given point is inside the volume (or on its boundary), `false` -
+    /// otherwise.
+    pub fn contains(&self, point: Vector3<f32>) -> bool {
+        match self {
+            Self::Sphere { center, radius } => (point - center).norm_squared() <= radius * radius,
+            Self::Box(aabb) => aabb.is_contains_point(point),
+        }
+    }
+}
+
+/// Defines how particles advance through the frames of a sprite sheet.
+#[derive(
+    Default,
+    Copy,
+    Clone,
+    PartialOrd,
+    PartialEq,
+    Eq,
+    Ord,
+    Hash,
+    Debug,
+    Visit,
+    Reflect,
+    AsRefStr,
+    EnumString,
+    VariantNames,
+    TypeUuidProvider,
+)]
+#[type_uuid(id = "2b7f5d3e-9c1a-4e68-8d42-f0a6e1c7b395")]
+#[repr(u32)]
+pub enum SpriteSheetPlayback {
+    /// Every particle plays all the frames of the sprite sheet exactly once during its lifetime, so
+    /// short-living particles play the animation faster than long-living ones.
+    #[default]
+    OverLifetime = 0,
+    /// Every particle plays the frames with a fixed rate, defined by
+    /// [`ParticleSpriteSheet::frames_per_second`]. The animation is looped.
+    FramesPerSecond = 1,
+}
+
+/// Sprite sheet (flipbook) animation of particles. The texture of the material of a particle
+/// system is split into a grid of `columns x rows` frames and each particle advances through the
+/// frames based on its age. Frames are numbered row by row, left to right, starting from the
+/// origin of texture coordinates. See [`ParticleSystem::set_sprite_sheet`] for more info.
+#[derive(Debug, Clone, PartialEq, Visit, Reflect, TypeUuidProvider)]
+#[type_uuid(id = "91630ba6-b81e-4b3e-9f46-2406332c9135")]
+pub struct ParticleSpriteSheet {
+    /// Amount of frames along the horizontal axis of the texture.
+    #[reflect(min_value = 1.0)]
+    pub columns: u32,
+    /// Amount of frames along the vertical axis of the texture.
+    #[reflect(min_value = 1.0)]
+    pub rows: u32,
+    /// Defines how particles advance through the frames.
+    pub playback: SpriteSheetPlayback,
+    /// Playback speed in frames per second. Used only with
+    /// [`SpriteSheetPlayback::FramesPerSecond`] playback.
+    #[reflect(min_value = 0.0, step = 1.0)]
+    pub frames_per_second: f32,
+    /// If `true`, every particle starts the animation from a random frame, which breaks the
+    /// repetitiveness of the effect.
+    pub random_start_frame: bool,
+}
+
+impl Default for ParticleSpriteSheet {
+    fn default() -> Self {
+        Self {
+            columns: 1,
+            rows: 1,
+            playback: Default::default(),
+            frames_per_second: 30.0,
+            random_start_frame: false,
+        }
+    }
+}
+
+impl ParticleSpriteSheet {
+    /// Returns total amount of frames in the sprite sheet.
+    pub fn frame_count(&self) -> u32 {
+        self.columns.max(1).saturating_mul(self.rows.max(1))
+    }
+
+    /// Returns the index of the frame, that should be used to render the given particle.
+    pub fn frame(&self, particle: &Particle) -> u32 {
+        let frame_count = self.frame_count();
+        let elapsed = match self.playback {
+            SpriteSheetPlayback::OverLifetime => {
+                if particle.initial_lifetime > 0.0 {
+                    let k = (particle.lifetime / particle.initial_lifetime).clamp(0.0, 1.0);
+                    ((k * frame_count as f32) as u32).min(frame_count - 1)
+                } else {
+                    0
+                }
+            }
+            SpriteSheetPlayback::FramesPerSecond => {
+                (particle.lifetime * self.frames_per_second.max(0.0)) as u32
+            }
+        };
+        particle.start_frame.wrapping_add(elapsed) % frame_count
+    }
+
+    /// Returns a rectangle of the given frame in texture coordinates.
+    pub fn frame_uv_rect(&self, frame: u32) -> Rect<f32> {
+        let columns = self.columns.max(1);
+        let rows = self.rows.max(1);
+        let frame = frame % self.frame_count();
+        let width = 1.0 / columns as f32;
+        let height = 1.0 / rows as f32;
+        Rect::new(
+            (frame % columns) as f32 * width,
+            (frame / columns) as f32 * height,
+            width,
+            height,
+        )
+    }
+}
+
 /// Particle system used to create visual effects that consists of many small parts,
 /// this can be smoke, fire, dust, sparks, etc. Particle system optimized to operate
 /// on many small parts, so it is much efficient to use particle system instead of
@@ -228,6 +846,45 @@ pub struct ParticleSystem {
     #[reflect(setter = "play")]
     is_playing: InheritableVariable<bool>,
 
+    #[reflect(setter = "set_simulation_space")]
+    simulation_space: InheritableVariable<SimulationSpace>,
+
+    #[reflect(setter = "set_prewarm")]
+    prewarm: InheritableVariable<bool>,
+
+    #[reflect(setter = "set_prewarm_duration")]
+    prewarm_duration: InheritableVariable<Option<f32>>,
+
+    #[reflect(setter = "set_particle_capacity")]
+    particle_capacity: InheritableVariable<Option<u32>>,
+
+    #[reflect(setter = "set_orientation_mode")]
+    orientation_mode: InheritableVariable<ParticleOrientationMode>,
+
+    #[reflect(setter = "set_velocity_stretch", min_value = 0.0, step = 0.05)]
+    velocity_stretch: InheritableVariable<f32>,
+
+    #[reflect(setter = "set_collision_plane")]
+    collision_plane: InheritableVariable<Option<ParticleCollisionPlane>>,
+
+    #[reflect(setter = "set_confinement")]
+    confinement: InheritableVariable<Option<ParticleConfinement>>,
+
+    #[reflect(setter = "set_sort_mode")]
+    sort_mode: InheritableVariable<ParticleSortMode>,
+
+    #[reflect(setter = "set_sprite_sheet")]
+    sprite_sheet: InheritableVariable<Option<ParticleSpriteSheet>>,
+
+    #[reflect(setter = "set_min_screen_size", min_value = 0.0, step = 0.5)]
+    min_screen_size: InheritableVariable<f32>,
+
+    #[reflect(setter = "set_attractors")]
+    attractors: InheritableVariable<Vec<ParticleAttractor>>,
+
+    #[reflect(setter = "set_turbulence")]
+    turbulence: InheritableVariable<ParticleTurbulence>,
+
     #[reflect(hidden)]
     particles: Vec<Particle>,
 
@@ -235,6 +892,27 @@ pub struct ParticleSystem {
     free_particles: Vec<u32>,
 
     rng: ParticleSystemRng,
+
+    #[reflect(hidden)]
+    age: f32,
+
+    #[reflect(hidden)]
+    is_emitting: bool,
+
+    #[reflect(hidden)]
+    is_prewarmed: bool,
+
+    #[reflect(hidden)]
+    pool_stats: ParticlePoolStats,
+
+    #[reflect(hidden)]
+    collision_hits: Vec<Vector3<f32>>,
+
+    /// Position of the particle system in world coordinates at the previous update. It is used to
+    /// calculate the travelled distance for emitters with [`EmissionMode::Distance`](emitter::base::EmissionMode::Distance)
+    /// mode.
+    #[reflect(hidden)]
+    last_global_position: Option<Vector3<f32>>,
 }
 
 impl Visit for ParticleSystem {
@@ -250,6 +928,24 @@ impl Visit for ParticleSystem {
         self.particles.visit("Particles", &mut region)?;
         self.free_particles.visit("FreeParticles", &mut region)?;
         let _ = self.rng.visit("Rng", &mut region);
+        let _ = self.age.visit("Age", &mut region);
+        let _ = self.simulation_space.visit("SimulationSpace", &mut region);
+        let _ = self.is_emitting.visit("IsEmitting", &mut region);
+        let _ = self.prewarm.visit("Prewarm", &mut region);
+        let _ = self.prewarm_duration.visit("PrewarmDuration", &mut region);
+        let _ = self.is_prewarmed.visit("IsPrewarmed", &mut region);
+        let _ = self
+            .particle_capacity
+            .visit("ParticleCapacity", &mut region);
+        let _ = self.orientation_mode.visit("OrientationMode", &mut region);
+        let _ = self.velocity_stretch.visit("VelocityStretch", &mut region);
+        let _ = self.collision_plane.visit("CollisionPlane", &mut region);
+        let _ = self.sort_mode.visit("SortMode", &mut region);
+        let _ = self.sprite_sheet.visit("SpriteSheet", &mut region);
+        let _ = self.min_screen_size.visit("MinScreenSize", &mut region);
+        let _ = self.attractors.visit("Attractors", &mut region);
+        let _ = self.turbulence.visit("Turbulence", &mut region);
+        let _ = self.confinement.visit("Confinement", &mut region);
 
         // Backward compatibility.
         if region.is_reading() {
@@ -276,6 +972,21 @@ impl Visit for ParticleSystem {
             ));
         }
 
+        if region.is_reading() {
+            for (index, emitter) in self.emitters.get_value_mut_silent().iter_mut().enumerate() {
+                for parameter in emitter.repair_invalid_parameters() {
+                    Log::warn(format!(
+                        "Emitter {} ({}) of particle system {} has invalid {} value, it was \
+                        replaced with the default value.",
+                        index,
+                        emitter.kind().as_ref(),
+                        self.base.name(),
+                        parameter
+                    ));
+                }
+            }
+        }
+
         Ok(())
     }
 }
@@ -324,11 +1035,125 @@ impl ParticleSystem {
         self.is_playing.set_value_and_mark_modified(is_playing)
     }
 
+    /// Sets new simulation space of the particle system. Already emitted particles are not
+    /// converted, so they may "jump" after the change. See [`SimulationSpace`] docs for more info.
+    pub fn set_simulation_space(&mut self, space: SimulationSpace) -> SimulationSpace {
+        self.simulation_space.set_value_and_mark_modified(space)
+    }
+
+    /// Returns current simulation space of the particle system.
+    pub fn simulation_space(&self) -> SimulationSpace {
+        *self.simulation_space
+    }
+
+    /// Enables or disables prewarming of the particle system. Prewarmed particle system instantly
+    /// simulates some time (see [`Self::set_prewarm_duration`]) on its first update, so the effect
+    /// is in its "steady" state right from the start. It is useful for continuous effects, such as
+    /// fog, smoke, etc. that otherwise would take a few seconds to "fill" in. Prewarming is done
+    /// only once, the fact that it was done is saved together with the particles, so a loaded
+    /// particle system continues its simulation instead of being prewarmed again.
+    pub fn set_prewarm(&mut self, prewarm: bool) -> bool {
+        self.prewarm.set_value_and_mark_modified(prewarm)
+    }
+
+    /// Returns `true` if the particle system is prewarmed on its first update, `false` - otherwise.
+    pub fn is_prewarm(&self) -> bool {
+        *self.prewarm
+    }
+
+    /// Sets the amount of time (in seconds) that will be simulated when prewarming the particle
+    /// system. [`None`] means that the maximum lifetime of particles of all emitters will be used,
+    /// which is enough to reach the steady state of most effects.
+    pub fn set_prewarm_duration(&mut self, duration: Option<f32>) -> Option<f32> {
+        self.prewarm_duration
+            .set_value_and_mark_modified(duration.map(|duration| duration.max(0.0)))
+    }
+
+    /// Returns the amount of time (in seconds) that will be simulated when prewarming the particle
+    /// system.
+    pub fn prewarm_duration(&self) -> Option<f32> {
+        *self.prewarm_duration
+    }
+
+    fn do_prewarm(&mut self) {
+        let duration = self.prewarm_duration.unwrap_or_else(|| {
+            self.emitters
+                .iter()
+                .map(|emitter| emitter.life_time_range().end)
+                .fold(0.0, f32::max)
+        });
+        self.rewind(1.0 / 60.0, duration);
+        self.is_prewarmed = true;
+    }
+
     /// Returns current particle system status.
     pub fn is_playing(&self) -> bool {
         *self.is_playing
     }
 
+    /// Enables or disables every emitter in the given group (see [`emitter::base::BaseEmitter::set_group`]) and
+    /// returns the amount of affected emitters. It allows you to control phases of a complex effect
+    /// by group names rather than by indices of emitters.
+    pub fn set_group_enabled(&mut self, group: &str, enabled: bool) -> usize {
+        let mut count = 0;
+        for emitter in self.emitters.get_value_mut_and_mark_modified().iter_mut() {
+            if emitter.group() == group {
+                emitter.set_enabled(enabled);
+                count += 1;
+            }
+        }
+        count
+    }
+
+    /// Returns an iterator over all emitters in the given group.
+    pub fn emitters_in_group<'a>(&'a self, group: &'a str) -> impl Iterator<Item = &'a Emitter> {
+        self.emitters
+            .iter()
+            .filter(move |emitter| emitter.group() == group)
+    }
+
+    /// Returns an iterator over all emitters of the particle system along with their kinds. It
+    /// could be used to build a user interface with a list of emitters.
+    pub fn emitters_with_kinds(&self) -> impl Iterator<Item = (EmitterKind, &Emitter)> {
+        self.emitters
+            .iter()
+            .map(|emitter| (emitter.kind(), emitter))
+    }
+
+    /// Returns an iterator over mutable references to all emitters of the particle system along
+    /// with their kinds. The emitters are marked as modified, see [`Self::emitters_with_kinds`]
+    /// for more info.
+    pub fn emitters_with_kinds_mut(&mut self) -> impl Iterator<Item = (EmitterKind, &mut Emitter)> {
+        self.emitters
+            .get_value_mut_and_mark_modified()
+            .iter_mut()
+            .map(|emitter| (emitter.kind(), emitter))
+    }
+
+    /// Stops emission of new particles, but keeps updating the particles that are alive at the
+    /// moment until they die. Unlike [`Self::clear_particles`], it does not remove the particles
+    /// immediately and so there's no abrupt disappearance of the effect. Use [`Self::is_finished`]
+    /// to check whether all the particles have died (for example to remove the node after that).
+    /// The emission is restarted by [`Self::rewind`].
+    pub fn stop(&mut self) {
+        self.is_emitting = false;
+        for emitter in self.emitters.get_value_mut_silent().iter_mut() {
+            emitter.particles_to_spawn = 0;
+        }
+    }
+
+    /// Returns `true` if the particle system emits new particles, `false` - if the emission was
+    /// stopped by [`Self::stop`].
+    pub fn is_emitting(&self) -> bool {
+        self.is_emitting
+    }
+
+    /// Returns `true` if the emission was stopped by [`Self::stop`] and all the particles have
+    /// died, `false` - otherwise.
+    pub fn is_finished(&self) -> bool {
+        !self.is_emitting && self.particles.iter().all(|particle| !particle.alive)
+    }
+
     /// Replaces the particles in the particle system with pre-generated set. It could be useful
     /// to create procedural particle effects; when particles cannot be pre-made.
     pub fn set_particles(&mut self, particles: Vec<Particle>) {
@@ -341,6 +1166,279 @@ impl ParticleSystem {
         &self.particles
     }
 
+    /// Returns indices (in the slice returned by [`Self::particles`]) and positions of every alive
+    /// particle, that is inside the given volume. The volume and the returned positions are in
+    /// world coordinates regardless of the simulation space of the particle system. The indices
+    /// could be used to remove the particles, see [`Self::remove_particle`].
+    ///
+    /// # Performance
+    ///
+    /// The method iterates over the entire particle buffer, so its cost is O(n) where n is the
+    /// amount of particles in the buffer (including dead ones). Avoid calling it multiple times per
+    /// frame for particle systems with large amounts of particles.
+    pub fn particles_in_volume(&self, volume: &ParticleQueryVolume) -> Vec<(usize, Vector3<f32>)> {
+        let to_world = if *self.simulation_space == SimulationSpace::World {
+            None
+        } else {
+            Some(self.base.global_transform())
+        };
+        self.particles
+            .iter()
+            .enumerate()
+            .filter(|(_, particle)| particle.alive)
+            .filter_map(|(index, particle)| {
+                let position = match to_world.as_ref() {
+                    Some(to_world) => {
+                        to_world
+                            .transform_point(&Point3::from(particle.position))
+                            .coords
+                    }
+                    None => particle.position,
+                };
+                volume.contains(position).then_some((index, position))
+            })
+            .collect()
+    }
+
+    /// Kills the particle at the given index (in the slice returned by [`Self::particles`]), its
+    /// slot will be reused for new particles. Returns `true` if the particle was alive, `false` -
+    /// if there is no such particle or it is dead already. See [`Self::particles_in_volume`] for
+    /// a way to find particles in some region.
+    pub fn remove_particle(&mut self, index: usize) -> bool {
+        let Some(particle) = self.particles.get_mut(index) else {
+            return false;
+        };
+        if !particle.alive {
+            return false;
+        }
+
+        particle.alive = false;
+        particle.lifetime = particle.initial_lifetime;
+        self.free_particles.push(index as u32);
+        if let Some(emitter) = self
+            .emitters
+            .get_value_mut_and_mark_modified()
+            .get_mut(particle.emitter_index as usize)
+        {
+            emitter.alive_particles = emitter.alive_particles.saturating_sub(1);
+        }
+        true
+    }
+
+    /// Returns total amount of particles, that are alive at the moment. It includes the particles of
+    /// every emitter as well as the particles set by [`Self::set_particles`]. Use
+    /// [`emitter::base::BaseEmitter::alive_particles`] to get the amount of particles of a
+    /// particular emitter.
+    pub fn alive_particles_count(&self) -> usize {
+        self.particles
+            .iter()
+            .filter(|particle| particle.alive)
+            .count()
+    }
+
+    /// Returns total amount of particles, that were emitted by every emitter during the last update
+    /// of the particle system. Use [`emitter::base::BaseEmitter::particles_emitted_last_tick`] to
+    /// get the amount of particles emitted by a particular emitter.
+    pub fn particles_emitted_last_tick(&self) -> u32 {
+        self.emitters
+            .iter()
+            .map(|emitter| emitter.particles_emitted_last_tick())
+            .sum()
+    }
+
+    /// Sets the maximum amount of particles that could exist in the particle system at the same
+    /// time. Particles are stored in a pool, slots of dead particles are reused for new particles
+    /// and the pool never shrinks. With a capacity the pool is allocated only once and emission of
+    /// particles past the capacity is dropped (see [`ParticlePoolStats::dropped`]), instead of
+    /// growing the pool. [`None`] means that the pool grows when needed (the default).
+    pub fn set_particle_capacity(&mut self, capacity: Option<u32>) -> Option<u32> {
+        self.particle_capacity.set_value_and_mark_modified(capacity)
+    }
+
+    /// Returns the maximum amount of particles that could exist in the particle system at the same
+    /// time. See [`Self::set_particle_capacity`] for more info.
+    pub fn particle_capacity(&self) -> Option<u32> {
+        *self.particle_capacity
+    }
+
+    /// Sets new orientation mode of particles. See [`ParticleOrientationMode`] docs for more info.
+    pub fn set_orientation_mode(
+        &mut self,
+        orientation_mode: ParticleOrientationMode,
+    ) -> ParticleOrientationMode {
+        self.orientation_mode
+            .set_value_and_mark_modified(orientation_mode)
+    }
+
+    /// Returns current orientation mode of particles.
+    pub fn orientation_mode(&self) -> ParticleOrientationMode {
+        *self.orientation_mode
+    }
+
+    /// Sets new velocity stretch factor of particles. Each particle is elongated along its velocity
+    /// by `speed * factor`, where the speed is the length of the velocity of the particle. Stretched
+    /// billboards are aligned with their velocity, which is useful for speed lines, sparks or rain.
+    /// Particles with [`ParticleOrientationMode::AxisAligned`] orientation are stretched along the
+    /// axis of the particle system. Zero disables stretching, negative values are clamped to zero.
+    pub fn set_velocity_stretch(&mut self, factor: f32) -> f32 {
+        self.velocity_stretch
+            .set_value_and_mark_modified(factor.max(0.0))
+    }
+
+    /// Returns current velocity stretch factor of particles. See [`Self::set_velocity_stretch`]
+    /// for more info.
+    pub fn velocity_stretch(&self) -> f32 {
+        *self.velocity_stretch
+    }
+
+    /// Sets new minimum size (in pixels) of particles on screen. Particles, that are projected to
+    /// a smaller size (for example, tiny distant particles), are enlarged up to this size when
+    /// rendered. It trades accuracy for stability - sub-pixel particles flicker, because they
+    /// cover different amount of pixels each frame. The simulation is not affected. Zero disables
+    /// the clamping, negative values are clamped to zero.
+    pub fn set_min_screen_size(&mut self, size: f32) -> f32 {
+        self.min_screen_size
+            .set_value_and_mark_modified(size.max(0.0))
+    }
+
+    /// Returns current minimum size (in pixels) of particles on screen. See
+    /// [`Self::set_min_screen_size`] for more info.
+    pub fn min_screen_size(&self) -> f32 {
+        *self.min_screen_size
+    }
+
+    /// Sets new set of attractors of the particle system. Each attractor pulls particles towards
+    /// itself or pushes them away, forces of all attractors are summed and applied together with
+    /// the acceleration of the particle system. See [`ParticleAttractor`] docs for more info.
+    /// Returns the previous set of attractors.
+    pub fn set_attractors(&mut self, attractors: Vec<ParticleAttractor>) -> Vec<ParticleAttractor> {
+        self.attractors.set_value_and_mark_modified(attractors)
+    }
+
+    /// Returns current set of attractors of the particle system. See [`Self::set_attractors`] for
+    /// more info.
+    pub fn attractors(&self) -> &[ParticleAttractor] {
+        &self.attractors
+    }
+
+    /// Sets new turbulence of the particle system, that perturbs particles with a procedural
+    /// curl noise force. The noise is sampled in the simulation space of the particle system. See
+    /// [`ParticleTurbulence`] docs for more info. Returns the previous turbulence.
+    pub fn set_turbulence(&mut self, turbulence: ParticleTurbulence) -> ParticleTurbulence {
+        self.turbulence.set_value_and_mark_modified(turbulence)
+    }
+
+    /// Returns current turbulence of the particle system. See [`Self::set_turbulence`] for more
+    /// info.
+    pub fn turbulence(&self) -> &ParticleTurbulence {
+        &self.turbulence
+    }
+
+    /// Sets new collision plane of the particle system. Particles, that cross the plane, are either
+    /// killed or bounce off it, depending on [`ParticleCollisionPlane::response`]. The plane is
+    /// defined in world coordinates regardless of the simulation space of the particle system.
+    /// [`None`] disables the collisions. Returns the previous collision plane.
+    pub fn set_collision_plane(
+        &mut self,
+        collision_plane: Option<ParticleCollisionPlane>,
+    ) -> Option<ParticleCollisionPlane> {
+        self.collision_plane
+            .set_value_and_mark_modified(collision_plane)
+    }
+
+    /// Returns current collision plane of the particle system.
+    pub fn collision_plane(&self) -> Option<&ParticleCollisionPlane> {
+        self.collision_plane.as_ref()
+    }
+
+    /// Returns the points (in world coordinates) where particles hit the collision plane during the
+    /// last update of the particle system. The points are cleared on each update, they could be fed
+    /// to [`Self::emit_at`] to produce secondary effects, such as sparks or splashes.
+    pub fn collision_hits(&self) -> &[Vector3<f32>] {
+        &self.collision_hits
+    }
+
+    /// Same as [`Self::collision_hits`], but moves the points out of the particle system, so they
+    /// are reported only once.
+    pub fn take_collision_hits(&mut self) -> Vec<Vector3<f32>> {
+        std::mem::take(&mut self.collision_hits)
+    }
+
+    /// Sets new confinement box of the particle system. Particles, that leave the box, are clamped
+    /// to the box, reflected back into it or killed, depending on
+    /// [`ParticleConfinement::response`]. The box is defined in local coordinates of the particle
+    /// system regardless of its simulation space. [`None`] disables the confinement. Returns the
+    /// previous confinement box.
+    pub fn set_confinement(
+        &mut self,
+        confinement: Option<ParticleConfinement>,
+    ) -> Option<ParticleConfinement> {
+        self.confinement.set_value_and_mark_modified(confinement)
+    }
+
+    /// Returns current confinement box of the particle system.
+    pub fn confinement(&self) -> Option<&ParticleConfinement> {
+        self.confinement.as_ref()
+    }
+
+    /// Sets new sort mode of particles, that defines the order in which particles are rendered. See
+    /// [`ParticleSortMode`] docs for more info. Returns the previous sort mode.
+    pub fn set_sort_mode(&mut self, sort_mode: ParticleSortMode) -> ParticleSortMode {
+        self.sort_mode.set_value_and_mark_modified(sort_mode)
+    }
+
+    /// Returns current sort mode of particles.
+    pub fn sort_mode(&self) -> ParticleSortMode {
+        *self.sort_mode
+    }
+
+    /// Sets new sprite sheet animation of particles. The texture of the material is treated as a
+    /// grid of frames and each particle shows a frame depending on its age. [`None`] disables the
+    /// animation, particles use the entire texture. Returns the previous sprite sheet.
+    pub fn set_sprite_sheet(
+        &mut self,
+        sprite_sheet: Option<ParticleSpriteSheet>,
+    ) -> Option<ParticleSpriteSheet> {
+        self.sprite_sheet.set_value_and_mark_modified(sprite_sheet)
+    }
+
+    /// Returns current sprite sheet animation of particles.
+    pub fn sprite_sheet(&self) -> Option<&ParticleSpriteSheet> {
+        self.sprite_sheet.as_ref()
+    }
+
+    /// Returns bounds of the space in which the emitters of the particle system spawn particles, in
+    /// local coordinates of the particle system. See [`Emitter::emission_bounds`] for more info.
+    /// The bounds are invalid (see [`AxisAlignedBoundingBox::is_valid`]) if there are no emitters.
+    pub fn emission_bounds(&self) -> AxisAlignedBoundingBox {
+        let mut bounds = AxisAlignedBoundingBox::default();
+        for emitter in self.emitters.iter() {
+            bounds.add_box(emitter.emission_bounds());
+        }
+        bounds
+    }
+
+    /// Sets new pseudo-random numbers generator of the particle system and returns the previous one.
+    /// It could be used to plug in a custom source of random numbers, see [`ParticleRngSource`].
+    pub fn set_rng(&mut self, rng: ParticleSystemRng) -> ParticleSystemRng {
+        std::mem::replace(&mut self.rng, rng)
+    }
+
+    /// Returns current pseudo-random numbers generator of the particle system.
+    pub fn rng(&self) -> &ParticleSystemRng {
+        &self.rng
+    }
+
+    /// Returns the statistics of the particle pool of the particle system.
+    pub fn pool_stats(&self) -> &ParticlePoolStats {
+        &self.pool_stats
+    }
+
+    /// Resets the statistics of the particle pool of the particle system.
+    pub fn reset_pool_stats(&mut self) {
+        self.pool_stats = Default::default();
+    }
+
     /// Removes all generated particles.
     pub fn clear_particles(&mut self) {
         self.particles.clear();
@@ -366,33 +1464,132 @@ impl ParticleSystem {
         &self.material
     }
 
+    /// Returns the time (in seconds) that has passed since the particle system was created or
+    /// rewound.
+    pub fn age(&self) -> f32 {
+        self.age
+    }
+
     fn tick(&mut self, dt: f32) {
-        for emitter in self.emitters.get_value_mut_silent().iter_mut() {
-            emitter.tick(dt);
+        self.age += dt;
+        self.collision_hits.clear();
+
+        // The lifetime of the node is decreasing over time, so the total lifetime is the sum of the
+        // age and remaining lifetime.
+        let normalized_age = self
+            .base
+            .lifetime()
+            .map(|remaining| (self.age / (self.age + remaining.max(0.0))).clamp(0.0, 1.0));
+
+        if self.is_emitting {
+            for emitter in self.emitters.get_value_mut_silent().iter_mut() {
+                emitter.normalized_system_age = normalized_age;
+                emitter.system_age = self.age;
+                emitter.volume = emitter.volume();
+                emitter.tick(dt);
+            }
         }
 
-        for (i, emitter) in self.emitters.get_value_mut_silent().iter_mut().enumerate() {
-            for _ in 0..emitter.particles_to_spawn {
-                let mut particle = Particle {
-                    emitter_index: i as u32,
-                    ..Particle::default()
-                };
-                emitter.alive_particles += 1;
-                emitter.emit(&mut particle, &mut self.rng);
-                if let Some(free_index) = self.free_particles.pop() {
-                    self.particles[free_index as usize] = particle;
-                } else {
-                    self.particles.push(particle);
-                }
-            }
+        for i in 0..self.emitters.len() {
+            let count = self.emitters[i].particles_to_spawn;
+            self.spawn_particles(i, count, None);
         }
 
         let acceleration_offset = self.acceleration.scale(dt * dt);
 
+        // Particles are simulated in local coordinates of the particle system, unless it uses world
+        // space simulation, so the plane must be transformed to the simulation space.
+        let collision_plane = self.collision_plane.as_ref().and_then(|plane| {
+            if *self.simulation_space == SimulationSpace::World {
+                Some(plane.clone())
+            } else {
+                plane.inverse_transform(&self.base.global_transform())
+            }
+        });
+        // Contact points are reported in world coordinates.
+        let contact_transform = if *self.simulation_space == SimulationSpace::World {
+            None
+        } else {
+            Some(self.base.global_transform())
+        };
+
+        // Attractors are defined in local coordinates of the particle system, so they must be
+        // transformed to the simulation space.
+        let attractors = self
+            .attractors
+            .iter()
+            .map(|attractor| {
+                let mut attractor = attractor.clone();
+                if *self.simulation_space == SimulationSpace::World {
+                    attractor.position = self
+                        .base
+                        .global_transform()
+                        .transform_point(&Point3::from(attractor.position))
+                        .coords;
+                }
+                attractor
+            })
+            .collect::<Vec<_>>();
+
+        let turbulence = self.turbulence.is_enabled().then_some(&*self.turbulence);
+
+        // The confinement box is defined in local coordinates of the particle system, so particles
+        // simulated in world space must be transformed to the local space to be checked.
+        let confinement = self.confinement.as_ref().and_then(|confinement| {
+            if *self.simulation_space == SimulationSpace::World {
+                let to_simulation = self.base.global_transform();
+                Some((confinement, to_simulation.try_inverse()?, to_simulation))
+            } else {
+                Some((confinement, Matrix4::identity(), Matrix4::identity()))
+            }
+        });
+
         for (i, particle) in self.particles.iter_mut().enumerate() {
             if particle.alive {
                 particle.lifetime += dt;
-                if particle.lifetime >= particle.initial_lifetime {
+
+                let mut is_dead = particle.lifetime >= particle.initial_lifetime;
+                if !is_dead {
+                    particle.velocity += acceleration_offset;
+                    for attractor in attractors.iter() {
+                        particle.velocity +=
+                            attractor.acceleration_at(particle.position).scale(dt * dt);
+                    }
+                    if let Some(turbulence) = turbulence {
+                        particle.velocity += turbulence
+                            .acceleration_at(particle.position, self.age)
+                            .scale(dt * dt);
+                    }
+                    particle.position += particle.velocity;
+                    particle.size += particle.size_modifier * dt;
+                    if particle.size < 0.0 {
+                        particle.size = 0.0;
+                    }
+                    particle.rotation += particle.rotation_speed * dt;
+
+                    let k = particle.lifetime / particle.initial_lifetime;
+                    particle.color = self.color_over_lifetime.get_color(k);
+
+                    if let Some(collision_plane) = collision_plane.as_ref() {
+                        if let Some(contact_point) = collision_plane.contact_point(particle) {
+                            self.collision_hits.push(match contact_transform.as_ref() {
+                                Some(transform) => {
+                                    transform
+                                        .transform_point(&Point3::from(contact_point))
+                                        .coords
+                                }
+                                None => contact_point,
+                            });
+                        }
+                        is_dead = collision_plane.collide(particle);
+                    }
+
+                    if let Some((confinement, to_local, to_simulation)) = confinement.as_ref() {
+                        is_dead |= confinement.confine(particle, to_local, to_simulation);
+                    }
+                }
+
+                if is_dead {
                     self.free_particles.push(i as u32);
                     if let Some(emitter) = self
                         .emitters
@@ -403,19 +1600,198 @@ impl ParticleSystem {
                     }
                     particle.alive = false;
                     particle.lifetime = particle.initial_lifetime;
-                } else {
-                    particle.velocity += acceleration_offset;
-                    particle.position += particle.velocity;
-                    particle.size += particle.size_modifier * dt;
-                    if particle.size < 0.0 {
-                        particle.size = 0.0;
-                    }
-                    particle.rotation += particle.rotation_speed * dt;
+                }
+            }
+        }
+    }
 
-                    let k = particle.lifetime / particle.initial_lifetime;
-                    particle.color = self.color_over_lifetime.get_color(k);
+    /// Spawns the given amount of particles of an emitter and returns the amount of actually spawned
+    /// particles. If `position` is set, it overrides the positions of the particles (it is in world
+    /// coordinates).
+    fn spawn_particles(
+        &mut self,
+        emitter_index: usize,
+        count: u32,
+        position: Option<Vector3<f32>>,
+    ) -> u32 {
+        let global_transform = self.base.global_transform();
+        let world_transform = if *self.simulation_space == SimulationSpace::World {
+            Some(global_transform)
+        } else {
+            None
+        };
+        // Simulation in local space requires the position in local coordinates of the system.
+        let position = match (position, world_transform) {
+            (Some(position), None) => global_transform
+                .try_inverse()
+                .map(|inv| inv.transform_point(&Point3::from(position)).coords),
+            (position, _) => position,
+        };
+
+        let capacity = self.particle_capacity.map(|capacity| capacity as usize);
+        if let Some(capacity) = capacity {
+            self.particles
+                .reserve_exact(capacity.saturating_sub(self.particles.len()));
+        }
+
+        let Some(emitter) = self.emitters.get_value_mut_silent().get_mut(emitter_index) else {
+            return 0;
+        };
+
+        let mut spawned = 0;
+        for _ in 0..count {
+            let free_index = self.free_particles.pop();
+            if free_index.is_none()
+                && capacity.is_some_and(|capacity| self.particles.len() >= capacity)
+            {
+                self.pool_stats.dropped += 1;
+                continue;
+            }
+
+            let mut particle = Particle {
+                emitter_index: emitter_index as u32,
+                ..Particle::default()
+            };
+            emitter.alive_particles += 1;
+            emitter.emit(&mut particle, &mut self.rng);
+            emitter.apply_orientation(&mut particle);
+            if let Some(custom) = emitter.next_custom_data(&mut self.rng) {
+                particle.custom = custom;
+            }
+            if let Some(sprite_sheet) = self.sprite_sheet.as_ref() {
+                if sprite_sheet.random_start_frame {
+                    particle.start_frame = self.rng.gen_range(0..sprite_sheet.frame_count());
                 }
             }
+            if let Some(world_transform) = world_transform.as_ref() {
+                particle.position = world_transform
+                    .transform_point(&Point3::from(particle.position))
+                    .coords;
+                particle.velocity = world_transform.transform_vector(&particle.velocity);
+            }
+            if let Some(position) = position {
+                particle.position = position;
+            }
+            if let Some(free_index) = free_index {
+                self.particles[free_index as usize] = particle;
+                self.pool_stats.recycled += 1;
+            } else {
+                self.particles.push(particle);
+            }
+            spawned += 1;
+        }
+
+        self.pool_stats.high_water_mark = self
+            .pool_stats
+            .high_water_mark
+            .max(self.particles.len() - self.free_particles.len());
+
+        spawned
+    }
+
+    /// Immediately spawns a burst of particles of the emitter with the given index at the given
+    /// point (in world coordinates). All the other initial parameters of the particles (lifetime,
+    /// velocity, size, etc.) are generated by the emitter as usual. Returns the amount of spawned
+    /// particles, which could be less than `count` if the particle system has limited capacity (see
+    /// [`Self::set_particle_capacity`]), or zero if there's no emitter with the given index.
+    /// Bursts ignore the spawn rate and the particle limits of the emitter, as well as its enabled
+    /// state and start delay.
+    ///
+    /// # Collisions
+    ///
+    /// This method is the hook for collision effects: the points, where particles hit the collision
+    /// plane of the particle system, are reported by [`Self::collision_hits`], other contacts could
+    /// be detected by gameplay code (for example, using contact events of the physics). The points
+    /// are fed to a dedicated emitter, for example to produce sparks when debris hits the ground. A
+    /// contact point is the only data needed, the appearance of the burst is defined by the emitter.
+    pub fn emit_at(&mut self, emitter_index: usize, position: Vector3<f32>, count: u32) -> u32 {
+        self.spawn_particles(emitter_index, count, Some(position))
+    }
+
+    fn update_node_bounds(&mut self, context: &UpdateContext) {
+        let emitters = self.emitters.get_value_mut_silent();
+        if emitters
+            .iter()
+            .all(|emitter| emitter.emission_volume() == EmissionVolume::Shape)
+        {
+            return;
+        }
+
+        // Particles are simulated in local coordinates of the particle system, while the bounds
+        // of the parent mesh are in its own local coordinates, so the bounds must be transformed.
+        // A mesh without any geometry has invalid bounds, emitters fall back to their shapes then.
+        let node_bounds = context
+            .nodes
+            .try_borrow(self.base.parent())
+            .and_then(|parent| parent.cast::<Mesh>())
+            .map(|mesh| mesh.local_bounding_box())
+            .filter(|bounds| bounds.is_valid())
+            .zip(self.base.local_transform().matrix().try_inverse())
+            .map(|(bounds, inv_local_transform)| bounds.transform(&inv_local_transform))
+            .filter(|bounds| bounds.is_valid());
+
+        for emitter in emitters.iter_mut() {
+            emitter.node_bounds = node_bounds;
+        }
+    }
+
+    /// Returns indices of alive particles in the order they must be rendered, that is defined by the
+    /// sort mode of the particle system.
+    fn sorted_particles(&self, observer_position: Vector3<f32>) -> Vec<u32> {
+        let is_world_space = *self.simulation_space == SimulationSpace::World;
+
+        let mut sorted_particles = Vec::new();
+        for (i, particle) in self.particles.iter().enumerate() {
+            if particle.alive {
+                let actual_position = if is_world_space {
+                    particle.position
+                } else {
+                    particle.position + self.base.global_position()
+                };
+                particle
+                    .sqr_distance_to_camera
+                    .set((observer_position - actual_position).norm_squared());
+                sorted_particles.push(i as u32);
+            }
+        }
+
+        let particles = &self.particles;
+
+        match *self.sort_mode {
+            ParticleSortMode::None => (),
+            ParticleSortMode::BackToFront => sorted_particles.sort_by(|a, b| {
+                let particle_a = particles.get(*a as usize).unwrap();
+                let particle_b = particles.get(*b as usize).unwrap();
+
+                // Reverse ordering because we want to sort back-to-front.
+                if particle_a.sqr_distance_to_camera < particle_b.sqr_distance_to_camera {
+                    Ordering::Greater
+                } else if particle_a.sqr_distance_to_camera > particle_b.sqr_distance_to_camera {
+                    Ordering::Less
+                } else {
+                    Ordering::Equal
+                }
+            }),
+            ParticleSortMode::OldestFirst => sorted_particles.sort_by(|a, b| {
+                let particle_a = particles.get(*a as usize).unwrap();
+                let particle_b = particles.get(*b as usize).unwrap();
+
+                particle_b.lifetime.total_cmp(&particle_a.lifetime)
+            }),
+        }
+
+        sorted_particles
+    }
+
+    fn update_travelled_distance(&mut self) {
+        let position = self.base.global_position();
+        let distance = self
+            .last_global_position
+            .map_or(0.0, |last_position| (position - last_position).norm());
+        self.last_global_position = Some(position);
+
+        for emitter in self.emitters.get_value_mut_silent().iter_mut() {
+            emitter.travelled_distance = distance;
         }
     }
 
@@ -425,6 +1801,8 @@ impl ParticleSystem {
 
         self.rng.reset();
         self.clear_particles();
+        self.age = 0.0;
+        self.is_emitting = true;
 
         let mut t = 0.0;
         while t < time {
@@ -444,7 +1822,35 @@ impl NodeTrait for ParticleSystem {
     crate::impl_query_component!();
 
     fn local_bounding_box(&self) -> AxisAlignedBoundingBox {
-        AxisAlignedBoundingBox::unit()
+        let mut bounds = self.emission_bounds();
+
+        // Particles simulated in world space must be moved back to local coordinates.
+        let inv_global_transform = if *self.simulation_space == SimulationSpace::World {
+            self.global_transform().try_inverse()
+        } else {
+            None
+        };
+        for particle in self.particles.iter().filter(|particle| particle.alive) {
+            let position = match inv_global_transform.as_ref() {
+                Some(inv_global_transform) => {
+                    inv_global_transform
+                        .transform_point(&Point3::from(particle.position))
+                        .coords
+                }
+                None => particle.position,
+            };
+            let half_size = Vector3::repeat(particle.size);
+            bounds.add_box(AxisAlignedBoundingBox::from_min_max(
+                position - half_size,
+                position + half_size,
+            ));
+        }
+
+        if bounds.is_valid() {
+            bounds
+        } else {
+            AxisAlignedBoundingBox::unit()
+        }
     }
 
     fn world_bounding_box(&self) -> AxisAlignedBoundingBox {
@@ -460,6 +1866,11 @@ impl NodeTrait for ParticleSystem {
         let dt = context.dt;
 
         if *self.is_playing {
+            self.update_node_bounds(context);
+            if *self.prewarm && !self.is_prewarmed {
+                self.do_prewarm();
+            }
+            self.update_travelled_distance();
             self.tick(dt);
         }
     }
@@ -479,34 +1890,15 @@ impl NodeTrait for ParticleSystem {
             return RdcControlFlow::Continue;
         }
 
-        let mut sorted_particles = Vec::new();
-        for (i, particle) in self.particles.iter().enumerate() {
-            if particle.alive {
-                let actual_position = particle.position + self.base.global_position();
-                particle
-                    .sqr_distance_to_camera
-                    .set((*ctx.observer_position - actual_position).norm_squared());
-                sorted_particles.push(i as u32);
-            }
-        }
-
-        let particles = &self.particles;
-
-        sorted_particles.sort_by(|a, b| {
-            let particle_a = particles.get(*a as usize).unwrap();
-            let particle_b = particles.get(*b as usize).unwrap();
-
-            // Reverse ordering because we want to sort back-to-front.
-            if particle_a.sqr_distance_to_camera < particle_b.sqr_distance_to_camera {
-                Ordering::Greater
-            } else if particle_a.sqr_distance_to_camera > particle_b.sqr_distance_to_camera {
-                Ordering::Less
-            } else {
-                Ordering::Equal
-            }
-        });
+        let sorted_particles = self.sorted_particles(*ctx.observer_position);
+        let is_world_space = *self.simulation_space == SimulationSpace::World;
 
         let global_transform = self.global_transform();
+        let orientation_mode = *self.orientation_mode;
+        let velocity_stretch = *self.velocity_stretch;
+        let system_axis = global_transform.transform_vector(&Vector3::y());
+        let sprite_sheet = &*self.sprite_sheet;
+        let min_screen_size = *self.min_screen_size;
         let sort_index = ctx.calculate_sorting_index(self.global_position());
 
         ctx.storage.push_triangles(
@@ -521,9 +1913,36 @@ impl NodeTrait for ParticleSystem {
                 let vertices = sorted_particles.iter().flat_map(move |particle_index| {
                     let particle = self.particles.get(*particle_index as usize).unwrap();
 
-                    let position = global_transform
-                        .transform_point(&Point3::from(particle.position))
-                        .coords;
+                    let position = if is_world_space {
+                        particle.position
+                    } else {
+                        global_transform
+                            .transform_point(&Point3::from(particle.position))
+                            .coords
+                    };
+
+                    let velocity = if is_world_space {
+                        particle.velocity
+                    } else {
+                        global_transform.transform_vector(&particle.velocity)
+                    };
+                    let stretch = velocity.norm() * velocity_stretch;
+
+                    let axis = match orientation_mode {
+                        // Stretched billboards are elongated along their velocity.
+                        ParticleOrientationMode::Billboard if stretch > 0.0 => velocity,
+                        ParticleOrientationMode::Billboard => Vector3::default(),
+                        ParticleOrientationMode::VelocityAligned => velocity,
+                        ParticleOrientationMode::AxisAligned => system_axis,
+                    };
+
+                    let frame = match sprite_sheet.as_ref() {
+                        Some(sprite_sheet) => {
+                            let rect = sprite_sheet.frame_uv_rect(sprite_sheet.frame(particle));
+                            Vector4::new(rect.position.x, rect.position.y, rect.size.x, rect.size.y)
+                        }
+                        None => Vector4::new(0.0, 0.0, 1.0, 1.0),
+                    };
 
                     [
                         Vertex {
@@ -532,6 +1951,11 @@ impl NodeTrait for ParticleSystem {
                             size: particle.size,
                             rotation: particle.rotation,
                             color: particle.color,
+                            axis,
+                            stretch,
+                            custom: particle.custom,
+                            frame,
+                            min_screen_size,
                         },
                         Vertex {
                             position,
@@ -539,6 +1963,11 @@ impl NodeTrait for ParticleSystem {
                             size: particle.size,
                             rotation: particle.rotation,
                             color: particle.color,
+                            axis,
+                            stretch,
+                            custom: particle.custom,
+                            frame,
+                            min_screen_size,
                         },
                         Vertex {
                             position,
@@ -546,6 +1975,11 @@ impl NodeTrait for ParticleSystem {
                             size: particle.size,
                             rotation: particle.rotation,
                             color: particle.color,
+                            axis,
+                            stretch,
+                            custom: particle.custom,
+                            frame,
+                            min_screen_size,
                         },
                         Vertex {
                             position,
@@ -553,6 +1987,11 @@ impl NodeTrait for ParticleSystem {
                             size: particle.size,
                             rotation: particle.rotation,
                             color: particle.color,
+                            axis,
+                            stretch,
+                            custom: particle.custom,
+                            frame,
+                            min_screen_size,
                         },
                     ]
                 });
@@ -593,6 +2032,19 @@ pub struct ParticleSystemBuilder {
     color_over_lifetime: ColorGradient,
     is_playing: bool,
     rng: ParticleSystemRng,
+    simulation_space: SimulationSpace,
+    prewarm: bool,
+    prewarm_duration: Option<f32>,
+    particle_capacity: Option<u32>,
+    orientation_mode: ParticleOrientationMode,
+    velocity_stretch: f32,
+    collision_plane: Option<ParticleCollisionPlane>,
+    confinement: Option<ParticleConfinement>,
+    sort_mode: ParticleSortMode,
+    sprite_sheet: Option<ParticleSpriteSheet>,
+    min_screen_size: f32,
+    attractors: Vec<ParticleAttractor>,
+    turbulence: ParticleTurbulence,
 }
 
 impl ParticleSystemBuilder {
@@ -610,6 +2062,19 @@ impl ParticleSystemBuilder {
             color_over_lifetime: Default::default(),
             is_playing: true,
             rng: ParticleSystemRng::default(),
+            simulation_space: Default::default(),
+            prewarm: false,
+            prewarm_duration: None,
+            particle_capacity: None,
+            orientation_mode: Default::default(),
+            velocity_stretch: 0.0,
+            collision_plane: None,
+            confinement: None,
+            sort_mode: Default::default(),
+            sprite_sheet: None,
+            min_screen_size: 0.0,
+            attractors: Default::default(),
+            turbulence: Default::default(),
         }
     }
 
@@ -656,6 +2121,92 @@ impl ParticleSystemBuilder {
         self
     }
 
+    /// Sets desired simulation space.
+    pub fn with_simulation_space(mut self, space: SimulationSpace) -> Self {
+        self.simulation_space = space;
+        self
+    }
+
+    /// Sets whether the particle system should be prewarmed or not.
+    pub fn with_prewarm(mut self, prewarm: bool) -> Self {
+        self.prewarm = prewarm;
+        self
+    }
+
+    /// Sets desired prewarm duration.
+    pub fn with_prewarm_duration(mut self, duration: Option<f32>) -> Self {
+        self.prewarm_duration = duration;
+        self
+    }
+
+    /// Sets the maximum amount of particles that could exist in the particle system at the same
+    /// time.
+    pub fn with_particle_capacity(mut self, capacity: Option<u32>) -> Self {
+        self.particle_capacity = capacity;
+        self
+    }
+
+    /// Sets desired orientation mode of particles.
+    pub fn with_orientation_mode(mut self, orientation_mode: ParticleOrientationMode) -> Self {
+        self.orientation_mode = orientation_mode;
+        self
+    }
+
+    /// Sets desired velocity stretch factor of particles. See
+    /// [`ParticleSystem::set_velocity_stretch`] for more info.
+    pub fn with_velocity_stretch(mut self, factor: f32) -> Self {
+        self.velocity_stretch = factor.max(0.0);
+        self
+    }
+
+    /// Sets desired collision plane of the particle system. See
+    /// [`ParticleSystem::set_collision_plane`] for more info.
+    pub fn with_collision_plane(mut self, collision_plane: ParticleCollisionPlane) -> Self {
+        self.collision_plane = Some(collision_plane);
+        self
+    }
+
+    /// Sets desired confinement box of the particle system. See
+    /// [`ParticleSystem::set_confinement`] for more info.
+    pub fn with_confinement(mut self, confinement: ParticleConfinement) -> Self {
+        self.confinement = Some(confinement);
+        self
+    }
+
+    /// Sets desired sort mode of particles. See [`ParticleSortMode`] docs for more info.
+    pub fn with_sort_mode(mut self, sort_mode: ParticleSortMode) -> Self {
+        self.sort_mode = sort_mode;
+        self
+    }
+
+    /// Sets desired sprite sheet animation of particles. See [`ParticleSystem::set_sprite_sheet`]
+    /// for more info.
+    pub fn with_sprite_sheet(mut self, sprite_sheet: ParticleSpriteSheet) -> Self {
+        self.sprite_sheet = Some(sprite_sheet);
+        self
+    }
+
+    /// Sets desired minimum size (in pixels) of particles on screen. See
+    /// [`ParticleSystem::set_min_screen_size`] for more info.
+    pub fn with_min_screen_size(mut self, size: f32) -> Self {
+        self.min_screen_size = size.max(0.0);
+        self
+    }
+
+    /// Sets desired set of attractors of the particle system. See
+    /// [`ParticleSystem::set_attractors`] for more info.
+    pub fn with_attractors(mut self, attractors: Vec<ParticleAttractor>) -> Self {
+        self.attractors = attractors;
+        self
+    }
+
+    /// Sets desired turbulence of the particle system. See [`ParticleSystem::set_turbulence`] for
+    /// more info.
+    pub fn with_turbulence(mut self, turbulence: ParticleTurbulence) -> Self {
+        self.turbulence = turbulence;
+        self
+    }
+
     fn build_particle_system(self) -> ParticleSystem {
         ParticleSystem {
             base: self.base_builder.build_base(),
@@ -666,7 +2217,26 @@ impl ParticleSystemBuilder {
             acceleration: self.acceleration.into(),
             color_over_lifetime: self.color_over_lifetime.into(),
             is_playing: self.is_playing.into(),
+            simulation_space: self.simulation_space.into(),
+            prewarm: self.prewarm.into(),
+            prewarm_duration: self.prewarm_duration.into(),
+            particle_capacity: self.particle_capacity.into(),
+            orientation_mode: self.orientation_mode.into(),
+            velocity_stretch: self.velocity_stretch.into(),
+            collision_plane: self.collision_plane.into(),
+            confinement: self.confinement.into(),
+            sort_mode: self.sort_mode.into(),
+            sprite_sheet: self.sprite_sheet.into(),
+            min_screen_size: self.min_screen_size.into(),
+            attractors: self.attractors.into(),
+            turbulence: self.turbulence.into(),
             rng: self.rng,
+            age: 0.0,
+            is_emitting: true,
+            is_prewarmed: false,
+            pool_stats: Default::default(),
+            collision_hits: Default::default(),
+            last_global_position: None,
         }
     }
 
@@ -680,3 +2250,1104 @@ impl ParticleSystemBuilder {
         graph.add_node(self.build_node())
     }
 }
+
+#[cfg(test)]
+mod test {
+    use crate::{
+        asset::manager::ResourceManager,
+        core::{
+            algebra::{Matrix4, UnitQuaternion, Vector2, Vector3},
+            math::{
+                aabb::AxisAlignedBoundingBox,
+                curve::{Curve, CurveKey, CurveKeyKind},
+            },
+            reflect::prelude::*,
+            visitor::prelude::*,
+        },
+        rand::{Error, RngCore, SeedableRng},
+        scene::{
+            animation::prelude::*,
+            base::BaseBuilder,
+            graph::Graph,
+            mesh::MeshBuilder,
+            node::NodeTrait,
+            particle_system::{
+                emitter::{
+                    base::{
+                        BaseEmitter, BaseEmitterBuilder, EmissionMode, EmissionVolume,
+                        ParticleCustomData,
+                    },
+                    cylinder::{CylinderAxis, CylinderEmitterBuilder},
+                    sphere::SphereEmitterBuilder,
+                    torus::TorusEmitterBuilder,
+                    Emitter, EmitterKind,
+                },
+                particle::Particle,
+                turbulence::ParticleTurbulence,
+                ParticleAttractor, ParticleAttractorFalloff, ParticleCollisionPlane,
+                ParticleCollisionResponse, ParticleConfinement, ParticleConfinementResponse,
+                ParticlePoolStats, ParticleQueryVolume, ParticleSortMode, ParticleSpriteSheet,
+                ParticleSystem, ParticleSystemBuilder, ParticleSystemRng, SpriteSheetPlayback,
+            },
+        },
+    };
+    use std::sync::Arc;
+
+    #[test]
+    fn test_animate_spawn_rate() {
+        let mut node = ParticleSystemBuilder::new(BaseBuilder::new())
+            .with_emitters(vec![SphereEmitterBuilder::new(
+                BaseEmitterBuilder::new().with_spawn_rate(0),
+            )
+            .build()])
+            .build_node();
+
+        let mut frames = TrackDataContainer::new(TrackValueKind::Real);
+        frames.curves_mut()[0] = Curve::from(vec![
+            CurveKey::new(0.0, 0.0, CurveKeyKind::Linear),
+            CurveKey::new(1.0, 100.0, CurveKeyKind::Linear),
+        ]);
+        let track = Track::new(
+            frames,
+            ValueBinding::Property {
+                name: "emitters[0].Sphere@0.emitter.particle_spawn_rate".to_string(),
+                value_type: ValueType::U32,
+            },
+        );
+
+        let apply = |node: &mut crate::scene::node::Node, time: f32| {
+            let mut values = BoundValueCollection::default();
+            values.insert(track.fetch(time).unwrap());
+            assert!(values.validate(node).is_empty());
+            values.apply(node);
+        };
+
+        apply(&mut node, 0.0);
+        let particle_system = node.cast_mut::<ParticleSystem>().unwrap();
+        assert_eq!(particle_system.emitters[0].spawn_rate(), 0);
+        particle_system.tick(0.5);
+        assert_eq!(particle_system.particles_emitted_last_tick(), 0);
+
+        apply(&mut node, 1.0);
+        let particle_system = node.cast_mut::<ParticleSystem>().unwrap();
+        assert_eq!(particle_system.emitters[0].spawn_rate(), 100);
+        // The new rate is used on the next update.
+        particle_system.tick(0.5);
+        assert_eq!(particle_system.particles_emitted_last_tick(), 50);
+    }
+
+    #[test]
+    fn test_emitter_groups() {
+        let emitter = |group: &str| {
+            SphereEmitterBuilder::new(
+                BaseEmitterBuilder::new()
+                    .with_spawn_rate(10)
+                    .with_group(group),
+            )
+            .build()
+        };
+
+        let mut node = ParticleSystemBuilder::new(BaseBuilder::new())
+            .with_emitters(vec![emitter("smoke"), emitter("fire"), emitter("smoke")])
+            .build_node();
+        let particle_system = node.cast_mut::<ParticleSystem>().unwrap();
+
+        assert_eq!(particle_system.emitters_in_group("smoke").count(), 2);
+        assert!(particle_system
+            .emitters_with_kinds()
+            .all(|(kind, _)| kind == EmitterKind::Sphere));
+        assert_eq!(particle_system.set_group_enabled("smoke", false), 2);
+        assert_eq!(particle_system.set_group_enabled("sparks", false), 0);
+        assert!(!particle_system.emitters[0].is_enabled());
+        assert!(particle_system.emitters[1].is_enabled());
+
+        particle_system.tick(1.0);
+        assert_eq!(particle_system.particles_emitted_last_tick(), 10);
+
+        particle_system.set_group_enabled("smoke", true);
+        particle_system.tick(1.0);
+        assert_eq!(particle_system.particles_emitted_last_tick(), 30);
+    }
+
+    #[test]
+    fn test_pause_emitter() {
+        let mut emitter = BaseEmitterBuilder::new().with_spawn_rate(30).build();
+
+        emitter.tick(1.0 / 40.0);
+        assert_eq!(emitter.particles_to_spawn, 0);
+
+        // Paused emitter keeps the fractional 0.75 of a particle.
+        emitter.pause();
+        assert!(emitter.is_paused());
+        for _ in 0..10 {
+            emitter.tick(1.0 / 40.0);
+            assert_eq!(emitter.particles_to_spawn, 0);
+        }
+        emitter.resume();
+        emitter.tick(1.0 / 40.0);
+        assert_eq!(emitter.particles_to_spawn, 1);
+
+        // Disabled emitter discards it.
+        emitter.tick(1.0 / 40.0);
+        emitter.set_enabled(false);
+        emitter.tick(1.0 / 40.0);
+        emitter.set_enabled(true);
+        emitter.tick(1.0 / 40.0);
+        assert_eq!(emitter.particles_to_spawn, 0);
+
+        emitter.pause();
+        let mut visitor = Visitor::new();
+        emitter.visit("Emitter", &mut visitor).unwrap();
+        let data = visitor.save_binary_to_vec().unwrap();
+        let mut visitor = Visitor::load_from_memory(&data).unwrap();
+        let mut loaded = BaseEmitter::default();
+        loaded.visit("Emitter", &mut visitor).unwrap();
+        assert!(loaded.is_paused());
+    }
+
+    #[test]
+    fn test_particle_capacity() {
+        let mut node = ParticleSystemBuilder::new(BaseBuilder::new())
+            .with_emitters(vec![SphereEmitterBuilder::new(
+                BaseEmitterBuilder::new()
+                    .with_spawn_rate(10)
+                    .with_lifetime_range(0.5..0.6),
+            )
+            .build()])
+            .with_particle_capacity(Some(4))
+            .build_node();
+        let particle_system = node.cast_mut::<ParticleSystem>().unwrap();
+
+        // The particles die at the same tick they were emitted.
+        particle_system.tick(1.0);
+        assert_eq!(particle_system.particles().len(), 4);
+        assert_eq!(
+            *particle_system.pool_stats(),
+            ParticlePoolStats {
+                high_water_mark: 4,
+                recycled: 0,
+                dropped: 6,
+            }
+        );
+
+        particle_system.tick(1.0);
+        assert_eq!(particle_system.particles().len(), 4);
+        assert_eq!(
+            *particle_system.pool_stats(),
+            ParticlePoolStats {
+                high_water_mark: 4,
+                recycled: 4,
+                dropped: 12,
+            }
+        );
+
+        particle_system.reset_pool_stats();
+        assert_eq!(*particle_system.pool_stats(), Default::default());
+    }
+
+    #[test]
+    fn test_cylinder_emitter_axis() {
+        let mut node = ParticleSystemBuilder::new(BaseBuilder::new())
+            .with_emitters(vec![CylinderEmitterBuilder::new(
+                BaseEmitterBuilder::new().with_spawn_rate(100),
+            )
+            .with_radius(0.5)
+            .with_height(10.0)
+            .with_axis(CylinderAxis::Y)
+            .build()])
+            .with_acceleration(Default::default())
+            .build_node();
+        let particle_system = node.cast_mut::<ParticleSystem>().unwrap();
+        particle_system.tick(1.0);
+
+        assert!(!particle_system.particles().is_empty());
+        for particle in particle_system.particles() {
+            let p = particle.position;
+            assert!(p.y >= 0.0 && p.y <= 10.0);
+            assert!((p.x * p.x + p.z * p.z).sqrt() <= 0.5);
+        }
+        assert!(particle_system
+            .particles()
+            .iter()
+            .any(|particle| particle.position.y > 1.0));
+    }
+
+    #[test]
+    fn test_cylinder_emitter_arc() {
+        let mut node = ParticleSystemBuilder::new(BaseBuilder::new())
+            .with_emitters(vec![CylinderEmitterBuilder::new(
+                BaseEmitterBuilder::new().with_spawn_rate(100),
+            )
+            .with_radius(1.0)
+            .with_height(0.0)
+            .with_arc(0.0, std::f32::consts::FRAC_PI_2)
+            .build()])
+            .with_acceleration(Default::default())
+            .build_node();
+        let particle_system = node.cast_mut::<ParticleSystem>().unwrap();
+        particle_system.tick(1.0);
+
+        assert!(!particle_system.particles().is_empty());
+        for particle in particle_system.particles() {
+            assert!(particle.position.x >= 0.0 && particle.position.y >= 0.0);
+        }
+
+        // Density normalization takes the arc into account.
+        let full = CylinderEmitterBuilder::new(BaseEmitterBuilder::new()).build();
+        let quarter = CylinderEmitterBuilder::new(BaseEmitterBuilder::new())
+            .with_arc(0.0, std::f32::consts::FRAC_PI_2)
+            .build();
+        let (Emitter::Cylinder(full), Emitter::Cylinder(quarter)) = (full, quarter) else {
+            unreachable!()
+        };
+        assert!((quarter.volume() * 4.0 - full.volume()).abs() < 1.0e-5);
+
+        // Non-finite angles are replaced with the angles of the full circle.
+        let Emitter::Cylinder(mut invalid) = CylinderEmitterBuilder::new(BaseEmitterBuilder::new())
+            .with_arc(f32::NAN, f32::INFINITY)
+            .build()
+        else {
+            unreachable!()
+        };
+        assert_eq!(invalid.start_angle(), 0.0);
+        assert_eq!(invalid.end_angle(), 2.0 * std::f32::consts::PI);
+        invalid.set_arc(1.0, f32::NEG_INFINITY);
+        assert_eq!(invalid.start_angle(), 1.0);
+        assert_eq!(invalid.end_angle(), 2.0 * std::f32::consts::PI);
+    }
+
+    #[test]
+    fn test_emitter_sample_positions() {
+        let emitter = SphereEmitterBuilder::new(
+            BaseEmitterBuilder::new().with_position(Vector3::new(1.0, 0.0, 0.0)),
+        )
+        .with_radius(0.5)
+        .build();
+
+        let positions = emitter.sample_positions(32, 7);
+        assert_eq!(positions.len(), 32);
+        assert_eq!(positions, emitter.sample_positions(32, 7));
+        assert_ne!(positions, emitter.sample_positions(32, 8));
+
+        let bounds = emitter.emission_bounds();
+        assert!(positions
+            .iter()
+            .all(|position| bounds.is_contains_point(*position)));
+    }
+
+    #[test]
+    fn test_prewarm() {
+        let mut graph = Graph::new();
+        let particle_system = ParticleSystemBuilder::new(BaseBuilder::new())
+            .with_emitters(vec![SphereEmitterBuilder::new(
+                BaseEmitterBuilder::new()
+                    .with_spawn_rate(10)
+                    .with_lifetime_range(2.0..2.0),
+            )
+            .build()])
+            .with_prewarm(true)
+            .build(&mut graph);
+        assert_eq!(
+            graph[particle_system]
+                .cast::<ParticleSystem>()
+                .unwrap()
+                .alive_particles_count(),
+            0
+        );
+
+        // The lifetime of the particles is simulated on the first update, so the effect is in its
+        // steady state right away.
+        graph.update(Vector2::new(800.0, 600.0), 1.0 / 60.0, Default::default());
+        let particle_system = graph[particle_system].cast_mut::<ParticleSystem>().unwrap();
+        assert!(particle_system.is_prewarmed);
+        assert!(particle_system.alive_particles_count() >= 15);
+
+        let mut visitor = Visitor::new();
+        particle_system
+            .visit("ParticleSystem", &mut visitor)
+            .unwrap();
+        let data = visitor.save_binary_to_vec().unwrap();
+
+        let mut visitor = Visitor::load_from_memory(&data).unwrap();
+        visitor
+            .blackboard
+            .register(Arc::new(ResourceManager::new(Arc::new(Default::default()))));
+        let mut loaded = ParticleSystemBuilder::new(BaseBuilder::new()).build_particle_system();
+        loaded.visit("ParticleSystem", &mut visitor).unwrap();
+        assert!(loaded.is_prewarmed);
+        assert_eq!(
+            loaded.alive_particles_count(),
+            particle_system.alive_particles_count()
+        );
+    }
+
+    #[test]
+    fn test_node_bounds_of_empty_mesh() {
+        let mut graph = Graph::new();
+        let particle_system = ParticleSystemBuilder::new(BaseBuilder::new())
+            .with_acceleration(Default::default())
+            .with_emitters(vec![SphereEmitterBuilder::new(
+                BaseEmitterBuilder::new()
+                    .with_spawn_rate(100)
+                    .with_lifetime_range(10.0..10.0)
+                    .with_x_velocity_range(0.0..0.0)
+                    .with_y_velocity_range(0.0..0.0)
+                    .with_z_velocity_range(0.0..0.0)
+                    .with_emission_volume(EmissionVolume::NodeBoundsSurface),
+            )
+            .with_radius(0.5)
+            .build()])
+            .build(&mut graph);
+        // The mesh has no surfaces, so its bounds are invalid and the shape of the emitter is used.
+        MeshBuilder::new(BaseBuilder::new().with_children(&[particle_system])).build(&mut graph);
+
+        graph.update(Vector2::new(800.0, 600.0), 1.0, Default::default());
+
+        let particle_system = graph[particle_system].cast::<ParticleSystem>().unwrap();
+        assert!(particle_system.emitters[0].node_bounds.is_none());
+        assert!(particle_system.alive_particles_count() > 0);
+        assert!(particle_system
+            .particles()
+            .iter()
+            .filter(|particle| particle.alive)
+            .all(|particle| particle.position.norm() <= 0.5 + 1.0e-5));
+    }
+
+    #[test]
+    fn test_emission_bounds() {
+        let mut node = ParticleSystemBuilder::new(BaseBuilder::new())
+            .with_emitters(vec![
+                SphereEmitterBuilder::new(
+                    BaseEmitterBuilder::new().with_position(Vector3::new(1.0, 0.0, 0.0)),
+                )
+                .with_radius(0.5)
+                .build(),
+                CylinderEmitterBuilder::new(BaseEmitterBuilder::new())
+                    .with_radius(1.0)
+                    .with_height(2.0)
+                    .with_axis(CylinderAxis::Y)
+                    .build(),
+            ])
+            .build_node();
+        let particle_system = node.cast_mut::<ParticleSystem>().unwrap();
+
+        let sphere_bounds = particle_system.emitters[0].emission_bounds();
+        assert_eq!(sphere_bounds.min, Vector3::new(0.5, -0.5, -0.5));
+        assert_eq!(sphere_bounds.max, Vector3::new(1.5, 0.5, 0.5));
+
+        // The cylinder is rotated around X axis, so its height goes along Z axis.
+        particle_system.emitters[1].set_orientation(UnitQuaternion::from_euler_angles(
+            std::f32::consts::FRAC_PI_2,
+            0.0,
+            0.0,
+        ));
+        let cylinder_bounds = particle_system.emitters[1].emission_bounds();
+        assert!((cylinder_bounds.min - Vector3::new(-1.0, -1.0, 0.0)).norm() < 1.0e-5);
+        assert!((cylinder_bounds.max - Vector3::new(1.0, 1.0, 2.0)).norm() < 1.0e-5);
+
+        let bounds = particle_system.emission_bounds();
+        assert!((bounds.min - Vector3::new(-1.0, -1.0, -0.5)).norm() < 1.0e-5);
+        assert!((bounds.max - Vector3::new(1.5, 1.0, 2.0)).norm() < 1.0e-5);
+
+        // Live particles extend the bounds of the node.
+        particle_system.particles.push(Particle {
+            position: Vector3::new(5.0, 0.0, 0.0),
+            size: 0.5,
+            initial_lifetime: 1.0,
+            ..Default::default()
+        });
+        let local_bounds = particle_system.local_bounding_box();
+        assert!((local_bounds.max - Vector3::new(5.5, 1.0, 2.0)).norm() < 1.0e-5);
+    }
+
+    #[test]
+    fn test_emitter_max_particles() {
+        let mut node = ParticleSystemBuilder::new(BaseBuilder::new())
+            .with_emitters(vec![
+                SphereEmitterBuilder::new(
+                    BaseEmitterBuilder::new()
+                        .with_spawn_rate(1000)
+                        .with_max_particles(5)
+                        .with_lifetime_range(100.0..101.0),
+                )
+                .build(),
+                SphereEmitterBuilder::new(
+                    BaseEmitterBuilder::new()
+                        .with_spawn_rate(2)
+                        .with_lifetime_range(100.0..101.0),
+                )
+                .build(),
+            ])
+            .with_particle_capacity(Some(20))
+            .build_node();
+        let particle_system = node.cast_mut::<ParticleSystem>().unwrap();
+
+        for i in 1..=3 {
+            particle_system.tick(1.0);
+            assert_eq!(particle_system.emitters[0].alive_particles(), 5);
+            // The greedy emitter does not starve the other one.
+            assert_eq!(particle_system.emitters[1].alive_particles(), 2 * i);
+        }
+        assert_eq!(particle_system.pool_stats().dropped, 0);
+    }
+
+    #[test]
+    fn test_emit_at() {
+        let mut node = ParticleSystemBuilder::new(BaseBuilder::new())
+            .with_emitters(vec![SphereEmitterBuilder::new(
+                BaseEmitterBuilder::new()
+                    .with_spawn_rate(0)
+                    .with_lifetime_range(10.0..11.0),
+            )
+            .build()])
+            .with_particle_capacity(Some(5))
+            .build_node();
+        let particle_system = node.cast_mut::<ParticleSystem>().unwrap();
+
+        let point = crate::core::algebra::Vector3::new(1.0, 2.0, 3.0);
+        assert_eq!(particle_system.emit_at(0, point, 3), 3);
+        assert_eq!(particle_system.emit_at(1, point, 3), 0);
+        assert!(particle_system
+            .particles()
+            .iter()
+            .all(|particle| particle.position == point));
+        assert_eq!(particle_system.emitters[0].alive_particles(), 3);
+
+        // The rest is dropped because of limited capacity.
+        assert_eq!(particle_system.emit_at(0, point, 3), 2);
+        assert_eq!(particle_system.pool_stats().dropped, 1);
+    }
+
+    #[test]
+    fn test_density_normalization() {
+        let emitted = |radius: f32, normalize_density: bool| {
+            let mut node = ParticleSystemBuilder::new(BaseBuilder::new())
+                .with_emitters(vec![CylinderEmitterBuilder::new(
+                    BaseEmitterBuilder::new()
+                        .with_spawn_rate(10)
+                        .with_density_normalization(normalize_density),
+                )
+                .with_radius(radius)
+                .with_height(1.0 / std::f32::consts::PI)
+                .build()])
+                .build_node();
+            let particle_system = node.cast_mut::<ParticleSystem>().unwrap();
+            particle_system.tick(1.0);
+            particle_system.particles_emitted_last_tick()
+        };
+
+        assert_eq!(emitted(1.0, false), 10);
+        assert_eq!(emitted(2.0, false), 10);
+        // The volume is not exactly 1.0 due to limited precision of PI.
+        assert!(emitted(1.0, true).abs_diff(10) <= 1);
+        assert!(emitted(2.0, true).abs_diff(40) <= 1);
+    }
+
+    #[test]
+    fn test_emission_accumulation() {
+        let mut node = ParticleSystemBuilder::new(BaseBuilder::new())
+            .with_emitters(vec![SphereEmitterBuilder::new(
+                BaseEmitterBuilder::new().with_spawn_rate(3),
+            )
+            .build()])
+            .build_node();
+        let particle_system = node.cast_mut::<ParticleSystem>().unwrap();
+
+        // Less than one particle per frame, truncation would never emit anything.
+        let dt = 1.0 / 240.0;
+        let mut total = 0;
+        for _ in 0..2400 {
+            particle_system.tick(dt);
+            total += particle_system.particles_emitted_last_tick();
+        }
+
+        assert!(total.abs_diff(30) <= 1);
+    }
+
+    #[test]
+    fn test_collision_plane() {
+        let simulate = |response: ParticleCollisionResponse| {
+            let mut particle_system = ParticleSystemBuilder::new(BaseBuilder::new())
+                .with_acceleration(Default::default())
+                .with_emitters(vec![SphereEmitterBuilder::new(
+                    BaseEmitterBuilder::new()
+                        .with_position(Vector3::new(0.0, 1.0, 0.0))
+                        .with_spawn_rate(1)
+                        .with_max_particles(1)
+                        .resurrect_particles(false)
+                        .with_lifetime_range(100.0..100.0)
+                        .with_x_velocity_range(0.0..0.0)
+                        .with_y_velocity_range(-0.1..-0.1)
+                        .with_z_velocity_range(0.0..0.0),
+                )
+                .with_radius(0.0)
+                .build()])
+                .with_collision_plane(ParticleCollisionPlane {
+                    response,
+                    ..Default::default()
+                })
+                .build_particle_system();
+            for _ in 0..20 {
+                particle_system.tick(1.0);
+            }
+            particle_system
+                .particles()
+                .iter()
+                .find(|particle| particle.alive)
+                .cloned()
+        };
+
+        assert!(simulate(ParticleCollisionResponse::Kill).is_none());
+
+        let bounced = simulate(ParticleCollisionResponse::Bounce).unwrap();
+        assert!(bounced.position.y >= 0.0);
+        assert!((bounced.velocity.y - 0.05).abs() < 1.0e-5);
+    }
+
+    #[test]
+    fn test_collision_hits() {
+        let mut particle_system = ParticleSystemBuilder::new(BaseBuilder::new())
+            .with_acceleration(Default::default())
+            .with_particles(vec![Particle {
+                initial_lifetime: 100.0,
+                velocity: Vector3::new(0.0, -0.1, 0.0),
+                ..Particle::default().with_position(Vector3::new(0.5, 0.05, 0.0))
+            }])
+            .with_collision_plane(ParticleCollisionPlane::default())
+            .build_particle_system();
+
+        particle_system.tick(1.0);
+        assert_eq!(particle_system.collision_hits().len(), 1);
+        assert!(
+            (particle_system.collision_hits()[0] - Vector3::new(0.5, 0.0, 0.0)).norm() < 1.0e-5
+        );
+
+        assert_eq!(particle_system.take_collision_hits().len(), 1);
+        assert!(particle_system.collision_hits().is_empty());
+
+        // The particle is killed by the plane, so there are no more hits.
+        particle_system.tick(1.0);
+        assert!(particle_system.collision_hits().is_empty());
+    }
+
+    #[test]
+    fn test_confinement() {
+        let simulate = |response: ParticleConfinementResponse| {
+            let mut particle_system = ParticleSystemBuilder::new(BaseBuilder::new())
+                .with_acceleration(Default::default())
+                .with_particles(vec![Particle {
+                    initial_lifetime: 100.0,
+                    velocity: Vector3::new(0.3, 0.1, 0.0),
+                    ..Particle::default().with_position(Vector3::new(0.9, 0.0, 0.0))
+                }])
+                .with_confinement(ParticleConfinement {
+                    response,
+                    ..Default::default()
+                })
+                .build_particle_system();
+            particle_system.tick(1.0);
+            particle_system
+                .particles()
+                .iter()
+                .find(|particle| particle.alive)
+                .cloned()
+        };
+
+        assert!(simulate(ParticleConfinementResponse::Kill).is_none());
+
+        let clamped = simulate(ParticleConfinementResponse::Clamp).unwrap();
+        assert!((clamped.position - Vector3::new(1.0, 0.1, 0.0)).norm() < 1.0e-5);
+        assert!((clamped.velocity - Vector3::new(0.0, 0.1, 0.0)).norm() < 1.0e-5);
+
+        let reflected = simulate(ParticleConfinementResponse::Reflect).unwrap();
+        assert!((reflected.position - Vector3::new(0.8, 0.1, 0.0)).norm() < 1.0e-5);
+        assert!((reflected.velocity - Vector3::new(-0.3, 0.1, 0.0)).norm() < 1.0e-5);
+    }
+
+    #[test]
+    fn test_collision_plane_friction() {
+        let plane = ParticleCollisionPlane {
+            response: ParticleCollisionResponse::Bounce,
+            restitution: 0.5,
+            friction: 0.25,
+            ..Default::default()
+        };
+
+        let mut particle = Particle {
+            position: Vector3::new(0.0, -0.1, 0.0),
+            velocity: Vector3::new(1.0, -1.0, 0.0),
+            ..Default::default()
+        };
+        assert!(!plane.collide(&mut particle));
+        assert!((particle.position.y - 0.05).abs() < 1.0e-5);
+        assert!((particle.velocity - Vector3::new(0.75, 0.5, 0.0)).norm() < 1.0e-5);
+
+        // Out of range values are clamped.
+        let plane = ParticleCollisionPlane {
+            restitution: 2.0,
+            friction: 5.0,
+            ..plane
+        };
+        let mut particle = Particle {
+            position: Vector3::new(0.0, -0.1, 0.0),
+            velocity: Vector3::new(1.0, -1.0, 0.0),
+            ..Default::default()
+        };
+        plane.collide(&mut particle);
+        assert!((particle.velocity - Vector3::new(0.0, 1.0, 0.0)).norm() < 1.0e-5);
+    }
+
+    #[test]
+    fn test_attractors() {
+        let simulate = |attractors: Vec<ParticleAttractor>| {
+            let mut particle_system = ParticleSystemBuilder::new(BaseBuilder::new())
+                .with_acceleration(Default::default())
+                .with_particles(vec![Particle {
+                    initial_lifetime: 100.0,
+                    ..Particle::default().with_position(Vector3::new(1.0, 0.0, 0.0))
+                }])
+                .with_attractors(attractors)
+                .build_particle_system();
+            particle_system.tick(0.1);
+            particle_system.particles()[0].velocity
+        };
+
+        let attractor = ParticleAttractor {
+            strength: 10.0,
+            falloff: ParticleAttractorFalloff::Constant,
+            ..Default::default()
+        };
+        let repulsor = ParticleAttractor {
+            strength: -10.0,
+            ..attractor.clone()
+        };
+
+        let velocity = simulate(vec![attractor.clone()]);
+        assert!((velocity - Vector3::new(-0.1, 0.0, 0.0)).norm() < 1.0e-5);
+        let velocity = simulate(vec![repulsor.clone()]);
+        assert!((velocity - Vector3::new(0.1, 0.0, 0.0)).norm() < 1.0e-5);
+        // Forces of multiple attractors are summed.
+        assert!(simulate(vec![attractor.clone(), repulsor]).norm() < 1.0e-5);
+        // Particles outside of the radius are not affected.
+        let velocity = simulate(vec![ParticleAttractor {
+            radius: 0.5,
+            ..attractor.clone()
+        }]);
+        assert_eq!(velocity, Vector3::default());
+
+        let linear = ParticleAttractor {
+            radius: 2.0,
+            falloff: ParticleAttractorFalloff::Linear,
+            ..attractor.clone()
+        };
+        assert_eq!(
+            linear.acceleration_at(Vector3::new(1.0, 0.0, 0.0)),
+            Vector3::new(-5.0, 0.0, 0.0)
+        );
+        let inverse_square = ParticleAttractor {
+            falloff: ParticleAttractorFalloff::InverseSquare,
+            ..attractor
+        };
+        assert_eq!(
+            inverse_square.acceleration_at(Vector3::new(0.0, 2.0, 0.0)),
+            Vector3::new(0.0, -2.5, 0.0)
+        );
+    }
+
+    #[test]
+    fn test_particles_in_volume() {
+        let particle = |x: f32| Particle {
+            initial_lifetime: 100.0,
+            ..Particle::default().with_position(Vector3::new(x, 0.0, 0.0))
+        };
+        let mut particle_system = ParticleSystemBuilder::new(BaseBuilder::new())
+            .with_particles(vec![particle(0.0), particle(1.0), particle(5.0)])
+            .build_particle_system();
+        particle_system
+            .base
+            .global_transform
+            .set(Matrix4::new_translation(&Vector3::new(0.0, 10.0, 0.0)));
+
+        // Particles are simulated in local space, but the volume is in world space.
+        let sphere = ParticleQueryVolume::Sphere {
+            center: Vector3::new(0.0, 10.0, 0.0),
+            radius: 2.0,
+        };
+        assert_eq!(
+            particle_system.particles_in_volume(&sphere),
+            vec![
+                (0, Vector3::new(0.0, 10.0, 0.0)),
+                (1, Vector3::new(1.0, 10.0, 0.0))
+            ]
+        );
+        let aabb = ParticleQueryVolume::Box(AxisAlignedBoundingBox::from_min_max(
+            Vector3::new(4.0, 9.0, -1.0),
+            Vector3::new(6.0, 11.0, 1.0),
+        ));
+        assert_eq!(
+            particle_system.particles_in_volume(&aabb),
+            vec![(2, Vector3::new(5.0, 10.0, 0.0))]
+        );
+
+        assert!(particle_system.remove_particle(1));
+        assert!(!particle_system.remove_particle(1));
+        assert!(!particle_system.remove_particle(10));
+        assert_eq!(particle_system.alive_particles_count(), 2);
+        assert_eq!(
+            particle_system.particles_in_volume(&sphere),
+            vec![(0, Vector3::new(0.0, 10.0, 0.0))]
+        );
+    }
+
+    #[test]
+    fn test_turbulence() {
+        let simulate = |turbulence: ParticleTurbulence| {
+            let mut particle_system = ParticleSystemBuilder::new(BaseBuilder::new())
+                .with_acceleration(Default::default())
+                .with_particles(vec![Particle {
+                    initial_lifetime: 100.0,
+                    ..Particle::default().with_position(Vector3::new(0.3, 1.7, -2.1))
+                }])
+                .with_turbulence(turbulence)
+                .build_particle_system();
+            particle_system.tick(0.1);
+            particle_system.particles()[0].velocity
+        };
+
+        assert_eq!(simulate(Default::default()), Vector3::default());
+
+        let turbulence = ParticleTurbulence {
+            frequency: 0.5,
+            strength: 2.0,
+        };
+        let expected = turbulence
+            .acceleration_at(Vector3::new(0.3, 1.7, -2.1), 0.1)
+            .scale(0.1 * 0.1);
+        assert!((simulate(turbulence) - expected).norm() < 1.0e-6);
+    }
+
+    #[test]
+    fn test_sort_mode() {
+        let particle = |z: f32, lifetime: f32| Particle {
+            lifetime,
+            ..Particle::default().with_position(Vector3::new(0.0, 0.0, z))
+        };
+        let mut particle_system = ParticleSystemBuilder::new(BaseBuilder::new())
+            .with_particles(vec![
+                particle(1.0, 0.5),
+                particle(5.0, 0.1),
+                particle(3.0, 0.9),
+            ])
+            .build_particle_system();
+
+        let mut sorted = |sort_mode: ParticleSortMode| {
+            particle_system.set_sort_mode(sort_mode);
+            particle_system.sorted_particles(Vector3::default())
+        };
+        assert_eq!(sorted(ParticleSortMode::None), vec![0, 1, 2]);
+        assert_eq!(sorted(ParticleSortMode::BackToFront), vec![1, 2, 0]);
+        assert_eq!(sorted(ParticleSortMode::OldestFirst), vec![2, 0, 1]);
+    }
+
+    #[test]
+    fn test_emission_per_distance() {
+        let mut node = ParticleSystemBuilder::new(BaseBuilder::new())
+            .with_emitters(vec![SphereEmitterBuilder::new(
+                BaseEmitterBuilder::new()
+                    .with_spawn_rate(1000)
+                    .with_emission_mode(EmissionMode::Distance)
+                    .with_particles_per_meter(4.0),
+            )
+            .build()])
+            .build_node();
+        let particle_system = node.cast_mut::<ParticleSystem>().unwrap();
+
+        let mut move_and_tick = |position: Vector3<f32>| {
+            particle_system
+                .base
+                .global_transform
+                .set(Matrix4::new_translation(&position));
+            particle_system.update_travelled_distance();
+            particle_system.tick(1.0 / 60.0);
+            particle_system.particles_emitted_last_tick()
+        };
+
+        // The first update only remembers the position, stationary system emits nothing.
+        assert_eq!(move_and_tick(Vector3::new(1.0, 0.0, 0.0)), 0);
+        assert_eq!(move_and_tick(Vector3::new(1.0, 0.0, 0.0)), 0);
+
+        // The amount depends only on the distance, not on the speed.
+        assert_eq!(move_and_tick(Vector3::new(3.0, 0.0, 0.0)), 8);
+        let mut total = 0;
+        for i in 1..=40 {
+            total += move_and_tick(Vector3::new(3.0, 0.0, i as f32 * 0.05));
+        }
+        assert!(total.abs_diff(8) <= 1);
+    }
+
+    #[test]
+    fn test_particle_custom_data() {
+        let mut particle_system = ParticleSystemBuilder::new(BaseBuilder::new())
+            .with_emitters(vec![
+                SphereEmitterBuilder::new(
+                    BaseEmitterBuilder::new().with_custom_data(ParticleCustomData::Index),
+                )
+                .build(),
+                SphereEmitterBuilder::new(
+                    BaseEmitterBuilder::new().with_custom_data(ParticleCustomData::Random),
+                )
+                .build(),
+                SphereEmitterBuilder::new(BaseEmitterBuilder::new()).build(),
+            ])
+            .build_particle_system();
+
+        assert_eq!(particle_system.spawn_particles(0, 3, None), 3);
+        assert_eq!(particle_system.spawn_particles(1, 10, None), 10);
+        assert_eq!(particle_system.spawn_particles(2, 1, None), 1);
+
+        let particles = particle_system.particles();
+        let indices = particles[0..3].iter().map(|p| p.custom).collect::<Vec<_>>();
+        assert_eq!(indices, [0.0, 1.0, 2.0]);
+        assert!(particles[3..13]
+            .iter()
+            .all(|p| (0.0..1.0).contains(&p.custom)));
+        assert_eq!(particles[13].custom, 0.0);
+    }
+
+    #[test]
+    fn test_sprite_sheet() {
+        let mut sprite_sheet = ParticleSpriteSheet {
+            columns: 2,
+            rows: 2,
+            ..Default::default()
+        };
+        assert_eq!(sprite_sheet.frame_count(), 4);
+
+        let uv_rect = sprite_sheet.frame_uv_rect(3);
+        assert_eq!(uv_rect.position, Vector2::new(0.5, 0.5));
+        assert_eq!(uv_rect.size, Vector2::new(0.5, 0.5));
+
+        let particle = |lifetime: f32, start_frame: u32| Particle {
+            initial_lifetime: 1.0,
+            lifetime,
+            start_frame,
+            ..Default::default()
+        };
+        assert_eq!(sprite_sheet.frame(&particle(0.0, 0)), 0);
+        assert_eq!(sprite_sheet.frame(&particle(0.6, 0)), 2);
+        assert_eq!(sprite_sheet.frame(&particle(1.0, 0)), 3);
+        assert_eq!(sprite_sheet.frame(&particle(0.6, 3)), 1);
+
+        sprite_sheet.playback = SpriteSheetPlayback::FramesPerSecond;
+        sprite_sheet.frames_per_second = 10.0;
+        assert_eq!(sprite_sheet.frame(&particle(0.25, 0)), 2);
+        assert_eq!(sprite_sheet.frame(&particle(0.15, 3)), 0);
+
+        let mut particle_system = ParticleSystemBuilder::new(BaseBuilder::new())
+            .with_emitters(vec![
+                SphereEmitterBuilder::new(BaseEmitterBuilder::new()).build()
+            ])
+            .with_sprite_sheet(ParticleSpriteSheet {
+                columns: 3,
+                rows: 3,
+                random_start_frame: true,
+                ..Default::default()
+            })
+            .build_particle_system();
+        assert_eq!(particle_system.spawn_particles(0, 32, None), 32);
+        let particles = particle_system.particles();
+        assert!(particles.iter().all(|p| p.start_frame < 9));
+        assert!(particles
+            .iter()
+            .any(|p| p.start_frame != particles[0].start_frame));
+    }
+
+    #[derive(Clone, Debug)]
+    struct CountingRng(u64);
+
+    impl RngCore for CountingRng {
+        fn next_u32(&mut self) -> u32 {
+            self.next_u64() as u32
+        }
+
+        fn next_u64(&mut self) -> u64 {
+            self.0 = self.0.wrapping_add(1);
+            self.0
+        }
+
+        fn fill_bytes(&mut self, dest: &mut [u8]) {
+            for chunk in dest.chunks_mut(8) {
+                let bytes = self.next_u64().to_le_bytes();
+                chunk.copy_from_slice(&bytes[..chunk.len()]);
+            }
+        }
+
+        fn try_fill_bytes(&mut self, dest: &mut [u8]) -> Result<(), Error> {
+            self.fill_bytes(dest);
+            Ok(())
+        }
+    }
+
+    impl SeedableRng for CountingRng {
+        type Seed = [u8; 8];
+
+        fn from_seed(seed: Self::Seed) -> Self {
+            Self(u64::from_le_bytes(seed))
+        }
+
+        fn seed_from_u64(state: u64) -> Self {
+            Self(state)
+        }
+    }
+
+    #[test]
+    fn test_custom_rng_source() {
+        let mut rng = ParticleSystemRng::with_source(5, CountingRng(100));
+        assert_eq!(rng.seed(), 5);
+        assert_eq!(rng.next_u64(), 6);
+        assert_eq!(rng.next_u64(), 7);
+
+        let mut copy = rng.clone();
+        assert_eq!(copy.next_u64(), 8);
+
+        rng.reset();
+        assert_eq!(rng.next_u64(), 6);
+
+        rng.set_source(CountingRng(0));
+        assert_eq!(rng.next_u64(), 6);
+
+        // The same source gives the same particles.
+        let make_particle_system = || {
+            let mut particle_system = ParticleSystemBuilder::new(BaseBuilder::new())
+                .with_emitters(vec![SphereEmitterBuilder::new(
+                    BaseEmitterBuilder::new().with_spawn_rate(10),
+                )
+                .build()])
+                .with_rng(ParticleSystemRng::with_source(1, CountingRng(0)))
+                .build_particle_system();
+            particle_system.tick(1.0);
+            particle_system
+        };
+        let a = make_particle_system();
+        let b = make_particle_system();
+        assert_eq!(a.particles().len(), 10);
+        for (a, b) in a.particles().iter().zip(b.particles()) {
+            assert_eq!(a.position, b.position);
+            assert_eq!(a.initial_lifetime, b.initial_lifetime);
+        }
+    }
+
+    #[test]
+    fn test_repair_invalid_emitter_parameters() {
+        let mut particle_system = ParticleSystemBuilder::new(BaseBuilder::new())
+            .with_emitters(vec![
+                CylinderEmitterBuilder::new(
+                    BaseEmitterBuilder::new().with_lifetime_range(f32::NAN..1.0),
+                )
+                .with_radius(f32::NAN)
+                .with_height(2.0)
+                .build(),
+                SphereEmitterBuilder::new(BaseEmitterBuilder::new())
+                    .with_radius(-1.0)
+                    .build(),
+            ])
+            .build_particle_system();
+
+        let mut visitor = Visitor::new();
+        particle_system
+            .visit("ParticleSystem", &mut visitor)
+            .unwrap();
+        let data = visitor.save_binary_to_vec().unwrap();
+
+        let mut visitor = Visitor::load_from_memory(&data).unwrap();
+        // The material of the particle system is a resource, so it needs a resource manager.
+        visitor
+            .blackboard
+            .register(Arc::new(ResourceManager::new(Arc::new(Default::default()))));
+        let mut loaded = ParticleSystemBuilder::new(BaseBuilder::new()).build_particle_system();
+        loaded.visit("ParticleSystem", &mut visitor).unwrap();
+
+        let Emitter::Cylinder(cylinder) = &loaded.emitters[0] else {
+            unreachable!()
+        };
+        assert_eq!(cylinder.radius(), 0.5);
+        assert_eq!(cylinder.height(), 2.0);
+        assert_eq!(cylinder.life_time_range(), 5.0..10.0);
+        let Emitter::Sphere(sphere) = &loaded.emitters[1] else {
+            unreachable!()
+        };
+        assert_eq!(sphere.radius(), 0.5);
+
+        // Repaired parameters are reported.
+        let mut emitter = CylinderEmitterBuilder::new(BaseEmitterBuilder::new())
+            .with_height(f32::INFINITY)
+            .build();
+        assert_eq!(emitter.repair_invalid_parameters(), vec!["height"]);
+        assert!(emitter.repair_invalid_parameters().is_empty());
+
+        // Non-finite arc of a cylinder emitter (for example, set through reflection) is reset to
+        // the full circle.
+        let mut emitter = CylinderEmitterBuilder::new(BaseEmitterBuilder::new()).build();
+        let Emitter::Cylinder(cylinder) = &mut emitter else {
+            unreachable!()
+        };
+        for (name, angle) in [("start_angle", f32::NAN), ("end_angle", f32::INFINITY)] {
+            cylinder.set_field(name, Box::new(angle), &mut |result| assert!(result.is_ok()));
+        }
+        assert_eq!(
+            emitter.repair_invalid_parameters(),
+            vec!["start_angle", "end_angle"]
+        );
+        let Emitter::Cylinder(cylinder) = &emitter else {
+            unreachable!()
+        };
+        assert_eq!(cylinder.start_angle(), 0.0);
+        assert_eq!(cylinder.end_angle(), 2.0 * std::f32::consts::PI);
+    }
+
+    #[test]
+    fn test_torus_emitter() {
+        let major_radius = 2.0;
+        let minor_radius = 0.5;
+        let tube_distance = |p: crate::core::algebra::Vector3<f32>| {
+            let axis_distance = (p.x * p.x + p.z * p.z).sqrt();
+            ((axis_distance - major_radius).powi(2) + p.y * p.y).sqrt()
+        };
+
+        for surface_only in [false, true] {
+            let mut node = ParticleSystemBuilder::new(BaseBuilder::new())
+                .with_emitters(vec![TorusEmitterBuilder::new(
+                    BaseEmitterBuilder::new().with_spawn_rate(2000),
+                )
+                .with_major_radius(major_radius)
+                .with_minor_radius(minor_radius)
+                .with_surface_only(surface_only)
+                .build()])
+                .with_acceleration(Default::default())
+                .build_node();
+            let particle_system = node.cast_mut::<ParticleSystem>().unwrap();
+            particle_system.tick(1.0);
+
+            let particles = particle_system.particles();
+            assert!(!particles.is_empty());
+            for particle in particles {
+                let distance = tube_distance(particle.position);
+                if surface_only {
+                    assert!((distance - minor_radius).abs() <= 5.0e-3);
+                } else {
+                    assert!(distance <= minor_radius + 5.0e-3);
+                }
+            }
+
+            // The outer half of the tube is longer than the inner half, so it must receive more particles.
+            let outer = particles
+                .iter()
+                .filter(|particle| {
+                    let p = particle.position;
+                    (p.x * p.x + p.z * p.z).sqrt() > major_radius
+                })
+                .count();
+            assert!(outer > particles.len() - outer);
+        }
+    }
+}
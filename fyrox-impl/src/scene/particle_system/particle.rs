@@ -25,6 +25,15 @@ pub struct Particle {
     pub rotation: f32,
     /// Color of particle.
     pub color: Color,
+    /// Arbitrary value, that is passed to the shader as `particleCustom` vertex attribute. It
+    /// could be used for advanced effects, for example to pick a frame of a sprite sheet. Emitters
+    /// could fill it automatically, see [`super::emitter::base::ParticleCustomData`].
+    #[visit(optional)] // Backward compatibility
+    pub custom: f32,
+
+    /// Index of the first frame of the sprite sheet animation of the particle.
+    #[visit(optional)] // Backward compatibility
+    pub(super) start_frame: u32,
 
     pub(super) alive: bool,
     pub(super) emitter_index: u32,
@@ -49,6 +58,8 @@ impl Default for Particle {
             rotation: 0.0,
             emitter_index: 0,
             color: Color::WHITE,
+            custom: 0.0,
+            start_frame: 0,
             sqr_distance_to_camera: Cell::new(0.0),
         }
     }
@@ -102,4 +113,10 @@ impl Particle {
         self.color = color;
         self
     }
+
+    /// Sets new custom value in builder manner.
+    pub fn with_custom(mut self, custom: f32) -> Self {
+        self.custom = custom;
+        self
+    }
 }
@@ -0,0 +1,159 @@
+//! Turbulence is a procedural force, that perturbs particles using curl noise. See [`ParticleTurbulence`]
+//! docs for more info.
+
+use crate::core::{
+    algebra::Vector3,
+    reflect::prelude::*,
+    uuid::{uuid, Uuid},
+    visitor::prelude::*,
+    TypeUuidProvider,
+};
+
+/// Turbulence applies a procedural acceleration to every particle of a particle system, that is
+/// sampled from a curl noise field using the position of the particle and the age of the particle
+/// system. Curl noise is divergence-free, so particles swirl around instead of clumping together,
+/// which makes it a good fit for smoke, fire, dust, etc. See
+/// [`super::ParticleSystem::set_turbulence`] for more info.
+#[derive(Debug, Clone, PartialEq, Visit, Reflect)]
+pub struct ParticleTurbulence {
+    /// Spatial frequency of the noise. Higher values produce smaller swirls.
+    #[reflect(min_value = 0.0, step = 0.05)]
+    pub frequency: f32,
+    /// Strength (acceleration in units per second squared) of the turbulence. Zero disables the
+    /// turbulence.
+    pub strength: f32,
+}
+
+impl Default for ParticleTurbulence {
+    fn default() -> Self {
+        Self {
+            frequency: 1.0,
+            strength: 0.0,
+        }
+    }
+}
+
+impl TypeUuidProvider for ParticleTurbulence {
+    fn type_uuid() -> Uuid {
+        uuid!("5d9e2f17-8a43-4c6b-b1f0-73e4a5c8d926")
+    }
+}
+
+// Maps the integer lattice coordinates to a pseudo-random number in [-1; 1] range.
+fn lattice_value(x: i32, y: i32, z: i32, w: i32, seed: u32) -> f32 {
+    let mut hash = seed.wrapping_mul(0x9E37_79B9);
+    for coordinate in [x, y, z, w] {
+        hash ^= (coordinate as u32).wrapping_mul(0x85EB_CA6B);
+        hash = hash.rotate_left(13).wrapping_mul(0xC2B2_AE35);
+    }
+    hash ^= hash >> 16;
+    hash = hash.wrapping_mul(0x7FEB_352D);
+    hash ^= hash >> 15;
+    (hash as f32 / u32::MAX as f32) * 2.0 - 1.0
+}
+
+// Smooth step and its derivative.
+fn fade(t: f32) -> (f32, f32) {
+    (t * t * (3.0 - 2.0 * t), 6.0 * t * (1.0 - t))
+}
+
+// Returns the spatial gradient of four-dimensional value noise at the given point and time.
+fn noise_gradient(point: Vector3<f32>, time: f32, seed: u32) -> Vector3<f32> {
+    let coords = [point.x, point.y, point.z, time];
+    let cell = coords.map(|c| c.floor() as i32);
+    let fades = coords.map(|c| fade(c - c.floor()));
+
+    let mut gradient = Vector3::default();
+    for corner in 0..16 {
+        let mut offsets = [0; 4];
+        let mut weights = [0.0; 4];
+        let mut derivatives = [0.0; 4];
+        for (axis, &(s, ds)) in fades.iter().enumerate() {
+            if corner & (1 << axis) != 0 {
+                offsets[axis] = 1;
+                weights[axis] = s;
+                derivatives[axis] = ds;
+            } else {
+                weights[axis] = 1.0 - s;
+                derivatives[axis] = -ds;
+            }
+        }
+
+        let corner_value = lattice_value(
+            cell[0] + offsets[0],
+            cell[1] + offsets[1],
+            cell[2] + offsets[2],
+            cell[3] + offsets[3],
+            seed,
+        );
+        let [wx, wy, wz, wt] = weights;
+        gradient += Vector3::new(
+            derivatives[0] * wy * wz,
+            wx * derivatives[1] * wz,
+            wx * wy * derivatives[2],
+        )
+        .scale(corner_value * wt);
+    }
+
+    gradient
+}
+
+impl ParticleTurbulence {
+    /// Returns `true` if the turbulence affects particles, `false` - otherwise.
+    pub fn is_enabled(&self) -> bool {
+        self.strength != 0.0
+    }
+
+    /// Returns the acceleration, that the turbulence applies to a particle at the given point at
+    /// the given time (in seconds). The acceleration is the curl of a vector potential, that is
+    /// made of three independent noise fields.
+    pub fn acceleration_at(&self, point: Vector3<f32>, time: f32) -> Vector3<f32> {
+        if !self.is_enabled() {
+            return Vector3::default();
+        }
+
+        let point = point.scale(self.frequency.max(0.0));
+        let dx = noise_gradient(point, time, 0);
+        let dy = noise_gradient(point, time, 1);
+        let dz = noise_gradient(point, time, 2);
+        Vector3::new(dz.y - dy.z, dx.z - dz.x, dy.x - dx.y).scale(self.strength)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{core::algebra::Vector3, scene::particle_system::turbulence::ParticleTurbulence};
+
+    #[test]
+    fn test_turbulence() {
+        let disabled = ParticleTurbulence::default();
+        assert!(!disabled.is_enabled());
+        assert_eq!(
+            disabled.acceleration_at(Vector3::new(0.3, 1.7, -2.1), 0.5),
+            Vector3::default()
+        );
+
+        let turbulence = ParticleTurbulence {
+            frequency: 0.5,
+            strength: 2.0,
+        };
+        let point = Vector3::new(0.3, 1.7, -2.1);
+        let acceleration = turbulence.acceleration_at(point, 0.5);
+        assert_ne!(acceleration, Vector3::default());
+        assert_eq!(acceleration, turbulence.acceleration_at(point, 0.5));
+        assert_ne!(acceleration, turbulence.acceleration_at(point, 0.75));
+
+        // Curl noise is divergence-free.
+        let h = 1.0e-2;
+        let divergence = (0..3)
+            .map(|axis| {
+                let mut offset = Vector3::default();
+                offset[axis] = h;
+                (turbulence.acceleration_at(point + offset, 0.5)[axis]
+                    - turbulence.acceleration_at(point - offset, 0.5)[axis])
+                    / (2.0 * h)
+            })
+            .sum::<f32>();
+        assert!(divergence.abs() < 1.0e-2);
+    }
+}
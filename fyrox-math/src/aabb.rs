@@ -1,7 +1,7 @@
 use crate::Matrix4Ext;
 use nalgebra::{Matrix4, Vector3};
 
-#[derive(Copy, Clone, Debug)]
+#[derive(Copy, Clone, Debug, PartialEq)]
 pub struct AxisAlignedBoundingBox {
     pub min: Vector3<f32>,
     pub max: Vector3<f32>,
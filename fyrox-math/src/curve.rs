@@ -344,6 +344,94 @@ impl Curve {
 
         Rect::new(min_x, min_y, max_x - min_x, max_y - min_y)
     }
+
+    /// Converts every key of the curve to a cubic key with tangents, that make the curve smooth: the slope at each key
+    /// is the slope between its neighbours (like in Catmull-Rom splines), the first and the last key use the slope of
+    /// the adjacent segment. A curve with keys that lie on a straight line remains the same.
+    pub fn set_smooth_tangents(&mut self) {
+        let slopes = (0..self.keys.len())
+            .map(|i| {
+                let left = &self.keys[i.saturating_sub(1)];
+                let right = &self.keys[(i + 1).min(self.keys.len() - 1)];
+                let dx = right.location - left.location;
+                if dx > 0.0 {
+                    (right.value - left.value) / dx
+                } else {
+                    0.0
+                }
+            })
+            .collect::<Vec<_>>();
+
+        // Tangents are scaled by the difference of values of adjacent keys in `cubicf`, so this scaling must be
+        // compensated to get the desired slope in terms of locations.
+        fn tangent(slope: f32, left: &CurveKey, right: &CurveKey) -> f32 {
+            let scale = (right.value - left.value).abs();
+            if scale > 0.0 {
+                slope * (right.location - left.location) / scale
+            } else {
+                0.0
+            }
+        }
+
+        let count = self.keys.len();
+        let kinds = (0..count)
+            .map(|i| CurveKeyKind::Cubic {
+                left_tangent: if i > 0 {
+                    tangent(slopes[i], &self.keys[i - 1], &self.keys[i])
+                } else {
+                    0.0
+                },
+                right_tangent: if i + 1 < count {
+                    tangent(slopes[i], &self.keys[i], &self.keys[i + 1])
+                } else {
+                    0.0
+                },
+            })
+            .collect::<Vec<_>>();
+
+        for (key, kind) in self.keys.iter_mut().zip(kinds) {
+            key.kind = kind;
+        }
+    }
+
+    /// Mirrors the curve in time around the given location, so the keys located at `location + t` will be moved to
+    /// `location - t`. Tangents of cubic keys are swapped and negated, so the shape of the curve is mirrored exactly
+    /// when all keys have the same kind. Keep in mind, that constant keys hold the value of their left neighbour,
+    /// so the steps of a mirrored curve will happen at the other end of the spans.
+    pub fn mirror(&mut self, location: f32) {
+        for key in self.keys.iter_mut() {
+            key.location = 2.0 * location - key.location;
+            if let CurveKeyKind::Cubic {
+                left_tangent,
+                right_tangent,
+            } = key.kind
+            {
+                key.kind = CurveKeyKind::Cubic {
+                    left_tangent: -right_tangent,
+                    right_tangent: -left_tangent,
+                };
+            }
+        }
+        self.keys.reverse();
+    }
+
+    /// Multiplies the values of every key by the given factor. Tangents of cubic keys are scaled too, so the shape of
+    /// the curve is scaled exactly.
+    pub fn scale_values(&mut self, factor: f32) {
+        for key in self.keys.iter_mut() {
+            key.value *= factor;
+            if let CurveKeyKind::Cubic {
+                left_tangent,
+                right_tangent,
+            } = key.kind
+            {
+                key.kind = CurveKeyKind::Cubic {
+                    left_tangent: left_tangent * factor,
+                    right_tangent: right_tangent * factor,
+                };
+            }
+        }
+    }
 }
 
 #[cfg(test)]
@@ -522,4 +610,54 @@ mod test {
         assert_eq!(curve.name(), "");
         assert_eq!(curve.keys(), vec![key, key2, key4, key3,]);
     }
+
+    #[test]
+    fn test_curve_mirror() {
+        let mut curve = Curve::from(vec![
+            CurveKey::new(0.0, 0.0, CurveKeyKind::new_cubic(0.0, 0.3)),
+            CurveKey::new(1.0, 2.0, CurveKeyKind::new_cubic(-0.2, 0.5)),
+            CurveKey::new(3.0, 1.0, CurveKeyKind::new_cubic(0.4, 0.0)),
+        ]);
+        let original = curve.clone();
+        curve.mirror(1.5);
+
+        assert_eq!(curve.keys()[0].location, 0.0);
+        assert_eq!(curve.keys()[1].location, 2.0);
+        assert_eq!(curve.keys()[2].location, 3.0);
+        for location in [0.0, 0.25, 0.9, 1.5, 2.2, 3.0] {
+            assert!((curve.value_at(location) - original.value_at(3.0 - location)).abs() < 1.0e-5);
+        }
+
+        curve.mirror(1.5);
+        assert_eq!(curve, original);
+    }
+
+    #[test]
+    fn test_curve_smooth_tangents() {
+        // Keys on a straight line must remain a straight line.
+        let mut curve = Curve::from(vec![
+            CurveKey::new(0.0, 0.0, CurveKeyKind::Linear),
+            CurveKey::new(1.0, 1.0, CurveKeyKind::Linear),
+            CurveKey::new(3.0, 3.0, CurveKeyKind::Linear),
+        ]);
+        curve.set_smooth_tangents();
+        assert!(curve
+            .keys()
+            .iter()
+            .all(|k| matches!(k.kind, CurveKeyKind::Cubic { .. })));
+        for location in [0.25, 0.5, 1.5, 2.0, 2.75] {
+            assert!((curve.value_at(location) - location).abs() < 1.0e-5);
+        }
+
+        // A peak must be smooth: the curve has zero slope at the middle key.
+        let mut curve = Curve::from(vec![
+            CurveKey::new(0.0, 0.0, CurveKeyKind::Linear),
+            CurveKey::new(1.0, 1.0, CurveKeyKind::Linear),
+            CurveKey::new(2.0, 0.0, CurveKeyKind::Linear),
+        ]);
+        curve.set_smooth_tangents();
+        let eps = 1.0e-3;
+        assert!((curve.value_at(1.0 - eps) - curve.value_at(1.0 + eps)).abs() < 1.0e-5);
+        assert!(curve.value_at(1.0 - eps) < 1.0);
+    }
 }
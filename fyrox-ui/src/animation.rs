@@ -12,7 +12,9 @@ use crate::{
         visitor::prelude::*,
     },
     define_constructor, define_widget_deref,
-    generic_animation::value::{BoundValueCollection, TrackValue, ValueBinding},
+    generic_animation::value::{
+        BindingError, BoundValueCollection, TrackValue, ValueBinding, ValueCoercion,
+    },
     message::UiMessage,
     widget::{Widget, WidgetBuilder},
     BuildContext, Control, UiNode, UserInterface,
@@ -63,7 +65,10 @@ pub mod prelude {
     pub use crate::generic_animation::{
         container::{TrackDataContainer, TrackValueKind},
         signal::AnimationSignal,
-        value::{BoundValueCollection, TrackValue, ValueBinding, ValueType},
+        value::{
+            BindingError, BoundValueCollection, RotationBlendPath, TrackValue, ValueBinding,
+            ValueCoercion, ValueType,
+        },
         AnimationEvent,
     };
 }
@@ -111,10 +116,23 @@ pub trait BoundValueCollectionExt {
     /// Tries to set each value from the collection to the respective property (by binding) of the
     /// given widget.
     fn apply(&self, node_ref: &mut UiNode);
+
+    /// Same as [`Self::apply`], but allows you to specify what happens if a value of a property binding cannot be
+    /// applied as is. See
+    /// [`crate::generic_animation::value::BoundValue::apply_to_object_with_coercion`] for more info.
+    fn apply_with_coercion(&self, node_ref: &mut UiNode, coercion: ValueCoercion);
+
+    /// Checks whether every value from the collection can be applied to the given widget, without applying
+    /// anything. Returns a list of problems, that would otherwise be logged by [`Self::apply`].
+    fn validate(&self, node_ref: &UiNode) -> Vec<BindingError>;
 }
 
 impl BoundValueCollectionExt for BoundValueCollection {
     fn apply(&self, node_ref: &mut UiNode) {
+        self.apply_with_coercion(node_ref, ValueCoercion::Strict)
+    }
+
+    fn apply_with_coercion(&self, node_ref: &mut UiNode, coercion: ValueCoercion) {
         for bound_value in self.values.iter() {
             match bound_value.binding {
                 ValueBinding::Position => {
@@ -128,12 +146,43 @@ impl BoundValueCollectionExt for BoundValueCollection {
                 }
                 ValueBinding::Scale => Log::warn("Implement me!"),
                 ValueBinding::Rotation => Log::warn("Implement me!"),
+                // Properties are applied all at once below.
+                ValueBinding::Property { .. } => (),
+            }
+        }
+
+        self.apply_properties_to_object(node_ref, coercion);
+    }
+
+    fn validate(&self, node_ref: &UiNode) -> Vec<BindingError> {
+        let mut errors = Vec::new();
+        for bound_value in self.values.iter() {
+            let result = match bound_value.binding {
+                ValueBinding::Position => {
+                    if let TrackValue::Vector2(_) = bound_value.value {
+                        Ok(())
+                    } else {
+                        Err(BindingError::ValueMismatch {
+                            binding: bound_value.binding.clone(),
+                            expected: "Vector2",
+                        })
+                    }
+                }
+                ValueBinding::Scale | ValueBinding::Rotation => {
+                    Err(BindingError::UnsupportedBinding {
+                        binding: bound_value.binding.clone(),
+                    })
+                }
                 ValueBinding::Property {
                     name: ref property_name,
                     value_type,
-                } => bound_value.apply_to_object(node_ref, property_name, value_type),
+                } => bound_value.validate_for_object(node_ref, property_name, value_type),
+            };
+            if let Err(error) = result {
+                errors.push(error);
             }
         }
+        errors
     }
 }
 